@@ -12,11 +12,31 @@
  * See the Mulan PSL v2 for more details.
  */
 
-use std::{error::Error, path::PathBuf};
+use std::{env, error::Error, fs, path::PathBuf};
 
 use walkdir::WalkDir;
 
 const PROTO_DIR: &str = "proto";
+const API_CATALOG: &str = "src/utils/api_name.rs";
+
+/// Hashes every `Variant = value` pair of the api catalog with FNV-1a, so that
+/// comments and formatting do not change the hash.
+fn api_catalog_hash(source: &str) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    source
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.trim().strip_suffix(',')?.split_once(" = ")?;
+            value.parse::<u64>().ok()?;
+            Some(format!("{}={};", name, value))
+        })
+        .flat_map(String::into_bytes)
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
     let proto_files: Vec<PathBuf> = WalkDir::new(PROTO_DIR)
@@ -35,5 +55,15 @@ fn main() -> Result<(), Box<dyn Error>> {
     }
     println!("cargo:rerun-if-changed={}", PROTO_DIR);
 
+    let catalog_hash = api_catalog_hash(&fs::read_to_string(API_CATALOG)?);
+    fs::write(
+        PathBuf::from(env::var("OUT_DIR")?).join("api_catalog.rs"),
+        format!(
+            "pub const API_CATALOG_HASH: u64 = {:#018x};\n",
+            catalog_hash
+        ),
+    )?;
+    println!("cargo:rerun-if-changed={}", API_CATALOG);
+
     Ok(())
 }
@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+//! Lists xgpu shared memory segments and removes those whose creator is gone.
+
+use std::{env, process, time::SystemTime};
+
+use xgpu_common::ipc::transport::shmem::ShmemSegment;
+
+fn main() {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .with_file(false)
+        .with_writer(std::io::stderr)
+        .init();
+
+    let args: Vec<String> = env::args().collect();
+
+    let dry_run = match &args[1..] {
+        [] => false,
+        [flag] if flag == "-n" || flag == "--dry-run" => true,
+        _ => {
+            eprintln!(
+                "Usage: {} [-n | --dry-run]\n\n\
+                 Lists xgpu shared memory segments and removes the orphaned ones,\n\
+                 i.e. those whose creating process has exited.",
+                args[0]
+            );
+            process::exit(1);
+        }
+    };
+
+    let segments = match ShmemSegment::list() {
+        Ok(segments) => segments,
+        Err(e) => {
+            eprintln!("Failed to list shared memory segments: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let mut failed = false;
+    let mut orphaned = 0;
+    println!(
+        "{:<10} {:>8} {:>10} {:>12}  NAME",
+        "STATUS", "PID", "AGE", "SIZE"
+    );
+    for segment in &segments {
+        let status = match segment.creator().and_then(|creator| creator.is_alive()) {
+            Some(true) => "alive",
+            Some(false) => "orphaned",
+            None => "unknown",
+        };
+        let pid = segment
+            .creator()
+            .map_or_else(|| "-".to_owned(), |creator| creator.pid.to_string());
+        let age = SystemTime::now()
+            .duration_since(segment.created_at())
+            .map_or_else(|_| "-".to_owned(), |age| format!("{}s", age.as_secs()));
+
+        println!(
+            "{:<10} {:>8} {:>10} {:>12}  {}",
+            status,
+            pid,
+            age,
+            segment.size(),
+            segment.name()
+        );
+
+        if !segment.is_orphaned() {
+            continue;
+        }
+        orphaned += 1;
+
+        if !dry_run && let Err(e) = segment.remove() {
+            eprintln!("Failed to remove '{}': {}", segment.name(), e);
+            failed = true;
+        }
+    }
+
+    match dry_run {
+        true => println!("{} of {} segments orphaned", orphaned, segments.len()),
+        false => println!(
+            "{} of {} segments orphaned and removed",
+            orphaned,
+            segments.len()
+        ),
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
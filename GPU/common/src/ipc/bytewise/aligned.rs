@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    alloc::{self, Layout},
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+};
+
+/// An owned, zero-initialized byte buffer whose start address is aligned.
+///
+/// Bytewise encoding aligns data by memory address rather than by offset, so a
+/// byte stream only decodes correctly if the receiver places it at an address
+/// with the same alignment the sender used. Stream based transports use this
+/// buffer to give every frame a stable, well-aligned base address.
+pub struct AlignedBuffer {
+    ptr: NonNull<u8>,
+    len: usize,
+    align: usize,
+}
+
+// SAFETY: `AlignedBuffer` exclusively owns its allocation, just like `Box<[u8]>`.
+unsafe impl Send for AlignedBuffer {}
+
+// SAFETY: Shared access only hands out `&[u8]`, just like `Box<[u8]>`.
+unsafe impl Sync for AlignedBuffer {}
+
+impl AlignedBuffer {
+    /// Allocates a new zero-filled buffer.
+    ///
+    /// # Parameters
+    /// - `len`: The buffer length in bytes.
+    /// - `align`: The alignment of the buffer start address, must be a power of two.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two or the layout overflows.
+    pub fn new(len: usize, align: usize) -> Self {
+        let layout = Self::layout(len, align);
+
+        // SAFETY: The layout always has a non-zero size.
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+
+        Self { ptr, len, align }
+    }
+
+    /// Returns the alignment of the buffer start address.
+    #[inline]
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Resizes the buffer in place, preserving its alignment.
+    ///
+    /// Existing content is kept up to the new length, any newly added bytes are
+    /// zero-filled. The buffer may be moved to a different address.
+    pub fn resize(&mut self, new_len: usize) {
+        let old_layout = Self::layout(self.len, self.align);
+        let new_layout = Self::layout(new_len, self.align);
+
+        // SAFETY: `ptr` was allocated with `old_layout`, and the new size is non-zero
+        // and does not overflow `isize` once rounded up to `align` (checked above).
+        let ptr = unsafe { alloc::realloc(self.ptr.as_ptr(), old_layout, new_layout.size()) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(new_layout));
+
+        if new_len > self.len {
+            // SAFETY: The range is within the new allocation.
+            unsafe { ptr::write_bytes(ptr.as_ptr().add(self.len), 0, new_len - self.len) };
+        }
+
+        self.ptr = ptr;
+        self.len = new_len;
+    }
+
+    #[inline]
+    fn layout(len: usize, align: usize) -> Layout {
+        Layout::from_size_align(len.max(1), align).expect("Invalid buffer layout")
+    }
+}
+
+impl Deref for AlignedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: `ptr` is valid for `len` initialized bytes.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: `ptr` is valid for `len` initialized bytes and exclusively owned.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl AsRef<[u8]> for AlignedBuffer {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl AsMut<[u8]> for AlignedBuffer {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl Clone for AlignedBuffer {
+    fn clone(&self) -> Self {
+        let mut buffer = Self::new(self.len, self.align);
+        buffer.copy_from_slice(self);
+        buffer
+    }
+}
+
+impl Debug for AlignedBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlignedBuffer")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .field("align", &self.align)
+            .finish()
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` was allocated with this exact layout.
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), Self::layout(self.len, self.align)) };
+    }
+}
@@ -30,6 +30,9 @@ pub enum BytewiseError {
 
     #[error("Illegal overlapping copy operation")]
     IllegalOverlappingCopy,
+
+    #[error("Invalid encoding of {0}")]
+    InvalidEncoding(&'static str),
 }
 
 /// Provides a way to read raw, aligned data from a byte buffer.
@@ -256,5 +259,8 @@ pub trait BytewiseReadOwned: Sized {
 mod buffer;
 pub use buffer::*;
 
+mod aligned;
+pub use aligned::*;
+
 #[cfg(test)]
 mod tests;
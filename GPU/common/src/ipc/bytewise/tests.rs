@@ -159,3 +159,26 @@ fn btyewise_read_write() {
 
     assert_eq!(read_bytes, write_bytes);
 }
+
+#[test]
+fn aligned_buffer_resize() {
+    const ALIGN: usize = 4096;
+
+    let mut buf = AlignedBuffer::new(64, ALIGN);
+    assert_eq!(buf.as_ptr() as usize % ALIGN, 0);
+    assert!(buf.iter().all(|&b| b == 0));
+
+    buf[..4].copy_from_slice(&[1, 2, 3, 4]);
+    buf.resize(3 * ALIGN);
+    assert_eq!(buf.len(), 3 * ALIGN);
+    assert_eq!(buf.as_ptr() as usize % ALIGN, 0);
+    assert_eq!(&buf[..4], &[1, 2, 3, 4]);
+    assert!(buf[4..].iter().all(|&b| b == 0));
+
+    buf.resize(2);
+    assert_eq!(&buf[..], &[1, 2]);
+
+    let cloned = buf.clone();
+    assert_eq!(cloned.as_ptr() as usize % ALIGN, 0);
+    assert_eq!(&cloned[..], &buf[..]);
+}
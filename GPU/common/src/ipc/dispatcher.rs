@@ -0,0 +1,412 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+//! Pipelined invocation over a single connection.
+//!
+//! A `Dispatcher` lets several threads share one `Client`: each call sends its
+//! request right away and gets a `Ticket` back, while responses are matched to
+//! tickets by request id in whatever order the server sends them. At most
+//! `window` requests are in flight at any time.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    sync::{
+        Arc, Condvar, Mutex, MutexGuard,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use tracing::debug;
+
+use crate::sys::page;
+
+use super::{
+    bytewise::{AlignedBuffer, BytewiseBuffer, BytewiseError, BytewiseReadOwned, BytewiseWrite},
+    error::IpcError,
+    framer::Framer,
+    message::{Request, RequestBatch, Response, ResponseBatch},
+    peer::Client,
+    transport::{BulkRegion, Transport},
+};
+
+/// Time slice a thread holds the connection for before giving others a turn.
+const POLL_SLICE: Duration = Duration::from_micros(200);
+
+/// A response copied out of the transport buffer, so that it outlives later reads.
+///
+/// Responses of one batch share a single copy of the frame.
+#[derive(Debug, Clone)]
+pub struct ResponseFrame {
+    buf: Arc<AlignedBuffer>,
+    offset: usize,
+    request_id: u64,
+}
+
+impl ResponseFrame {
+    /// Copies a frame payload, splitting a `ResponseBatch` into its responses.
+    pub(super) fn split_from(payload: &[u8]) -> Result<Vec<Self>, BytewiseError> {
+        // Bytewise padding depends on absolute addresses, so the copy keeps the
+        // payload at the same offset within a page.
+        let align = page::page_size();
+        let offset = payload.as_ptr() as usize % align;
+
+        let mut buf = AlignedBuffer::new(offset + payload.len(), align);
+        buf[offset..].copy_from_slice(payload);
+        let buf = Arc::new(buf);
+
+        let responses = ResponseBatch::read_offsets(&mut BytewiseBuffer::new(&buf[offset..]))?;
+        let frames = responses
+            .into_iter()
+            .map(|(response_offset, response)| Self {
+                buf: buf.clone(),
+                offset: offset + response_offset,
+                request_id: response.request_id(),
+            })
+            .collect();
+
+        Ok(frames)
+    }
+
+    #[inline]
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    /// Decodes the response, its arguments point into this frame.
+    pub fn response(&self) -> Result<Response<'_>, BytewiseError> {
+        Response::read_from(&mut BytewiseBuffer::new(&self.buf[self.offset..]))
+    }
+}
+
+/// A request in flight, redeemed for its response by `Dispatcher::wait`.
+#[must_use = "a request in flight holds a slot of the window until waited for"]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Ticket {
+    request_id: u64,
+}
+
+impl Ticket {
+    #[inline]
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+}
+
+#[derive(Debug, Default)]
+struct DispatchState {
+    /// Requests sent whose response has not arrived yet.
+    in_flight: HashSet<u64>,
+    /// Responses arrived but not collected yet.
+    arrived: HashMap<u64, ResponseFrame>,
+    /// Whether a thread is reading responses on behalf of everyone.
+    reading: bool,
+}
+
+#[derive(Debug)]
+pub struct Dispatcher<F: Framer, T: Transport> {
+    client: Mutex<Client<F, T>>,
+    /// Threads about to send, the reader steps aside for them.
+    sending: AtomicUsize,
+    state: Mutex<DispatchState>,
+    progress: Condvar,
+    window: usize,
+    timeout: Option<Duration>,
+    arena: Option<Arc<dyn BulkRegion>>,
+}
+
+impl<F: Framer, T: Transport> Dispatcher<F, T> {
+    /// Shares `client` between threads, allowing up to `window` requests in flight.
+    ///
+    /// The client's timeout becomes the default timeout of `invoke_async` and `wait`.
+    pub fn new(mut client: Client<F, T>, window: usize) -> Self {
+        let timeout = client.timeout();
+        client.set_timeout(Some(POLL_SLICE));
+        let arena = client.arena();
+
+        Self {
+            client: Mutex::new(client),
+            sending: AtomicUsize::new(0),
+            state: Mutex::new(DispatchState::default()),
+            progress: Condvar::new(),
+            window: window.max(1),
+            timeout,
+            arena,
+        }
+    }
+
+    /// Returns the bulk data arena of the connection, usable without holding it.
+    #[inline]
+    pub fn arena(&self) -> Option<Arc<dyn BulkRegion>> {
+        self.arena.clone()
+    }
+
+    #[inline]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Returns the number of requests sent whose response has not arrived yet.
+    #[inline]
+    pub fn in_flight(&self) -> usize {
+        self.lock_state().in_flight.len()
+    }
+
+    /// Invokes a request and waits for its response.
+    pub fn invoke(&self, request: &Request) -> Result<ResponseFrame, IpcError<F, T>> {
+        let ticket = self.invoke_async(request)?;
+        self.wait(ticket)
+    }
+
+    /// Sends a request without waiting for its response.
+    ///
+    /// Blocks while the window is full. Request ids must be unique among the
+    /// requests in flight.
+    pub fn invoke_async(&self, request: &Request) -> Result<Ticket, IpcError<F, T>> {
+        debug_assert!(!request.is_no_reply(), "No-reply requests are posted");
+
+        let request_id = request.request_id();
+        self.send_tracked(request, &[request_id])?;
+
+        Ok(Ticket { request_id })
+    }
+
+    /// Sends a batch of requests in a single frame, returning a ticket for each
+    /// request expecting a response, in batch order.
+    ///
+    /// Blocks until the window has room for all of them, a batch larger than the
+    /// window waits for every other request to complete.
+    pub fn invoke_batch_async(&self, batch: &RequestBatch) -> Result<Vec<Ticket>, IpcError<F, T>> {
+        let request_ids = batch
+            .requests()
+            .iter()
+            .filter(|request| !request.is_no_reply())
+            .map(Request::request_id)
+            .collect::<Vec<_>>();
+
+        self.send_tracked(batch, &request_ids)?;
+
+        Ok(request_ids
+            .into_iter()
+            .map(|request_id| Ticket { request_id })
+            .collect())
+    }
+
+    /// Sends a no-reply request, which takes no slot of the window.
+    pub fn post(&self, request: &Request) -> Result<(), IpcError<F, T>> {
+        debug_assert!(request.is_no_reply(), "Only no-reply requests are posted");
+
+        self.send_until(request, Self::deadline(self.timeout))
+    }
+
+    /// Waits for the response of `ticket`, using the client's timeout.
+    pub fn wait(&self, ticket: Ticket) -> Result<ResponseFrame, IpcError<F, T>> {
+        self.wait_until(ticket, Self::deadline(self.timeout))
+    }
+
+    /// Waits for the response of `ticket`, failing with `IpcError::Timeout` after `timeout`.
+    ///
+    /// On failure the request is abandoned and its response discarded on arrival.
+    pub fn wait_timeout(
+        &self,
+        ticket: Ticket,
+        timeout: Duration,
+    ) -> Result<ResponseFrame, IpcError<F, T>> {
+        self.wait_until(ticket, Self::deadline(Some(timeout)))
+    }
+
+    fn wait_until(
+        &self,
+        ticket: Ticket,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<ResponseFrame, IpcError<F, T>> {
+        let mut state = self.lock_state();
+
+        loop {
+            if let Some(frame) = state.arrived.remove(&ticket.request_id) {
+                return Ok(frame);
+            }
+            debug_assert!(
+                state.in_flight.contains(&ticket.request_id),
+                "Ticket {} is not in flight",
+                ticket.request_id
+            );
+
+            state = match self.progress_until(state, deadline) {
+                Ok(state) => state,
+                Err(e) => {
+                    self.abandon(ticket.request_id);
+                    return Err(e);
+                }
+            };
+        }
+    }
+
+    fn send_tracked<B: BytewiseWrite>(
+        &self,
+        message: &B,
+        request_ids: &[u64],
+    ) -> Result<(), IpcError<F, T>> {
+        let deadline = Self::deadline(self.timeout);
+
+        let mut state = self.lock_state();
+        while !state.in_flight.is_empty() && state.in_flight.len() + request_ids.len() > self.window
+        {
+            state = self.progress_until(state, deadline)?;
+        }
+        state.in_flight.extend(request_ids);
+        drop(state);
+
+        if let Err(e) = self.send_until(message, deadline) {
+            for &request_id in request_ids {
+                self.abandon(request_id);
+            }
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    fn send_until<B: BytewiseWrite>(
+        &self,
+        message: &B,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<(), IpcError<F, T>> {
+        loop {
+            let result = self.with_client(|client| match client.send_message(message) {
+                Ok(()) => Ok(client.is_flushed()),
+                Err(e) => Err(e),
+            });
+
+            match result {
+                Ok(true) => return Ok(()),
+                // Sent in part, the rest goes out between reads
+                Ok(false) => return self.flush_until(deadline),
+                // The connection is busy or full, read responses so that the server
+                // is able to make progress.
+                Err(IpcError::Timeout(_)) => {
+                    let state = self.lock_state();
+                    drop(self.progress_until(state, deadline)?);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Sends the queued fragments of a message, letting responses be read in
+    /// between: the server may not take more of the message before sending some.
+    fn flush_until(&self, deadline: Option<(Instant, Duration)>) -> Result<(), IpcError<F, T>> {
+        loop {
+            match self.with_client(|client| client.flush()) {
+                Err(IpcError::Timeout(_)) => {
+                    let state = self.lock_state();
+                    drop(self.progress_until(state, deadline)?);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Runs `f` on the client, ahead of the thread reading responses.
+    fn with_client<R>(&self, f: impl FnOnce(&mut Client<F, T>) -> R) -> R {
+        self.sending.fetch_add(1, Ordering::Relaxed);
+        let result = f(&mut self.lock_client());
+        self.sending.fetch_sub(1, Ordering::Relaxed);
+        result
+    }
+
+    /// Makes a step towards new responses: either reads once for everyone, or
+    /// waits for the thread currently reading.
+    fn progress_until<'a>(
+        &'a self,
+        mut state: MutexGuard<'a, DispatchState>,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<MutexGuard<'a, DispatchState>, IpcError<F, T>> {
+        let remaining = match deadline {
+            Some((instant, timeout)) => {
+                let remaining = instant.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(IpcError::Timeout(timeout));
+                }
+                Some(remaining)
+            }
+            None => None,
+        };
+
+        if state.reading {
+            let state = match remaining {
+                Some(remaining) => {
+                    self.progress
+                        .wait_timeout(state, remaining)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0
+                }
+                None => self.progress.wait(state).unwrap_or_else(|e| e.into_inner()),
+            };
+            return Ok(state);
+        }
+
+        state.reading = true;
+        drop(state);
+
+        while self.sending.load(Ordering::Relaxed) > 0 {
+            thread::yield_now();
+        }
+        let result = self.lock_client().receive_response_frames();
+
+        let mut state = self.lock_state();
+        state.reading = false;
+        self.progress.notify_all();
+
+        match result {
+            Ok(Some(frames)) => {
+                for frame in frames {
+                    let request_id = frame.request_id();
+                    if state.in_flight.remove(&request_id) {
+                        state.arrived.insert(request_id, frame);
+                    } else {
+                        debug!("Discarding response of abandoned request {}", request_id);
+                    }
+                }
+                Ok(state)
+            }
+            Ok(None) | Err(IpcError::Timeout(_)) => Ok(state),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn abandon(&self, request_id: u64) {
+        let mut state = self.lock_state();
+        state.in_flight.remove(&request_id);
+        state.arrived.remove(&request_id);
+        self.progress.notify_all();
+    }
+
+    #[inline]
+    fn lock_client(&self) -> MutexGuard<'_, Client<F, T>> {
+        self.client.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[inline]
+    fn lock_state(&self) -> MutexGuard<'_, DispatchState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[inline]
+    fn deadline(timeout: Option<Duration>) -> Option<(Instant, Duration)> {
+        timeout.map(|timeout| (Instant::now() + timeout, timeout))
+    }
+}
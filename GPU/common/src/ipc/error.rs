@@ -12,11 +12,15 @@
  * See the Mulan PSL v2 for more details.
  */
 
+use std::time::Duration;
+
 use thiserror::Error;
 
 use crate::ipc::transport::Transport;
 
-use super::{bytewise::BytewiseError, framer::Framer};
+use super::{
+    bytewise::BytewiseError, fragment::FragmentError, framer::Framer, message::HandshakeError,
+};
 
 #[derive(Debug, Error)]
 pub enum IpcError<F: Framer, T: Transport> {
@@ -28,4 +32,13 @@ pub enum IpcError<F: Framer, T: Transport> {
 
     #[error("Bytewise Error: {0}")]
     BytewiseError(#[from] BytewiseError),
+
+    #[error("Timeout Error: no progress within {0:?}")]
+    Timeout(Duration),
+
+    #[error("Handshake Error: {0}")]
+    HandshakeError(#[from] HandshakeError),
+
+    #[error("Fragment Error: {0}")]
+    FragmentError(#[from] FragmentError),
 }
@@ -0,0 +1,310 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+//! Fragmentation of messages larger than a single frame.
+//!
+//! A message that does not fit in the transport buffer is encoded into a staging
+//! buffer first, then streamed as consecutive fragment frames. The receiver
+//! appends fragments to a reassembly buffer and decodes the message once the last
+//! one arrives. Both buffers start page-aligned, so bytewise padding comes out the
+//! same on either side.
+
+use thiserror::Error;
+
+use crate::sys::page;
+
+use super::{
+    bytewise::{AlignedBuffer, BytewiseBuffer, BytewiseError, BytewiseWrite, BytewiseWriter},
+    framer::Fragment,
+};
+
+/// Room a fragment waits for, unless the transport never offers as much.
+pub(super) const MIN_FRAGMENT_LEN: usize = 4096;
+
+/// Default upper bound on the size of a reassembled message.
+pub const DEFAULT_MAX_MESSAGE_LEN: usize = 1024 * 1024 * 1024;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FragmentError {
+    #[error("fragment out of order (expected: {expected}, actual: {actual})")]
+    OutOfOrder { expected: u16, actual: u16 },
+
+    #[error("reassembled message exceeds limit (limit: {limit}, actual: {actual})")]
+    MessageTooLarge { limit: usize, actual: usize },
+}
+
+/// Encodes a message into a page-aligned buffer of exactly its encoded size.
+///
+/// The buffer starts at `hint` bytes and doubles until the message fits.
+pub(super) fn stage<B: BytewiseWrite>(
+    message: &B,
+    hint: usize,
+) -> Result<AlignedBuffer, BytewiseError> {
+    let mut buf = AlignedBuffer::new(hint.max(page::page_size()), page::page_size());
+
+    loop {
+        let mut writer = BytewiseBuffer::new(buf.as_mut());
+        match message.write_to(&mut writer) {
+            Ok(()) => {
+                let len = writer.written_bytes();
+                buf.resize(len);
+                return Ok(buf);
+            }
+            Err(BytewiseError::InsufficientBuffer { .. }) => {
+                let len = buf.len().saturating_mul(2);
+                buf = AlignedBuffer::new(len, page::page_size());
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// A staged message on its way out as fragments.
+#[derive(Debug)]
+pub(super) struct Fragmentation {
+    buf: AlignedBuffer,
+    sent: usize,
+    seq: u16,
+}
+
+impl Fragmentation {
+    #[inline]
+    pub(super) fn new(staged: AlignedBuffer) -> Self {
+        Self {
+            buf: staged,
+            sent: 0,
+            seq: 0,
+        }
+    }
+
+    /// Returns `true` once some fragment is out, from then on the peer expects the rest.
+    #[inline]
+    pub(super) fn is_started(&self) -> bool {
+        self.sent > 0
+    }
+
+    #[inline]
+    pub(super) fn is_done(&self) -> bool {
+        self.sent == self.buf.len()
+    }
+
+    /// Returns the next fragment of at most `len` bytes, with its position.
+    pub(super) fn next(&self, len: usize) -> (Fragment, &[u8]) {
+        let end = self.sent + len.min(self.buf.len() - self.sent);
+        let fragment = Fragment {
+            seq: self.seq,
+            last: end == self.buf.len(),
+        };
+
+        (fragment, &self.buf[self.sent..end])
+    }
+
+    /// Marks the fragment returned by `next` as sent.
+    #[inline]
+    pub(super) fn advance(&mut self, len: usize) {
+        self.sent += len;
+        self.seq = self.seq.wrapping_add(1);
+    }
+}
+
+/// Collects the fragments of one message at a time.
+///
+/// Like a message decoded in place from the transport buffer, the reassembled
+/// message stays valid until the next read, that is until `release`.
+#[derive(Debug)]
+pub(super) struct Reassembly {
+    buf: Option<AlignedBuffer>,
+    len: usize,
+    max_len: usize,
+    next_seq: u16,
+    complete: bool,
+}
+
+impl Default for Reassembly {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_MESSAGE_LEN)
+    }
+}
+
+impl Reassembly {
+    /// Creates a reassembly accepting messages of up to `max_len` bytes.
+    #[inline]
+    pub(super) fn new(max_len: usize) -> Self {
+        Self {
+            buf: None,
+            len: 0,
+            max_len,
+            next_seq: 0,
+            complete: false,
+        }
+    }
+
+    #[inline]
+    pub(super) fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Discards the message being reassembled, if any.
+    #[inline]
+    fn reset(&mut self) {
+        *self = Self::new(self.max_len);
+    }
+
+    /// Frees the last reassembled message, if any.
+    #[inline]
+    pub(super) fn release(&mut self) {
+        if self.complete {
+            self.reset();
+        }
+    }
+
+    /// Appends a fragment, returning the whole message once `fragment` is the last one.
+    ///
+    /// A fragment out of sequence, or growing the message beyond the limit, discards
+    /// the partial message.
+    pub(super) fn push(
+        &mut self,
+        fragment: Fragment,
+        payload: &[u8],
+    ) -> Result<Option<&[u8]>, FragmentError> {
+        self.release();
+
+        if fragment.seq != self.next_seq {
+            let expected = self.next_seq;
+            self.reset();
+
+            return Err(FragmentError::OutOfOrder {
+                expected,
+                actual: fragment.seq,
+            });
+        }
+
+        let len = self.len.saturating_add(payload.len());
+        if len > self.max_len {
+            let limit = self.max_len;
+            self.reset();
+
+            return Err(FragmentError::MessageTooLarge { limit, actual: len });
+        }
+        let buf = self
+            .buf
+            .get_or_insert_with(|| AlignedBuffer::new(len, page::page_size()));
+        if buf.len() < len {
+            buf.resize(len.max(buf.len().saturating_mul(2)));
+        }
+        buf[self.len..len].copy_from_slice(payload);
+
+        self.len = len;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        self.complete = fragment.last;
+
+        Ok(fragment.last.then(|| &buf[..len]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::{
+        bytewise::BytewiseReadOwned,
+        message::{Argument, ArgumentFlag, Request},
+    };
+
+    #[test]
+    fn test_stage_and_reassemble() {
+        let data: Vec<u8> = (0..100_000).map(|i| i as u8).collect();
+        let request = Request::with_arg(0x1, Argument::from_slice(&data, ArgumentFlag::ARG_IN));
+
+        let staged = stage(&request, 16).unwrap();
+
+        let mut reassembly = Reassembly::default();
+        let mut chunks = staged.chunks(4000).peekable();
+        let mut seq = 0;
+        let message = loop {
+            let chunk = chunks.next().unwrap();
+            let last = chunks.peek().is_none();
+            if let Some(message) = reassembly.push(Fragment { seq, last }, chunk).unwrap() {
+                assert!(last);
+                break message;
+            }
+            seq += 1;
+        };
+        assert_eq!(message, &staged[..]);
+
+        let decoded = Request::read_from(&mut BytewiseBuffer::new(message)).unwrap();
+        assert_eq!(decoded.args()[0].downcast_slice::<u8>(), Ok(&data[..]));
+    }
+
+    #[test]
+    fn test_reassemble_out_of_order() {
+        let mut reassembly = Reassembly::default();
+
+        let first = Fragment {
+            seq: 0,
+            last: false,
+        };
+        assert!(reassembly.push(first, &[1, 2, 3]).unwrap().is_none());
+
+        let err = reassembly
+            .push(Fragment { seq: 2, last: true }, &[4])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            FragmentError::OutOfOrder {
+                expected: 1,
+                actual: 2
+            }
+        );
+
+        // The partial message is gone, a new one starts over.
+        let message = reassembly
+            .push(Fragment { seq: 0, last: true }, &[5, 6])
+            .unwrap()
+            .unwrap();
+        assert_eq!(message, &[5, 6]);
+    }
+
+    #[test]
+    fn test_reassemble_too_large() {
+        let mut reassembly = Reassembly::new(8);
+
+        let first = Fragment {
+            seq: 0,
+            last: false,
+        };
+        assert!(reassembly.push(first, &[0; 6]).unwrap().is_none());
+
+        let second = Fragment {
+            seq: 1,
+            last: false,
+        };
+        let err = reassembly.push(second, &[0; 6]).unwrap_err();
+        assert_eq!(
+            err,
+            FragmentError::MessageTooLarge {
+                limit: 8,
+                actual: 12
+            }
+        );
+
+        // The partial message is gone, the limit still applies to the next one.
+        assert!(reassembly.push(second, &[0; 1]).is_err());
+        let message = reassembly
+            .push(Fragment { seq: 0, last: true }, &[7; 8])
+            .unwrap()
+            .unwrap();
+        assert_eq!(message, &[7; 8]);
+        assert_eq!(reassembly.max_len(), 8);
+    }
+}
@@ -0,0 +1,371 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+//! A framer that compresses large payloads of the frames of another framer.
+//!
+//! Each payload starts with a small header telling whether the rest is LZ4
+//! compressed and how long it is once decompressed. Payloads are only compressed
+//! once both sides negotiated `HandshakeFeatures::COMPRESSION`, and only if that
+//! saves space, while compressed frames are always understood. The wrapped framer
+//! still checks the frame as sent, compressed or not.
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut, Range},
+    sync::{Mutex, MutexGuard, TryLockError},
+};
+
+use lz4_flex::block::{self as lz4, DecompressError};
+use thiserror::Error;
+use tracing::debug;
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, LittleEndian, U32};
+
+use crate::{
+    ipc::{bytewise::AlignedBuffer, message::HandshakeFeatures},
+    sys::page,
+};
+
+use super::{Fragment, Frame, FrameBuf, Framer};
+
+/// Payloads smaller than this are sent as they are.
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 4 * 1024;
+pub const DEFAULT_MAX_DECOMPRESSED_LEN: usize = 16 * 1024 * 1024;
+
+/// The payload is LZ4 compressed.
+const FLAG_COMPRESSED: u32 = 0b0001;
+
+#[derive(Debug, Error)]
+pub enum CompressingFramerError<E> {
+    #[error(transparent)]
+    FramerError(E),
+
+    #[error("compression header is truncated (length: {length})")]
+    TruncatedHeader { length: usize },
+
+    #[error("decompressed length exceeds limit (limit: {limit}, actual: {actual})")]
+    DecompressedTooLarge { limit: usize, actual: usize },
+
+    #[error("decompressed length mismatch (expected: {expected}, actual: {actual})")]
+    LengthMismatch { expected: usize, actual: usize },
+
+    #[error("decompression failed: {0}")]
+    DecompressError(#[from] DecompressError),
+
+    #[error("a decompressed frame of this framer is still alive")]
+    FrameInUse,
+}
+
+#[repr(C)]
+#[derive(Debug, FromBytes, IntoBytes, KnownLayout, Immutable)]
+struct CompressionHeader {
+    flags: U32<LittleEndian>,
+    raw_len: U32<LittleEndian>,
+}
+
+const HEADER_LEN: usize = size_of::<CompressionHeader>();
+
+type Scratch = Option<AlignedBuffer>;
+
+enum Payload<'a> {
+    /// The payload follows the compression header in the wrapped frame.
+    Plain,
+    /// The payload was decompressed into the scratch buffer of the framer, which
+    /// stays locked while the frame is alive.
+    Decompressed(MutexGuard<'a, Scratch>, Range<usize>),
+}
+
+struct CompressedFrame<'a, Fr> {
+    frame: Fr,
+    payload: Payload<'a>,
+}
+
+impl<Fr: Frame> Deref for CompressedFrame<'_, Fr> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match &self.payload {
+            Payload::Plain => &self.frame[HEADER_LEN..],
+            Payload::Decompressed(scratch, range) => {
+                let buf = scratch.as_ref().expect("Scratch buffer holds the payload");
+                &buf[range.clone()]
+            }
+        }
+    }
+}
+
+impl<Fr: Frame> Frame for CompressedFrame<'_, Fr> {
+    fn frame_len(&self) -> usize {
+        self.frame.frame_len()
+    }
+
+    fn fragment(&self) -> Option<Fragment> {
+        self.frame.fragment()
+    }
+}
+
+struct CompressedFrameBuffer<B> {
+    frame_buf: B,
+    threshold: Option<usize>,
+}
+
+impl<B: FrameBuf> FrameBuf for CompressedFrameBuffer<B> {
+    type Error = CompressingFramerError<B::Error>;
+
+    fn set_fragment(&mut self, fragment: Fragment) {
+        self.frame_buf.set_fragment(fragment);
+    }
+
+    fn finalize(mut self, payload_len: usize) -> Result<usize, Self::Error> {
+        if self.frame_buf.len() < HEADER_LEN + payload_len {
+            // Too small for the payload, let the wrapped framer report it
+            return self
+                .frame_buf
+                .finalize(HEADER_LEN + payload_len)
+                .map_err(CompressingFramerError::FramerError);
+        }
+
+        let (header_buf, payload) = self.frame_buf.split_at_mut(HEADER_LEN);
+        let payload = &mut payload[..payload_len];
+
+        let mut flags = 0;
+        let mut sent_len = payload_len;
+        if let Some(threshold) = self.threshold
+            && payload_len >= threshold
+        {
+            let mut compressed = vec![0u8; lz4::get_maximum_output_size(payload_len)];
+            if let Ok(len) = lz4::compress_into(payload, &mut compressed)
+                && len < payload_len
+            {
+                payload[..len].copy_from_slice(&compressed[..len]);
+                flags = FLAG_COMPRESSED;
+                sent_len = len;
+
+                debug!(
+                    "[Frame] Compressed payload {} bytes into {} bytes",
+                    payload_len, len
+                );
+            }
+        }
+
+        let header = CompressionHeader {
+            flags: U32::new(flags),
+            raw_len: U32::new(payload_len as u32),
+        };
+        header
+            .write_to_prefix(header_buf)
+            .expect("Header buffer is correctly sized");
+
+        self.frame_buf
+            .finalize(HEADER_LEN + sent_len)
+            .map_err(CompressingFramerError::FramerError)
+    }
+}
+
+impl<B: FrameBuf> Deref for CompressedFrameBuffer<B> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        let buf = &self.frame_buf[..];
+        &buf[HEADER_LEN.min(buf.len())..]
+    }
+}
+
+impl<B: FrameBuf> DerefMut for CompressedFrameBuffer<B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let buf = &mut self.frame_buf[..];
+        let start = HEADER_LEN.min(buf.len());
+        &mut buf[start..]
+    }
+}
+
+/// Wraps another framer, compressing payloads of at least `threshold` bytes.
+///
+/// Every payload carries the compression header, so both sides must use this
+/// framer, whether or not they offer compression.
+///
+/// Decompressed payloads are kept in a buffer of the framer until the next frame
+/// is decoded, like payloads decoded in place from a transport buffer. A frame
+/// holding one locks the buffer, decoding another compressed frame meanwhile fails
+/// with `FrameInUse`. Clones get a buffer of their own, so each peer must use its
+/// own clone.
+pub struct CompressingFramer<F> {
+    inner: F,
+    threshold: usize,
+    max_len: usize,
+    offer: bool,
+    enabled: bool,
+    scratch: Mutex<Scratch>,
+}
+
+impl<F: Framer> CompressingFramer<F> {
+    #[inline]
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner,
+            threshold: DEFAULT_COMPRESSION_THRESHOLD,
+            max_len: DEFAULT_MAX_DECOMPRESSED_LEN,
+            offer: true,
+            enabled: false,
+            scratch: Mutex::new(None),
+        }
+    }
+
+    /// Sets the payload size from which compression is attempted.
+    #[inline]
+    pub fn threshold(mut self, value: usize) -> Self {
+        self.threshold = value;
+        self
+    }
+
+    /// Sets the largest payload accepted once decompressed.
+    #[inline]
+    pub fn max_decompressed_len(mut self, value: usize) -> Self {
+        self.max_len = value;
+        self
+    }
+
+    /// Sets whether compression is offered in the handshake. Compressed frames
+    /// sent by the peer are decoded either way.
+    #[inline]
+    pub fn offer(mut self, value: bool) -> Self {
+        self.offer = value;
+        self
+    }
+
+    /// Returns whether frames are being compressed, i.e. both sides agreed to.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Decompresses `input` into the scratch buffer, at the same offset from a page
+    /// boundary as `origin`, where the payload would have been if sent as is.
+    fn decompress(
+        &self,
+        input: &[u8],
+        raw_len: usize,
+        origin: *const u8,
+    ) -> Result<Payload<'_>, CompressingFramerError<F::Error>> {
+        if raw_len > self.max_len {
+            return Err(CompressingFramerError::DecompressedTooLarge {
+                limit: self.max_len,
+                actual: raw_len,
+            });
+        }
+
+        let offset = origin as usize % page::page_size();
+        let len = offset + raw_len;
+
+        let mut scratch = match self.scratch.try_lock() {
+            Ok(scratch) => scratch,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return Err(CompressingFramerError::FrameInUse),
+        };
+        let buf = scratch.get_or_insert_with(|| AlignedBuffer::new(len, page::page_size()));
+        if buf.len() < len {
+            buf.resize(len.max(buf.len().saturating_mul(2)));
+        }
+
+        let output = &mut buf[offset..len];
+        let actual = lz4::decompress_into(input, output)?;
+        if actual != raw_len {
+            return Err(CompressingFramerError::LengthMismatch {
+                expected: raw_len,
+                actual,
+            });
+        }
+
+        debug!(
+            "[Frame] Decompressed payload {} bytes into {} bytes",
+            input.len(),
+            raw_len
+        );
+        Ok(Payload::Decompressed(scratch, offset..len))
+    }
+}
+
+impl<F: Framer + Clone> Clone for CompressingFramer<F> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            threshold: self.threshold,
+            max_len: self.max_len,
+            offer: self.offer,
+            enabled: self.enabled,
+            scratch: Mutex::new(None),
+        }
+    }
+}
+
+impl<F: fmt::Debug> fmt::Debug for CompressingFramer<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompressingFramer")
+            .field("inner", &self.inner)
+            .field("threshold", &self.threshold)
+            .field("max_len", &self.max_len)
+            .field("offer", &self.offer)
+            .field("enabled", &self.enabled)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Framer> Framer for CompressingFramer<F> {
+    type Error = CompressingFramerError<F::Error>;
+
+    fn encode_frame<'a>(&self, buf: &'a mut [u8]) -> impl FrameBuf<Error = Self::Error> + 'a {
+        CompressedFrameBuffer {
+            frame_buf: self.inner.encode_frame(buf),
+            threshold: self.enabled.then_some(self.threshold),
+        }
+    }
+
+    fn decode_frame<'a>(&'a self, buf: &'a [u8]) -> Result<Option<impl Frame + 'a>, Self::Error> {
+        let frame = match self
+            .inner
+            .decode_frame(buf)
+            .map_err(CompressingFramerError::FramerError)?
+        {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+
+        let header = CompressionHeader::read_from_prefix(&frame)
+            .map_err(|_| CompressingFramerError::TruncatedHeader {
+                length: frame.len(),
+            })?
+            .0;
+
+        let payload = match header.flags.get() & FLAG_COMPRESSED {
+            0 => Payload::Plain,
+            _ => {
+                let input = &frame[HEADER_LEN..];
+                self.decompress(input, header.raw_len.get() as usize, input.as_ptr())?
+            }
+        };
+
+        Ok(Some(CompressedFrame { frame, payload }))
+    }
+
+    fn features(&self) -> HandshakeFeatures {
+        match self.offer {
+            true => self.inner.features() | HandshakeFeatures::COMPRESSION,
+            false => self.inner.features(),
+        }
+    }
+
+    fn set_features(&mut self, features: HandshakeFeatures) {
+        self.enabled = features.contains(HandshakeFeatures::COMPRESSION);
+        self.inner.set_features(features);
+    }
+}
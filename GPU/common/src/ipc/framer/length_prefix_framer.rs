@@ -12,17 +12,22 @@
  * See the Mulan PSL v2 for more details.
  */
 
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, Range};
 
 use thiserror::Error;
 use tracing::debug;
-use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, NativeEndian, U32};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, LittleEndian, U16, U32};
 
-use super::{Frame, FrameBuf, Framer};
+use super::{Fragment, Frame, FrameBuf, Framer};
 
 const FRAME_MAGIC_NUMBER: u32 = 0x78464D45; // 'xFME' in ASCII
 pub const DEFAULT_MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
 
+/// The frame is a fragment of a larger message.
+const FLAG_FRAGMENT: u16 = 0b0001;
+/// The frame is the last fragment of its message.
+const FLAG_LAST: u16 = 0b0010;
+
 #[derive(Debug, Error)]
 pub enum LengthPrefixFramerError {
     #[error("insufficient buffer capacity (required: {required}, capacity: {capacity})")]
@@ -44,17 +49,40 @@ pub enum LengthPrefixFramerError {
     ChecksumMismatch { expected: u32, actual: u32 },
 }
 
+/// Fixed-width little-endian, so peers of any architecture agree on it.
 #[repr(C)]
 #[derive(Debug, FromBytes, IntoBytes, KnownLayout, Immutable)]
 struct FrameHeader {
-    magic: U32<NativeEndian>,
-    length: U32<NativeEndian>,
-    checksum: U32<NativeEndian>,
+    magic: U32<LittleEndian>,
+    length: U32<LittleEndian>,
+    checksum: U32<LittleEndian>,
+    seq: U16<LittleEndian>,
+    flags: U16<LittleEndian>,
+}
+
+impl FrameHeader {
+    fn checksum(&self, payload: &[u8]) -> u32 {
+        let mut crc32 = crc32fast::Hasher::new();
+        crc32.update(self.length.as_bytes());
+        crc32.update(self.seq.as_bytes());
+        crc32.update(self.flags.as_bytes());
+        crc32.update(payload);
+        crc32.finalize()
+    }
+
+    fn fragment(&self) -> Option<Fragment> {
+        let flags = self.flags.get();
+        (flags & FLAG_FRAGMENT != 0).then(|| Fragment {
+            seq: self.seq.get(),
+            last: flags & FLAG_LAST != 0,
+        })
+    }
 }
 
 #[derive(Debug)]
 struct LengthPrefixFrame<B> {
     payload: B,
+    fragment: Option<Fragment>,
 }
 
 impl<B: AsRef<[u8]>> Deref for LengthPrefixFrame<B> {
@@ -69,17 +97,34 @@ impl<B: AsRef<[u8]>> Frame for LengthPrefixFrame<B> {
     fn frame_len(&self) -> usize {
         size_of::<FrameHeader>().saturating_add(self.payload.as_ref().len())
     }
+
+    fn fragment(&self) -> Option<Fragment> {
+        self.fragment
+    }
 }
 
 #[derive(Debug)]
 struct LengthPrefixFrameBuffer<B> {
     buffer: B,
     limit: usize,
+    fragment: Option<Fragment>,
+}
+
+impl<B: AsRef<[u8]>> LengthPrefixFrameBuffer<B> {
+    /// Returns the payload range, which never exceeds the frame limit.
+    fn payload_range(&self) -> Range<usize> {
+        let end = self.buffer.as_ref().len().min(self.limit);
+        size_of::<FrameHeader>().min(end)..end
+    }
 }
 
 impl<B: AsRef<[u8]> + AsMut<[u8]>> FrameBuf for LengthPrefixFrameBuffer<B> {
     type Error = LengthPrefixFramerError;
 
+    fn set_fragment(&mut self, fragment: Fragment) {
+        self.fragment = Some(fragment);
+    }
+
     fn finalize(mut self, payload_len: usize) -> Result<usize, Self::Error> {
         let buf_len = self.buffer.as_ref().len();
         let frame_len = size_of::<FrameHeader>() + payload_len;
@@ -100,19 +145,21 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> FrameBuf for LengthPrefixFrameBuffer<B> {
 
         let (header_buf, payload_buf) = self.buffer.as_mut().split_at_mut(size_of::<FrameHeader>());
 
+        let (seq, flags) = match self.fragment {
+            Some(Fragment { seq, last: false }) => (seq, FLAG_FRAGMENT),
+            Some(Fragment { seq, last: true }) => (seq, FLAG_FRAGMENT | FLAG_LAST),
+            None => (0, 0),
+        };
         let mut header = FrameHeader {
             magic: U32::new(FRAME_MAGIC_NUMBER),
             length: U32::new(payload_len as u32),
             checksum: U32::new(0),
+            seq: U16::new(seq),
+            flags: U16::new(flags),
         };
-
-        let checksum = {
-            let mut crc32 = crc32fast::Hasher::new();
-            crc32.update(header.length.as_bytes());
-            crc32.update(&payload_buf[..payload_len]);
-            crc32.finalize()
-        };
-        header.checksum.set(checksum);
+        header
+            .checksum
+            .set(header.checksum(&payload_buf[..payload_len]));
 
         header
             .write_to_prefix(header_buf)
@@ -130,13 +177,14 @@ impl<B: AsRef<[u8]>> Deref for LengthPrefixFrameBuffer<B> {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        &self.buffer.as_ref()[size_of::<FrameHeader>()..]
+        &self.buffer.as_ref()[self.payload_range()]
     }
 }
 
 impl<B: AsRef<[u8]> + AsMut<[u8]>> DerefMut for LengthPrefixFrameBuffer<B> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.buffer.as_mut()[size_of::<FrameHeader>()..]
+        let range = self.payload_range();
+        &mut self.buffer.as_mut()[range]
     }
 }
 
@@ -167,10 +215,11 @@ impl Framer for LengthPrefixFramer {
         LengthPrefixFrameBuffer {
             buffer: buf,
             limit: self.limit,
+            fragment: None,
         }
     }
 
-    fn decode_frame<'a>(&self, buf: &'a [u8]) -> Result<Option<impl Frame + 'a>, Self::Error> {
+    fn decode_frame<'a>(&'a self, buf: &'a [u8]) -> Result<Option<impl Frame + 'a>, Self::Error> {
         let buf_len = buf.len();
         let header_len = size_of::<FrameHeader>();
 
@@ -219,12 +268,7 @@ impl Framer for LengthPrefixFramer {
         let payload = &buf[header_len..frame_len];
 
         /* Calculate frame checksum */
-        let checksum = {
-            let mut crc32 = crc32fast::Hasher::new();
-            crc32.update(header.length.as_bytes());
-            crc32.update(payload);
-            crc32.finalize()
-        };
+        let checksum = header.checksum(payload);
 
         /* Check frame checksum */
         let header_checksum = header.checksum.get();
@@ -239,6 +283,9 @@ impl Framer for LengthPrefixFramer {
             "[Frame] Decoded {} bytes (payload {} bytes)",
             frame_len, payload_len
         );
-        Ok(Some(LengthPrefixFrame { payload }))
+        Ok(Some(LengthPrefixFrame {
+            payload,
+            fragment: header.fragment(),
+        }))
     }
 }
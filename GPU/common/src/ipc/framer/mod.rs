@@ -18,10 +18,28 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+use super::message::HandshakeFeatures;
+
+/// Position of a frame within a message split across consecutive frames.
+///
+/// Fragments carry the message as encoded at the start of an aligned buffer,
+/// so it must be reassembled at such an address before decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fragment {
+    /// Index of the frame within its message, wrapping around.
+    pub seq: u16,
+    /// Whether this frame completes the message.
+    pub last: bool,
+}
+
 /// A type implementing this trait can be read from like a `&[u8]` and
 /// must provide its frame size.
 pub trait Frame: Deref<Target = [u8]> {
     fn frame_len(&self) -> usize;
+
+    /// Returns the position of the frame within a fragmented message, or `None`
+    /// if the frame holds a whole message.
+    fn fragment(&self) -> Option<Fragment>;
 }
 
 /// A type implementing this trait can be written to like a `&mut [u8]` and
@@ -30,6 +48,9 @@ pub trait FrameBuf: Deref<Target = [u8]> + DerefMut {
     /// The error type returned upon finalization failure.
     type Error: StdError + Send + Sync + 'static;
 
+    /// Marks the frame as a fragment of a larger message.
+    fn set_fragment(&mut self, fragment: Fragment);
+
     /// Finalizes the frame, writing the header and returning the total frame size.
     fn finalize(self, payload_len: usize) -> Result<usize, Self::Error>;
 }
@@ -42,9 +63,21 @@ pub trait Framer: Debug + Send + Sync + 'static {
     /// Prepares a new frame for writing within the given buffer.
     fn encode_frame<'a>(&self, buf: &'a mut [u8]) -> impl FrameBuf<Error = Self::Error> + 'a;
 
-    /// Decodes a frame from the beginning of the provided buffer.
-    fn decode_frame<'a>(&self, buf: &'a [u8]) -> Result<Option<impl Frame + 'a>, Self::Error>;
+    /// Decodes a frame from the beginning of the provided buffer. The frame may
+    /// borrow from the framer as well, e.g. a payload it decoded.
+    fn decode_frame<'a>(&'a self, buf: &'a [u8]) -> Result<Option<impl Frame + 'a>, Self::Error>;
+
+    /// Returns the handshake features this framer implements, see `HandshakeFeatures::FRAMER`.
+    fn features(&self) -> HandshakeFeatures {
+        HandshakeFeatures::empty()
+    }
+
+    /// Applies the features negotiated by the handshake to the frames encoded from now on.
+    fn set_features(&mut self, _features: HandshakeFeatures) {}
 }
 
 mod length_prefix_framer;
 pub use length_prefix_framer::LengthPrefixFramer;
+
+mod compressing_framer;
+pub use compressing_framer::{CompressingFramer, CompressingFramerError};
@@ -12,24 +12,38 @@
  * See the Mulan PSL v2 for more details.
  */
 
-use std::{
-    any::{TypeId, type_name},
-    fmt::Debug,
-    marker::PhantomData,
-    ptr, slice,
-};
+use std::{any::type_name, ffi::CStr, fmt::Debug, marker::PhantomData, ptr, slice};
 
 use bitflags::bitflags;
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, LittleEndian, U16, U32, U64};
 
 use crate::ipc::bytewise::{
     BytewiseError, BytewiseReadOwned, BytewiseReader, BytewiseWrite, BytewiseWriter,
 };
 
-use super::MessageError;
+use super::{
+    MessageError, PointerField, PointerFields, TypeTag,
+    pointer_field::{copy_keeping_fields, read_fields, write_fields},
+};
 
 const INLINED_DATA_SIZE: usize = 16;
 const INLINED_DATA_ALIGN: usize = 16;
 
+/// The value follows the descriptor, and is copied into the argument.
+const STORAGE_INLINE: u8 = 0;
+/// The value follows the descriptor, and is borrowed from the buffer.
+const STORAGE_DATA: u8 = 1;
+/// The value stays in a shared region, at the offset of the descriptor.
+const STORAGE_SHARED: u8 = 2;
+/// No value follows, the argument is a null pointer.
+const STORAGE_NULL: u8 = 3;
+/// No value follows, the receiver allocates room for `len` elements.
+const STORAGE_RESERVED: u8 = 4;
+
+/// Upper bound on the room a peer may reserve in one argument, allocated as soon
+/// as the argument is read.
+const MAX_RESERVED_SIZE: usize = 16 * 1024 * 1024;
+
 bitflags! {
     #[repr(transparent)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -46,16 +60,36 @@ impl Default for ArgumentFlag {
     }
 }
 
+#[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ArgumentKind {
-    Scalar,
-    Slice,
+    Scalar = 0,
+    Slice = 1,
+    /// Bytes of a nul-terminated string, the nul included.
+    CStr = 2,
+    /// A buffer filled by the peer, of which it reports the used length. Only its
+    /// capacity goes out, the data comes back.
+    OutBuffer = 3,
+}
+
+impl TryFrom<u8> for ArgumentKind {
+    type Error = BytewiseError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Scalar),
+            1 => Ok(Self::Slice),
+            2 => Ok(Self::CStr),
+            3 => Ok(Self::OutBuffer),
+            _ => Err(BytewiseError::InvalidEncoding("argument kind")),
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
 struct ArgumentMetadata {
     kind: ArgumentKind,
-    type_id: TypeId,
+    type_tag: u64,
     type_size: usize,
     type_align: usize,
     len: usize,
@@ -64,7 +98,7 @@ struct ArgumentMetadata {
 impl Debug for ArgumentMetadata {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ArgumentMetadata")
-            .field("type_id", &self.type_id)
+            .field("type_tag", &format_args!("{:#018x}", self.type_tag))
             .field("type_size", &self.type_size)
             .field("type_align", &self.type_align)
             .field("len", &self.len)
@@ -72,6 +106,77 @@ impl Debug for ArgumentMetadata {
     }
 }
 
+/// Wire form of an argument, fixed-width little-endian. The value, if sent,
+/// follows as opaque bytes aligned to `type_align`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, KnownLayout, Immutable)]
+struct ArgumentDescriptor {
+    type_tag: U64<LittleEndian>,
+    type_size: U64<LittleEndian>,
+    len: U64<LittleEndian>,
+    /// Offset of the value within the shared region, for `STORAGE_SHARED`.
+    offset: U64<LittleEndian>,
+    type_align: U32<LittleEndian>,
+    flag: U32<LittleEndian>,
+    kind: u8,
+    storage: u8,
+    /// Number of pointer fields following the value.
+    field_count: U16<LittleEndian>,
+    _reserved: [u8; 4],
+}
+
+impl ArgumentDescriptor {
+    fn new(
+        meta: &ArgumentMetadata,
+        flag: ArgumentFlag,
+        storage: u8,
+        offset: usize,
+        field_count: usize,
+    ) -> Result<Self, BytewiseError> {
+        let type_align = u32::try_from(meta.type_align)
+            .map_err(|_| BytewiseError::InvalidEncoding("argument alignment"))?;
+        let field_count = u16::try_from(field_count)
+            .map_err(|_| BytewiseError::InvalidEncoding("pointer field count"))?;
+
+        Ok(Self {
+            type_tag: U64::new(meta.type_tag),
+            type_size: U64::new(meta.type_size as u64),
+            len: U64::new(meta.len as u64),
+            offset: U64::new(offset as u64),
+            type_align: U32::new(type_align),
+            flag: U32::new(flag.bits()),
+            kind: meta.kind as u8,
+            storage,
+            field_count: U16::new(field_count),
+            _reserved: [0; 4],
+        })
+    }
+
+    fn metadata(&self) -> Result<ArgumentMetadata, BytewiseError> {
+        let to_usize = |value: u64, what| {
+            usize::try_from(value).map_err(|_| BytewiseError::InvalidEncoding(what))
+        };
+
+        let meta = ArgumentMetadata {
+            kind: ArgumentKind::try_from(self.kind)?,
+            type_tag: self.type_tag.get(),
+            type_size: to_usize(self.type_size.get(), "argument size")?,
+            type_align: to_usize(self.type_align.get().into(), "argument alignment")?,
+            len: to_usize(self.len.get(), "argument length")?,
+        };
+        if meta.type_size.checked_mul(meta.len).is_none() {
+            return Err(BytewiseError::InvalidEncoding("argument length"));
+        }
+
+        Ok(meta)
+    }
+
+    fn offset(&self) -> Result<usize, BytewiseError> {
+        usize::try_from(self.offset.get())
+            .map_err(|_| BytewiseError::InvalidEncoding("shared argument offset"))
+    }
+}
+
 #[derive(Clone, Copy)]
 #[repr(align(16))]
 struct InlineBytes([u8; INLINED_DATA_SIZE]);
@@ -94,34 +199,82 @@ impl Debug for InlineBytes {
     }
 }
 
-#[derive(Clone, Copy)]
+impl InlineBytes {
+    /// Copies `len` bytes at `src` into as many chunks as needed, keeping their
+    /// alignment.
+    ///
+    /// # Safety
+    ///
+    /// `src` must be valid for reads of `len` bytes.
+    unsafe fn spill(src: *const u8, len: usize) -> Box<[InlineBytes]> {
+        let mut chunks =
+            vec![InlineBytes([0u8; INLINED_DATA_SIZE]); len.div_ceil(INLINED_DATA_SIZE)]
+                .into_boxed_slice();
+        unsafe {
+            ptr::copy_nonoverlapping(src, chunks.as_mut_ptr().cast(), len);
+        }
+        chunks
+    }
+}
+
+#[derive(Clone)]
 enum ArgumentValue<'a> {
     Val(InlineBytes),
+    /// A value too large to inline, sent the same way.
+    Spilled(Box<[InlineBytes]>),
     Ref(ptr::NonNull<u8>, PhantomData<&'a ()>),
     Mut(ptr::NonNull<u8>, PhantomData<&'a mut ()>),
+    /// Data at an offset of a region mapped by both peers, the pointer is local to
+    /// each process and `None` until resolved.
+    Shared(usize, Option<ptr::NonNull<u8>>, PhantomData<&'a mut ()>),
+    /// A null pointer, for optional arguments.
+    Null,
+    /// Memory for the peer to fill, possibly uninitialized: never read, only its
+    /// capacity goes out.
+    Reserved(ptr::NonNull<u8>, PhantomData<&'a mut ()>),
+    /// Room allocated for memory the peer reserved, filled in place and sent back.
+    Buffer(Box<[InlineBytes]>),
 }
 
 impl Debug for ArgumentValue<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Val(inline_bytes) => f.debug_struct("Val").field("data", inline_bytes).finish(),
+            Self::Spilled(chunks) => f
+                .debug_struct("Spilled")
+                .field("data", &chunks[0])
+                .field("chunks", &chunks.len())
+                .finish(),
             Self::Ref(ptr, _) => f.debug_struct("Ref").field("ptr", ptr).finish(),
             Self::Mut(ptr, _) => f.debug_struct("Mut").field("ptr", ptr).finish(),
+            Self::Shared(offset, ptr, _) => f
+                .debug_struct("Shared")
+                .field("offset", offset)
+                .field("ptr", ptr)
+                .finish(),
+            Self::Null => f.write_str("Null"),
+            Self::Reserved(ptr, _) => f.debug_struct("Reserved").field("ptr", ptr).finish(),
+            Self::Buffer(chunks) => f
+                .debug_struct("Buffer")
+                .field("chunks", &chunks.len())
+                .finish(),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Argument<'a> {
     meta: ArgumentMetadata,
     value: ArgumentValue<'a>,
     flag: ArgumentFlag,
+    /// Pointers held in the value, whose data is sent along with it.
+    fields: Option<Box<[PointerField]>>,
 }
 
 impl Argument<'_> {
     #[inline]
-    pub const fn type_id(&self) -> TypeId {
-        self.meta.type_id
+    pub const fn type_tag(&self) -> u64 {
+        self.meta.type_tag
     }
 
     #[inline]
@@ -153,6 +306,28 @@ impl Argument<'_> {
     pub const fn flag(&self) -> ArgumentFlag {
         self.flag
     }
+
+    /// Returns the pointer fields sent along with the value.
+    #[inline]
+    pub fn pointer_fields(&self) -> &[PointerField] {
+        self.fields.as_deref().unwrap_or_default()
+    }
+
+    /// Returns true if the argument was made from a null pointer.
+    #[inline]
+    pub const fn is_null(&self) -> bool {
+        matches!(self.value, ArgumentValue::Null)
+    }
+
+    /// Returns the offset of the data within the shared region, if the argument
+    /// references one.
+    #[inline]
+    pub const fn shared_offset(&self) -> Option<usize> {
+        match self.value {
+            ArgumentValue::Shared(offset, _, _) => Some(offset),
+            _ => None,
+        }
+    }
 }
 
 impl Argument<'_> {
@@ -161,23 +336,17 @@ impl Argument<'_> {
         Self::from_value((), ArgumentFlag::default())
     }
 
+    /// Creates an `Argument` holding a copy of `value`, of any size. Values over
+    /// 16 bytes are kept on the heap.
     #[inline]
-    pub fn from_value<T: Copy + 'static>(value: T, flag: ArgumentFlag) -> Argument<'static> {
+    pub fn from_value<T: Copy + TypeTag>(value: T, flag: ArgumentFlag) -> Argument<'static> {
         let meta = ArgumentMetadata {
-            type_id: TypeId::of::<T>(),
+            type_tag: T::TYPE_TAG,
             kind: ArgumentKind::Scalar,
             type_size: size_of::<T>(),
             type_align: align_of::<T>(),
             len: 1,
         };
-        if meta.type_size > INLINED_DATA_SIZE {
-            panic!(
-                "Type '{}' size {} exceeds {}-byte limit",
-                type_name::<T>(),
-                meta.type_size,
-                INLINED_DATA_SIZE,
-            );
-        }
         if meta.type_align > INLINED_DATA_ALIGN {
             panic!(
                 "Type '{}' alignment {} exceeds {}-byte limit",
@@ -190,30 +359,50 @@ impl Argument<'_> {
         // For ZST, uses dangling pointer to save memory
         if meta.type_size == 0 {
             let value = ArgumentValue::Mut(ptr::NonNull::dangling(), PhantomData);
-            return Argument { meta, value, flag };
+            return Argument {
+                meta,
+                value,
+                flag,
+                fields: None,
+            };
+        }
+
+        let src = ptr::from_ref(&value).cast();
+        if meta.type_size > INLINED_DATA_SIZE {
+            let value = ArgumentValue::Spilled(unsafe { InlineBytes::spill(src, meta.type_size) });
+            return Argument {
+                meta,
+                value,
+                flag,
+                fields: None,
+            };
         }
 
         let mut bytes = InlineBytes([0u8; INLINED_DATA_SIZE]);
         unsafe {
-            ptr::copy_nonoverlapping(
-                ptr::from_ref(&value).cast(),
-                bytes.0.as_mut_ptr(),
-                meta.type_size,
-            );
+            ptr::copy_nonoverlapping(src, bytes.0.as_mut_ptr(), meta.type_size);
         }
         let value = ArgumentValue::Val(bytes);
 
-        Argument { meta, value, flag }
+        Argument {
+            meta,
+            value,
+            flag,
+            fields: None,
+        }
     }
 
     /// Creates an `Argument` from a raw constant pointer.
     ///
+    /// A null `ptr` makes a null argument, which is sent as absent and received
+    /// through `downcast_opt_ref`.
+    ///
     /// # Safety
     ///
     /// The caller must ensure that the pointer `ptr` adheres to the following conditions,
     /// which are necessary to create a valid shared reference `&'a T`:
     ///
-    /// 1. `ptr` must be non-null and point to a properly initialized value of type `T`.
+    /// 1. `ptr` must be null, or point to a properly initialized value of type `T`.
     /// 2. The memory pointed to by `ptr` must be valid for reads for the entire lifetime `'a`.
     /// 3. For the entire lifetime `'a`, the data pointed to by `ptr` must **not be mutated**
     ///    through any other pointer or reference. Multiple shared references are allowed, but
@@ -221,45 +410,54 @@ impl Argument<'_> {
     ///
     /// Failure to uphold these guarantees will result in **undefined behavior**.
     #[inline]
-    pub unsafe fn from_ptr<'a, T: 'static>(ptr: *const T, flag: ArgumentFlag) -> Argument<'a> {
+    pub unsafe fn from_ptr<'a, T: TypeTag>(ptr: *const T, flag: ArgumentFlag) -> Argument<'a> {
         let meta = ArgumentMetadata {
             kind: ArgumentKind::Scalar,
-            type_id: TypeId::of::<T>(),
+            type_tag: T::TYPE_TAG,
             type_size: size_of::<T>(),
             type_align: align_of::<T>(),
             len: 1,
         };
-        let value = ArgumentValue::Ref(
-            {
-                let ptr = ptr::NonNull::new(ptr.cast_mut().cast()).expect("Invalid null pointer");
-                if ptr != ptr::NonNull::dangling() {
-                    assert!(
-                        meta.type_size != 0,
-                        "Pointer should not point to zero-sized type"
-                    );
-                } else {
-                    assert!(
-                        meta.type_size == 0,
-                        "Dangling pointer should not point to non-zero-sized type"
-                    );
-                }
-
-                ptr
-            },
-            PhantomData,
-        );
+        let Some(ptr) = ptr::NonNull::new(ptr.cast_mut().cast()) else {
+            return Argument {
+                meta,
+                value: ArgumentValue::Null,
+                flag,
+                fields: None,
+            };
+        };
+        if ptr != ptr::NonNull::dangling() {
+            assert!(
+                meta.type_size != 0,
+                "Pointer should not point to zero-sized type"
+            );
+        } else {
+            assert!(
+                meta.type_size == 0,
+                "Dangling pointer should not point to non-zero-sized type"
+            );
+        }
+        let value = ArgumentValue::Ref(ptr, PhantomData);
 
-        Argument { meta, value, flag }
+        Argument {
+            meta,
+            value,
+            flag,
+            fields: None,
+        }
     }
 
     /// Creates an `Argument` from a raw mutable pointer.
     ///
+    /// A null `ptr` makes a null argument, which is sent as absent, received
+    /// through `downcast_opt_mut` and never written back.
+    ///
     /// # Safety
     ///
     /// The caller must ensure that the pointer `ptr` adheres to the following conditions,
     /// which are necessary to create a valid unique mutable reference `&'a mut T`:
     ///
-    /// 1. `ptr` must be non-null and point to a properly initialized value of type `T`.
+    /// 1. `ptr` must be null, or point to a properly initialized value of type `T`.
     /// 2. The memory pointed to by `ptr` must be valid for both reads and writes for the
     ///    entire lifetime `'a`.
     /// 3. For the entire lifetime `'a`, **no other pointers or references (read or write)**
@@ -268,72 +466,191 @@ impl Argument<'_> {
     ///
     /// Failure to uphold these guarantees will result in **undefined behavior**.
     #[inline]
-    pub unsafe fn from_mut_ptr<'a, T: 'static>(ptr: *mut T, flag: ArgumentFlag) -> Argument<'a> {
+    pub unsafe fn from_mut_ptr<'a, T: TypeTag>(ptr: *mut T, flag: ArgumentFlag) -> Argument<'a> {
         let meta = ArgumentMetadata {
             kind: ArgumentKind::Scalar,
-            type_id: TypeId::of::<T>(),
+            type_tag: T::TYPE_TAG,
             type_size: size_of::<T>(),
             type_align: align_of::<T>(),
             len: 1,
         };
-        let value = ArgumentValue::Mut(
-            {
-                let ptr = ptr::NonNull::new(ptr.cast()).expect("Invalid null pointer");
-                if ptr != ptr::NonNull::dangling() {
-                    assert!(
-                        meta.type_size != 0,
-                        "Pointer should not point to zero-sized type"
-                    );
-                } else {
-                    assert!(
-                        meta.type_size == 0,
-                        "Dangling pointer should not point to non-zero-sized type"
-                    );
-                }
+        let Some(ptr) = ptr::NonNull::new(ptr.cast()) else {
+            return Argument {
+                meta,
+                value: ArgumentValue::Null,
+                flag,
+                fields: None,
+            };
+        };
+        if ptr != ptr::NonNull::dangling() {
+            assert!(
+                meta.type_size != 0,
+                "Pointer should not point to zero-sized type"
+            );
+        } else {
+            assert!(
+                meta.type_size == 0,
+                "Dangling pointer should not point to non-zero-sized type"
+            );
+        }
+        let value = ArgumentValue::Mut(ptr, PhantomData);
 
-                ptr
-            },
-            PhantomData,
-        );
+        Argument {
+            meta,
+            value,
+            flag,
+            fields: None,
+        }
+    }
+
+    /// Creates an `Argument` from `len` elements at `offset` within a region shared
+    /// with the peer, mapped at `ptr` in this process.
+    ///
+    /// Only the offset is sent, the peer reaches the data through its own mapping
+    /// once the argument is resolved by `resolve_shared`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    ///
+    /// 1. `ptr` is valid for both reads and writes of `len` elements of `T` for the
+    ///    entire lifetime `'a`, with no other references accessing the data meanwhile.
+    /// 2. The peer maps the same region, so that `offset` designates the same bytes.
+    #[inline]
+    pub unsafe fn from_shared<'a, T: TypeTag>(
+        offset: usize,
+        ptr: *mut T,
+        len: usize,
+        flag: ArgumentFlag,
+    ) -> Argument<'a> {
+        let meta = ArgumentMetadata {
+            kind: ArgumentKind::Slice,
+            type_tag: T::TYPE_TAG,
+            type_size: size_of::<T>(),
+            type_align: align_of::<T>(),
+            len,
+        };
+        let ptr = ptr::NonNull::new(ptr.cast()).unwrap_or(ptr::NonNull::dangling());
+        let value = ArgumentValue::Shared(offset, Some(ptr), PhantomData);
+
+        Argument {
+            meta,
+            value,
+            flag,
+            fields: None,
+        }
+    }
 
-        Argument { meta, value, flag }
+    /// Creates an `Argument` from a byte buffer at `offset` within a region shared
+    /// with the peer, see `from_shared`.
+    ///
+    /// The peer must map the same region for the data to reach it, though it never
+    /// resolves the argument outside of its mapping.
+    #[inline]
+    pub fn from_shared_slice<'a>(
+        offset: usize,
+        buf: &'a mut [u8],
+        flag: ArgumentFlag,
+    ) -> Argument<'a> {
+        // SAFETY: The buffer is exclusively borrowed for the lifetime of the argument.
+        unsafe { Self::from_shared(offset, buf.as_mut_ptr(), buf.len(), flag) }
+    }
+
+    /// Points a received shared argument into this process' mapping of the region,
+    /// `region_len` bytes starting at `base`. Other arguments are left untouched.
+    ///
+    /// Fails with `MessageError::SharedOutOfBounds` if the data exceeds the region.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be valid for both reads and writes of `region_len` bytes for the
+    /// entire lifetime of the argument, and map the region the peer referenced.
+    pub unsafe fn resolve_shared(
+        &mut self,
+        base: *mut u8,
+        region_len: usize,
+    ) -> Result<(), MessageError> {
+        let ArgumentValue::Shared(offset, _, _) = self.value else {
+            return Ok(());
+        };
+
+        let len = self.total_size();
+        if offset.checked_add(len).is_none_or(|end| end > region_len) {
+            return Err(MessageError::SharedOutOfBounds {
+                offset,
+                len,
+                region_len,
+            });
+        }
+
+        // SAFETY: The range was checked against the region, which the caller
+        // guarantees to be valid.
+        let ptr = ptr::NonNull::new(unsafe { base.add(offset) });
+        self.value = ArgumentValue::Shared(offset, ptr, PhantomData);
+
+        Ok(())
     }
 }
 
 impl<'a> Argument<'a> {
     #[inline]
-    pub fn from_ref<T: 'static>(value: &'a T, flag: ArgumentFlag) -> Self {
+    pub fn from_ref<T: TypeTag>(value: &'a T, flag: ArgumentFlag) -> Self {
         let meta = ArgumentMetadata {
             kind: ArgumentKind::Scalar,
-            type_id: TypeId::of::<T>(),
+            type_tag: T::TYPE_TAG,
             type_size: size_of::<T>(),
             type_align: align_of::<T>(),
             len: 1,
         };
         let value = ArgumentValue::Ref(ptr::NonNull::from(value).cast(), PhantomData);
 
-        Self { meta, value, flag }
+        Self {
+            meta,
+            value,
+            flag,
+            fields: None,
+        }
     }
 
     #[inline]
-    pub fn from_mut<T: 'static>(value: &'a mut T, flag: ArgumentFlag) -> Self {
+    pub fn from_mut<T: TypeTag>(value: &'a mut T, flag: ArgumentFlag) -> Self {
         let meta = ArgumentMetadata {
             kind: ArgumentKind::Scalar,
-            type_id: TypeId::of::<T>(),
+            type_tag: T::TYPE_TAG,
             type_size: size_of::<T>(),
             type_align: align_of::<T>(),
             len: 1,
         };
         let value = ArgumentValue::Mut(ptr::NonNull::from(value).cast(), PhantomData);
 
-        Self { meta, value, flag }
+        Self {
+            meta,
+            value,
+            flag,
+            fields: None,
+        }
+    }
+
+    /// Creates an `Argument` from an optional reference, `None` makes a null argument.
+    #[inline]
+    pub fn from_opt_ref<T: TypeTag>(value: Option<&'a T>, flag: ArgumentFlag) -> Self {
+        // SAFETY: The pointer comes from a reference valid for 'a, or is null.
+        unsafe { Self::from_ptr(value.map_or(ptr::null(), ptr::from_ref), flag) }
+    }
+
+    /// Creates an `Argument` from an optional mutable reference, `None` makes a null
+    /// argument.
+    #[inline]
+    pub fn from_opt_mut<T: TypeTag>(value: Option<&'a mut T>, flag: ArgumentFlag) -> Self {
+        // SAFETY: The pointer comes from a unique reference valid for 'a, or is null.
+        unsafe { Self::from_mut_ptr(value.map_or(ptr::null_mut(), ptr::from_mut), flag) }
     }
 
     #[inline]
-    pub fn from_slice<T: 'static>(value: &'a [T], flag: ArgumentFlag) -> Self {
+    pub fn from_slice<T: TypeTag>(value: &'a [T], flag: ArgumentFlag) -> Self {
         let meta = ArgumentMetadata {
             kind: ArgumentKind::Slice,
-            type_id: TypeId::of::<T>(),
+            type_tag: T::TYPE_TAG,
             type_size: size_of::<T>(),
             type_align: align_of::<T>(),
             len: value.len(),
@@ -342,14 +659,19 @@ impl<'a> Argument<'a> {
             ptr::NonNull::new(value.as_ptr().cast_mut().cast()).unwrap_or(ptr::NonNull::dangling());
         let value = ArgumentValue::Ref(ptr, PhantomData);
 
-        Self { meta, value, flag }
+        Self {
+            meta,
+            value,
+            flag,
+            fields: None,
+        }
     }
 
     #[inline]
-    pub fn from_mut_slice<T: 'static>(value: &'a mut [T], flag: ArgumentFlag) -> Self {
+    pub fn from_mut_slice<T: TypeTag>(value: &'a mut [T], flag: ArgumentFlag) -> Self {
         let meta = ArgumentMetadata {
             kind: ArgumentKind::Slice,
-            type_id: TypeId::of::<T>(),
+            type_tag: T::TYPE_TAG,
             type_size: size_of::<T>(),
             type_align: align_of::<T>(),
             len: value.len(),
@@ -357,27 +679,173 @@ impl<'a> Argument<'a> {
         let ptr = ptr::NonNull::new(value.as_mut_ptr().cast()).unwrap_or(ptr::NonNull::dangling());
         let value = ArgumentValue::Mut(ptr, PhantomData);
 
-        Self { meta, value, flag }
+        Self {
+            meta,
+            value,
+            flag,
+            fields: None,
+        }
+    }
+
+    /// Creates an `Argument` from a C string, sent with its nul terminator.
+    #[inline]
+    pub fn from_cstr(value: &'a CStr, flag: ArgumentFlag) -> Self {
+        let bytes = value.to_bytes_with_nul();
+        let meta = ArgumentMetadata {
+            kind: ArgumentKind::CStr,
+            type_tag: u8::TYPE_TAG,
+            type_size: size_of::<u8>(),
+            type_align: align_of::<u8>(),
+            len: bytes.len(),
+        };
+        let value = ArgumentValue::Ref(ptr::NonNull::from(bytes).cast(), PhantomData);
+
+        Self {
+            meta,
+            value,
+            flag,
+            fields: None,
+        }
+    }
+
+    /// Creates an `Argument` from a buffer of `capacity` elements for the peer to
+    /// fill, a null `ptr` makes a null argument. The buffer is never read, so it may
+    /// be uninitialized: only its capacity goes out, and only the part the peer
+    /// reports as used is written back, see `set_used`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null, or valid for writes of `capacity` elements for the entire
+    /// lifetime `'a`.
+    #[inline]
+    pub unsafe fn from_out_buffer<T: TypeTag>(
+        ptr: *mut T,
+        capacity: usize,
+        flag: ArgumentFlag,
+    ) -> Self {
+        let meta = ArgumentMetadata {
+            kind: ArgumentKind::OutBuffer,
+            type_tag: T::TYPE_TAG,
+            type_size: size_of::<T>(),
+            type_align: align_of::<T>(),
+            len: capacity,
+        };
+        let value = match ptr::NonNull::new(ptr) {
+            Some(ptr) => ArgumentValue::Reserved(ptr.cast(), PhantomData),
+            None => ArgumentValue::Null,
+        };
+
+        Self {
+            meta,
+            value,
+            flag,
+            fields: None,
+        }
+    }
+
+    /// Creates an `Argument` from a value holding pointers to further memory, which
+    /// is copied along with it. The peer sees the pointers at its own copy.
+    ///
+    /// # Safety
+    ///
+    /// Each pointer field of `value` must be null, or valid for reads of the
+    /// elements it declares for the entire lifetime `'a`.
+    #[inline]
+    pub unsafe fn from_deep_ref<T: TypeTag + PointerFields>(
+        value: &'a T,
+        flag: ArgumentFlag,
+    ) -> Self {
+        let fields = value.pointer_fields();
+        unsafe { Self::from_ref(value, flag).with_pointer_fields(fields) }
+    }
+
+    /// Creates an `Argument` from a mutable value holding pointers to further memory,
+    /// see `from_deep_ref`. The pointers are kept when the value is updated.
+    ///
+    /// # Safety
+    ///
+    /// Same as `from_deep_ref`.
+    #[inline]
+    pub unsafe fn from_deep_mut<T: TypeTag + PointerFields>(
+        value: &'a mut T,
+        flag: ArgumentFlag,
+    ) -> Self {
+        let fields = value.pointer_fields();
+        unsafe { Self::from_mut(value, flag).with_pointer_fields(fields) }
+    }
+
+    /// Declares pointers held in the referenced value, for values only laid out at
+    /// runtime such as the `void**` parameters of a kernel launch.
+    ///
+    /// # Safety
+    ///
+    /// Same as `from_deep_ref`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not referenced, or a pointer lies outside of it.
+    pub unsafe fn with_pointer_fields(mut self, fields: impl Into<Box<[PointerField]>>) -> Self {
+        let fields = fields.into();
+        assert!(
+            matches!(self.value, ArgumentValue::Ref(..) | ArgumentValue::Mut(..)),
+            "Only referenced values may hold pointer fields"
+        );
+        if let Some(field) = fields
+            .iter()
+            .find(|field| !field.fits_in(self.total_size()))
+        {
+            panic!(
+                "Pointer field at offset {} exceeds {}-byte value",
+                field.offset(),
+                self.total_size()
+            );
+        }
+
+        self.fields = (!fields.is_empty()).then_some(fields);
+        self
+    }
+
+    /// Drops the pointer fields, whose data only travels with the request.
+    #[inline]
+    pub(super) fn without_pointer_fields(mut self) -> Self {
+        self.fields = None;
+        self
+    }
+
+    /// Reports how many elements of an out buffer were filled, only those go back
+    /// to the caller. The length of the argument shrinks accordingly.
+    pub fn set_used(&mut self, len: usize) -> Result<(), MessageError> {
+        if self.meta.kind != ArgumentKind::OutBuffer {
+            return Err(MessageError::ArgumentIsNotOutBuffer);
+        }
+        if len > self.meta.len {
+            return Err(MessageError::OutBufferOverflow {
+                capacity: self.meta.len,
+                used: len,
+            });
+        }
+
+        self.meta.len = len;
+        Ok(())
     }
 }
 
 impl Argument<'_> {
-    fn validate_metadata<T: 'static>(
+    fn validate_metadata<T: TypeTag>(
         &self,
         expected_kind: ArgumentKind,
     ) -> Result<(), MessageError> {
-        if self.meta.type_id != TypeId::of::<T>() {
+        if self.meta.type_tag != T::TYPE_TAG {
             return Err(MessageError::ArgumentTypeMismatch);
         }
 
-        match (self.meta.kind, expected_kind) {
-            (ArgumentKind::Scalar, ArgumentKind::Slice) => {
-                return Err(MessageError::ArgumentIsNotSlice);
-            }
-            (ArgumentKind::Slice, ArgumentKind::Scalar) => {
-                return Err(MessageError::ArgumentIsNotScalar);
-            }
-            _ => {}
+        if self.meta.kind != expected_kind {
+            return Err(match expected_kind {
+                ArgumentKind::Scalar => MessageError::ArgumentIsNotScalar,
+                ArgumentKind::Slice => MessageError::ArgumentIsNotSlice,
+                ArgumentKind::CStr => MessageError::ArgumentIsNotCStr,
+                ArgumentKind::OutBuffer => MessageError::ArgumentIsNotOutBuffer,
+            });
         }
 
         if size_of::<T>() > 0 {
@@ -404,8 +872,17 @@ impl Argument<'_> {
             ArgumentValue::Val(data) => ptr::NonNull::new(data.0.as_ptr().cast_mut())
                 .expect("Inlined data pointer should not be NULL")
                 .cast(),
+            ArgumentValue::Spilled(chunks) | ArgumentValue::Buffer(chunks) => {
+                ptr::NonNull::new(chunks.as_ptr().cast_mut())
+                    .expect("Spilled data pointer should not be NULL")
+                    .cast()
+            }
             ArgumentValue::Ref(ptr, _) => ptr.cast(),
             ArgumentValue::Mut(ptr, _) => ptr.cast(),
+            ArgumentValue::Shared(_, Some(ptr), _) => ptr.cast(),
+            ArgumentValue::Shared(_, None, _) => return Err(MessageError::UnresolvedShared),
+            ArgumentValue::Null => return Err(MessageError::NullArgument),
+            ArgumentValue::Reserved(..) => return Err(MessageError::UnfilledOutBuffer),
         };
 
         if !ptr.is_aligned() {
@@ -418,9 +895,15 @@ impl Argument<'_> {
     #[inline]
     fn inner_ref_ptr<T>(&self) -> Result<ptr::NonNull<T>, MessageError> {
         let ptr = match &self.value {
-            ArgumentValue::Val(_) => return Err(MessageError::IllegalBorrowOfInlined),
+            ArgumentValue::Val(_) | ArgumentValue::Spilled(_) | ArgumentValue::Buffer(_) => {
+                return Err(MessageError::IllegalBorrowOfInlined);
+            }
             ArgumentValue::Ref(ptr, _) => ptr.cast::<T>(),
             ArgumentValue::Mut(ptr, _) => ptr.cast::<T>(),
+            ArgumentValue::Shared(_, Some(ptr), _) => ptr.cast::<T>(),
+            ArgumentValue::Shared(_, None, _) => return Err(MessageError::UnresolvedShared),
+            ArgumentValue::Null => return Err(MessageError::NullArgument),
+            ArgumentValue::Reserved(..) => return Err(MessageError::UnfilledOutBuffer),
         };
 
         if !ptr.is_aligned() {
@@ -433,9 +916,18 @@ impl Argument<'_> {
     #[inline]
     fn inner_mut_ptr<T>(&self) -> Result<ptr::NonNull<T>, MessageError> {
         let ptr = match &self.value {
-            ArgumentValue::Val(_) => return Err(MessageError::IllegalBorrowOfInlined),
+            ArgumentValue::Val(_) | ArgumentValue::Spilled(_) => {
+                return Err(MessageError::IllegalBorrowOfInlined);
+            }
             ArgumentValue::Ref(_, _) => return Err(MessageError::IllegalMutation),
             ArgumentValue::Mut(ptr, _) => ptr.cast::<T>(),
+            ArgumentValue::Buffer(chunks) => ptr::NonNull::new(chunks.as_ptr().cast_mut())
+                .expect("Buffer data pointer should not be NULL")
+                .cast(),
+            ArgumentValue::Shared(_, Some(ptr), _) => ptr.cast::<T>(),
+            ArgumentValue::Shared(_, None, _) => return Err(MessageError::UnresolvedShared),
+            ArgumentValue::Null => return Err(MessageError::NullArgument),
+            ArgumentValue::Reserved(..) => return Err(MessageError::UnfilledOutBuffer),
         };
 
         if !ptr.is_aligned() {
@@ -453,13 +945,13 @@ impl<'a> Argument<'a> {
     /// is properly aligned. It works for both inlined values (`Val`) and
     /// referenced values (`Ref`, `Mut`).
     #[inline]
-    pub fn downcast<T: Copy + 'static>(&self) -> Result<T, MessageError> {
+    pub fn downcast<T: Copy + TypeTag>(&self) -> Result<T, MessageError> {
         self.validate_metadata::<T>(ArgumentKind::Scalar)?;
 
         let ptr = self.inner_val_ptr()?;
 
         // SAFETY:
-        // 1. `validate_metadata` ensures the `TypeId`, size, and align match `T`.
+        // 1. `validate_metadata` ensures the type hash, size, and align match `T`.
         // 2. `get_ptr` provides a valid pointer to the start of the data,
         //    whether it's inlined (`Val`) or external (`Ref`/`Mut`).
         // 3. We have confirmed the pointer is aligned for `T`.
@@ -474,7 +966,7 @@ impl<'a> Argument<'a> {
     /// because the lifetime of inlined data is tied to the `Argument` struct itself,
     /// not the longer lifetime `'a`.
     #[inline]
-    pub fn downcast_ref<T: 'static>(&self) -> Result<&'a T, MessageError> {
+    pub fn downcast_ref<T: TypeTag>(&self) -> Result<&'a T, MessageError> {
         self.validate_metadata::<T>(ArgumentKind::Scalar)?;
 
         let ptr = self.inner_ref_ptr()?;
@@ -504,7 +996,7 @@ impl<'a> Argument<'a> {
     ///
     /// Violating this requirement is immediate **undefined behavior**.
     #[inline]
-    pub unsafe fn downcast_mut<T: 'static>(&self) -> Result<&'a mut T, MessageError> {
+    pub unsafe fn downcast_mut<T: TypeTag>(&self) -> Result<&'a mut T, MessageError> {
         self.validate_metadata::<T>(ArgumentKind::Scalar)?;
 
         let mut ptr = self.inner_mut_ptr()?;
@@ -516,9 +1008,38 @@ impl<'a> Argument<'a> {
         Ok(unsafe { ptr.as_mut() })
     }
 
+    /// Attempts to downcast a possibly null argument to `Option<&'a T>`, `None` if
+    /// the sender passed a null pointer.
+    #[inline]
+    pub fn downcast_opt_ref<T: TypeTag>(&self) -> Result<Option<&'a T>, MessageError> {
+        if self.is_null() {
+            self.validate_metadata::<T>(ArgumentKind::Scalar)?;
+            return Ok(None);
+        }
+
+        self.downcast_ref().map(Some)
+    }
+
+    /// Attempts to downcast a possibly null argument to `Option<&'a mut T>`, `None`
+    /// if the sender passed a null pointer.
+    ///
+    /// # Safety
+    ///
+    /// Same as `downcast_mut`.
+    #[inline]
+    pub unsafe fn downcast_opt_mut<T: TypeTag>(&self) -> Result<Option<&'a mut T>, MessageError> {
+        if self.is_null() {
+            self.validate_metadata::<T>(ArgumentKind::Scalar)?;
+            return Ok(None);
+        }
+
+        // SAFETY: The caller must uphold the safety contract of `downcast_mut`.
+        unsafe { self.downcast_mut() }.map(Some)
+    }
+
     /// Attempts to downcast the argument to a slice of type `&'a [T]`.
     #[inline]
-    pub fn downcast_slice<T: 'static>(&self) -> Result<&'a [T], MessageError> {
+    pub fn downcast_slice<T: TypeTag>(&self) -> Result<&'a [T], MessageError> {
         self.validate_metadata::<T>(ArgumentKind::Slice)?;
 
         let ptr = self.inner_ref_ptr()?;
@@ -546,7 +1067,7 @@ impl<'a> Argument<'a> {
     ///
     /// Violating this requirement is immediate **undefined behavior**.
     #[inline]
-    pub unsafe fn downcast_mut_slice<T: 'static>(&self) -> Result<&'a mut [T], MessageError> {
+    pub unsafe fn downcast_mut_slice<T: TypeTag>(&self) -> Result<&'a mut [T], MessageError> {
         self.validate_metadata::<T>(ArgumentKind::Slice)?;
 
         let ptr = self.inner_mut_ptr()?;
@@ -557,11 +1078,44 @@ impl<'a> Argument<'a> {
         // ensures the source was mutable.
         Ok(unsafe { slice::from_raw_parts_mut(ptr.as_ptr(), self.meta.len) })
     }
+
+    /// Attempts to downcast the argument to a C string, which must hold a single
+    /// nul, at its end.
+    #[inline]
+    pub fn downcast_cstr(&self) -> Result<&'a CStr, MessageError> {
+        self.validate_metadata::<u8>(ArgumentKind::CStr)?;
+
+        let ptr = self.inner_ref_ptr::<u8>()?;
+
+        // SAFETY: Same as `downcast_slice`, the bytes live for 'a.
+        let bytes = unsafe { slice::from_raw_parts(ptr.as_ptr(), self.meta.len) };
+        CStr::from_bytes_with_nul(bytes).map_err(|_| MessageError::InvalidCStr)
+    }
+
+    /// Attempts to downcast an out buffer received from the peer to a mutable slice
+    /// of type `&'a mut [T]`.
+    ///
+    /// The slice is as long as the capacity the peer declared, until `set_used`
+    /// shrinks it. It starts out zeroed, so bytes past the used length are zero.
+    ///
+    /// # Safety
+    ///
+    /// Same as `downcast_mut_slice`. The buffer is owned by the argument, the slice
+    /// must not outlive it.
+    #[inline]
+    pub unsafe fn downcast_out_buffer<T: TypeTag>(&self) -> Result<&'a mut [T], MessageError> {
+        self.validate_metadata::<T>(ArgumentKind::OutBuffer)?;
+
+        let ptr = self.inner_mut_ptr()?;
+
+        // SAFETY: The caller must uphold the safety contract of `downcast_mut_slice`.
+        Ok(unsafe { slice::from_raw_parts_mut(ptr.as_ptr(), self.meta.len) })
+    }
 }
 
 impl Argument<'_> {
     pub fn update_from(&mut self, source: &Argument<'_>) -> Result<(), MessageError> {
-        if self.meta.type_id != source.meta.type_id {
+        if self.meta.type_tag != source.meta.type_tag {
             return Err(MessageError::ArgumentTypeMismatch);
         }
 
@@ -579,6 +1133,14 @@ impl Argument<'_> {
             });
         }
 
+        // The peer only sends back the used part of an out buffer
+        if self.meta.kind == ArgumentKind::OutBuffer
+            && source.meta.kind == ArgumentKind::OutBuffer
+            && source.meta.len <= self.meta.len
+        {
+            self.meta.len = source.meta.len;
+        }
+
         if self.meta.len != source.meta.len {
             return Err(MessageError::ArgumentTypeLengthMismatch {
                 expect: self.meta.len,
@@ -592,12 +1154,37 @@ impl Argument<'_> {
         }
 
         let (src_ptr, dst_ptr) = match (&source.value, &mut self.value) {
+            // Nowhere to write back to
+            (_, ArgumentValue::Null) => {
+                return Ok(());
+            }
+            // The peer wrote into the shared region directly, nothing to copy
+            (ArgumentValue::Shared(src_offset, ..), ArgumentValue::Shared(dst_offset, ..))
+                if src_offset == dst_offset =>
+            {
+                return Ok(());
+            }
             (ArgumentValue::Val(src_bytes), ArgumentValue::Val(dst_bytes)) => {
                 assert!(total_size <= INLINED_DATA_SIZE);
                 (src_bytes.0.as_ptr(), dst_bytes.0.as_mut_ptr())
             }
+            (ArgumentValue::Spilled(src_chunks), ArgumentValue::Spilled(dst_chunks)) => {
+                (src_chunks.as_ptr().cast(), dst_chunks.as_mut_ptr().cast())
+            }
+            (
+                ArgumentValue::Ref(src_ptr, _) | ArgumentValue::Mut(src_ptr, _),
+                ArgumentValue::Reserved(dst_ptr, _),
+            ) => (src_ptr.as_ptr().cast_const(), dst_ptr.as_ptr()),
+            (ArgumentValue::Buffer(src_chunks), ArgumentValue::Reserved(dst_ptr, _)) => {
+                (src_chunks.as_ptr().cast(), dst_ptr.as_ptr())
+            }
             (ArgumentValue::Ref(src_ptr, _), ArgumentValue::Mut(dst_ptr, _))
             | (ArgumentValue::Mut(src_ptr, _), ArgumentValue::Mut(dst_ptr, _)) => {
+                // The peer's pointers mean nothing here
+                if let Some(fields) = &self.fields {
+                    unsafe { copy_keeping_fields(src_ptr.as_ptr(), *dst_ptr, total_size, fields) };
+                    return Ok(());
+                }
                 (src_ptr.as_ptr().cast_const(), dst_ptr.as_ptr())
             }
             (_, ArgumentValue::Ref(..)) => {
@@ -615,67 +1202,143 @@ impl Argument<'_> {
     }
 }
 
-impl BytewiseReadOwned for Argument<'_> {
-    fn read_from<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
-        // Read argument
-        let mut argument = unsafe { *reader.read_ref::<Argument>()? };
-
-        if matches!(argument.value, ArgumentValue::Val(_)) {
-            return Ok(argument);
-        }
+impl<'x> Argument<'x> {
+    /// Reads a descriptor and the value following it, the value is borrowed from
+    /// the buffer mutably if `mutable`.
+    fn read_with<'a, R: BytewiseReader<'a>>(
+        reader: &mut R,
+        mutable: bool,
+    ) -> Result<Self, BytewiseError> {
+        // Read argument descriptor
+        let descriptor = unsafe { *reader.read_ref::<ArgumentDescriptor>()? };
+        let meta = descriptor.metadata()?;
+        let flag = ArgumentFlag::from_bits_retain(descriptor.flag.get());
 
         // Read argument value
-        // TODO: This `read_raw` call requires argument value impl `Copy` trait
-        let data_ptr = unsafe { reader.read_raw(argument.total_size(), argument.meta.type_align)? };
-        let value = ArgumentValue::Ref(data_ptr, PhantomData);
-
-        // Update argument value
-        argument.value = value;
-
-        Ok(argument)
-    }
+        let value = match descriptor.storage {
+            STORAGE_INLINE => {
+                if meta.type_align > INLINED_DATA_ALIGN {
+                    return Err(BytewiseError::InvalidEncoding("inlined argument"));
+                }
 
-    fn read_from_mut<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
-        // Read argument metadata
-        let mut argument = unsafe { *reader.read_ref::<Argument>()? };
+                let data_ptr = unsafe { reader.read_raw(meta.type_size, meta.type_align)? };
+                if meta.type_size > INLINED_DATA_SIZE {
+                    ArgumentValue::Spilled(unsafe {
+                        InlineBytes::spill(data_ptr.as_ptr(), meta.type_size)
+                    })
+                } else {
+                    let mut bytes = InlineBytes([0u8; INLINED_DATA_SIZE]);
+                    unsafe {
+                        ptr::copy_nonoverlapping(
+                            data_ptr.as_ptr(),
+                            bytes.0.as_mut_ptr(),
+                            meta.type_size,
+                        );
+                    }
+                    ArgumentValue::Val(bytes)
+                }
+            }
+            STORAGE_DATA => {
+                // TODO: This `read_raw` call requires argument value impl `Copy` trait
+                let total_size = meta.type_size * meta.len;
+                let data_ptr = unsafe { reader.read_raw(total_size, meta.type_align)? };
+                match mutable {
+                    true => ArgumentValue::Mut(data_ptr, PhantomData),
+                    false => ArgumentValue::Ref(data_ptr, PhantomData),
+                }
+            }
+            // The peer's pointer means nothing here, the argument needs resolving
+            STORAGE_SHARED => ArgumentValue::Shared(descriptor.offset()?, None, PhantomData),
+            STORAGE_NULL => ArgumentValue::Null,
+            STORAGE_RESERVED => {
+                let total_size = meta.type_size * meta.len;
+                if meta.type_align > INLINED_DATA_ALIGN || total_size > MAX_RESERVED_SIZE {
+                    return Err(BytewiseError::InvalidEncoding("reserved argument"));
+                }
 
-        // Inlined data was already read from the buffer
-        if matches!(argument.value, ArgumentValue::Val(_)) {
-            return Ok(argument);
-        }
+                let chunks = vec![
+                    InlineBytes([0u8; INLINED_DATA_SIZE]);
+                    total_size.div_ceil(INLINED_DATA_SIZE)
+                ];
+                ArgumentValue::Buffer(chunks.into_boxed_slice())
+            }
+            _ => return Err(BytewiseError::InvalidEncoding("argument storage")),
+        };
 
-        // Read argument value
-        let data_ptr = unsafe {
-            // TODO: `read_raw` call requires argument value impl `Copy` trait
-            reader.read_raw(argument.total_size(), argument.meta.type_align)?
+        // Pointer fields are rebuilt in place, which takes a mutable value
+        let fields = match (descriptor.field_count.get(), &value) {
+            (0, _) => None,
+            (count, ArgumentValue::Mut(ptr, _)) => {
+                Some(unsafe { read_fields(reader, *ptr, meta.type_size * meta.len, count.into())? })
+            }
+            _ => return Err(BytewiseError::InvalidEncoding("pointer fields")),
         };
-        let value = ArgumentValue::Mut(data_ptr, PhantomData);
 
-        // Update argument value
-        argument.value = value;
+        Ok(Self {
+            meta,
+            value,
+            flag,
+            fields,
+        })
+    }
+}
 
-        Ok(argument)
+impl BytewiseReadOwned for Argument<'_> {
+    fn read_from<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
+        Self::read_with(reader, false)
+    }
+
+    fn read_from_mut<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
+        Self::read_with(reader, true)
     }
 }
 
 impl BytewiseWrite for Argument<'_> {
     fn write_to<W: BytewiseWriter>(&self, writer: &mut W) -> Result<(), BytewiseError> {
-        // Write argument metadata
-        writer.write_ref(self)?;
+        let (storage, offset) = match self.value {
+            ArgumentValue::Val(_) | ArgumentValue::Spilled(_) => (STORAGE_INLINE, 0),
+            ArgumentValue::Ref(..) | ArgumentValue::Mut(..) | ArgumentValue::Buffer(_) => {
+                (STORAGE_DATA, 0)
+            }
+            ArgumentValue::Shared(offset, _, _) => (STORAGE_SHARED, offset),
+            ArgumentValue::Null => (STORAGE_NULL, 0),
+            ArgumentValue::Reserved(..) => (STORAGE_RESERVED, 0),
+        };
+
+        // Write argument descriptor
+        let descriptor = ArgumentDescriptor::new(
+            &self.meta,
+            self.flag,
+            storage,
+            offset,
+            self.pointer_fields().len(),
+        )?;
+        writer.write_ref(&descriptor)?;
 
         // Write argument value
-        match self.value {
-            ArgumentValue::Val(_) => {
-                // Inlined data was already written to the buffer
-            }
-            ArgumentValue::Ref(ptr, _) => unsafe {
-                // TODO: `write_raw` requires argument value impl `Copy` trait
-                writer.write_raw(ptr, self.total_size(), self.type_align())?;
+        match &self.value {
+            ArgumentValue::Val(bytes) => unsafe {
+                let ptr = ptr::NonNull::from(&bytes.0).cast();
+                writer.write_raw(ptr, self.type_size(), self.type_align())?;
             },
-            ArgumentValue::Mut(ptr, _) => unsafe {
+            ArgumentValue::Spilled(chunks) => unsafe {
+                let ptr = ptr::NonNull::from(&chunks[..]).cast();
+                writer.write_raw(ptr, self.type_size(), self.type_align())?;
+            },
+            ArgumentValue::Ref(ptr, _) | ArgumentValue::Mut(ptr, _) => unsafe {
                 // TODO: `write_raw` requires argument value impl `Copy` trait
+                writer.write_raw(*ptr, self.total_size(), self.type_align())?;
+                write_fields(writer, *ptr, self.pointer_fields())?;
+            },
+            ArgumentValue::Buffer(chunks) => unsafe {
+                // Sent back like any slice, up to the used length
+                let ptr = ptr::NonNull::from(&chunks[..]).cast();
                 writer.write_raw(ptr, self.total_size(), self.type_align())?;
             },
+            ArgumentValue::Shared(..) => {
+                // Shared data stays in place, only its offset goes out
+            }
+            ArgumentValue::Null | ArgumentValue::Reserved(..) => {}
         }
 
         Ok(())
@@ -685,6 +1348,18 @@ impl BytewiseWrite for Argument<'_> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ipc::bytewise::BytewiseBuffer;
+    use std::mem::{MaybeUninit, offset_of};
+
+    /// Where the storage of an encoded argument is recorded.
+    const STORAGE: usize = offset_of!(ArgumentDescriptor, storage);
+    /// Where the value of an encoded argument starts, right after its descriptor.
+    const VALUE: usize = size_of::<ArgumentDescriptor>();
+
+    /// Returns the bytes of `field` within an encoded descriptor.
+    fn field(buf: &[u8], offset: usize, len: usize) -> &[u8] {
+        &buf[offset..offset + len]
+    }
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     struct ZeroSizedStruct;
@@ -700,6 +1375,15 @@ mod tests {
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     struct AlignedData(u32);
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Extent {
+        width: usize,
+        height: usize,
+        depth: usize,
+    }
+
+    crate::type_tags!(ZeroSizedStruct, Point, AlignedData, Extent);
+
     #[test]
     fn test_empty_downcast() {
         let argument = Argument::empty();
@@ -1061,7 +1745,7 @@ mod tests {
         let argument = Argument::from_mut_slice(orig_value.as_mut_slice(), ArgumentFlag::default());
         println!("argument:  {:#?}", argument);
 
-        let result = argument.downcast::<&[i32]>();
+        let result = argument.downcast::<[i32; 4]>();
         println!("result:    {:?}", result);
 
         assert!(matches!(result, Err(MessageError::ArgumentTypeMismatch)));
@@ -1236,4 +1920,318 @@ mod tests {
         println!("Update failed as expected: {:?}", result.unwrap_err());
         assert_eq!(dst_data, [99, 99], "Data should not be modified on failure");
     }
+
+    #[test]
+    fn test_descriptor_wire_format() {
+        let mut buf = vec![0u8; 256];
+
+        let values = [1u16, 2, 3];
+        let argument = Argument::from_slice(&values, ArgumentFlag::ARG_OUT);
+        argument
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        assert_eq!(size_of::<ArgumentDescriptor>(), 48);
+        assert_eq!(
+            field(&buf, offset_of!(ArgumentDescriptor, type_tag), 8),
+            u16::TYPE_TAG.to_le_bytes()
+        );
+        assert_eq!(
+            field(&buf, offset_of!(ArgumentDescriptor, type_size), 8),
+            2u64.to_le_bytes()
+        );
+        assert_eq!(
+            field(&buf, offset_of!(ArgumentDescriptor, len), 8),
+            3u64.to_le_bytes()
+        );
+        assert_eq!(
+            field(&buf, offset_of!(ArgumentDescriptor, type_align), 4),
+            2u32.to_le_bytes()
+        );
+        assert_eq!(
+            field(&buf, offset_of!(ArgumentDescriptor, flag), 4),
+            ArgumentFlag::ARG_OUT.bits().to_le_bytes()
+        );
+        assert_eq!(
+            buf[offset_of!(ArgumentDescriptor, kind)],
+            ArgumentKind::Slice as u8
+        );
+        assert_eq!(buf[STORAGE], STORAGE_DATA);
+
+        let received = Argument::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert_eq!(received.downcast_slice::<u16>(), Ok(&values[..]));
+        assert_eq!(received.flag(), ArgumentFlag::ARG_OUT);
+
+        // Unknown storage is rejected rather than misread
+        buf[STORAGE] = 0xFF;
+        assert!(matches!(
+            Argument::read_from(&mut BytewiseBuffer::new(&buf)),
+            Err(BytewiseError::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_inline_value_roundtrip() {
+        let mut buf = vec![0u8; 256];
+
+        let argument = Argument::from_value(0x1122_3344_5566_7788u64, ArgumentFlag::ARG_IN);
+        argument
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        // Only the value itself follows the descriptor
+        assert_eq!(buf[STORAGE], STORAGE_INLINE);
+        assert_eq!(
+            field(&buf, VALUE, 8),
+            0x1122_3344_5566_7788u64.to_ne_bytes()
+        );
+
+        let received = Argument::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert_eq!(received.downcast::<u64>(), Ok(0x1122_3344_5566_7788));
+    }
+
+    #[test]
+    fn test_spilled_value_roundtrip() {
+        let mut buf = vec![0u8; 256];
+
+        let extent = Extent {
+            width: 1,
+            height: 2,
+            depth: 3,
+        };
+        let argument = Argument::from_value(extent, ArgumentFlag::ARG_OUT);
+        assert_eq!(argument.downcast::<Extent>(), Ok(extent));
+        assert_eq!(
+            argument.downcast_ref::<Extent>(),
+            Err(MessageError::IllegalBorrowOfInlined)
+        );
+        argument
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        // Sent like any inlined value, right after the descriptor
+        assert_eq!(buf[STORAGE], STORAGE_INLINE);
+        assert_eq!(
+            field(&buf, VALUE + offset_of!(Extent, width), size_of::<usize>()),
+            1usize.to_ne_bytes()
+        );
+        assert_eq!(
+            field(&buf, VALUE + offset_of!(Extent, depth), size_of::<usize>()),
+            3usize.to_ne_bytes()
+        );
+
+        let received = Argument::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert_eq!(received.downcast::<Extent>(), Ok(extent));
+
+        let mut dst_arg = Argument::from_value(
+            Extent {
+                width: 0,
+                height: 0,
+                depth: 0,
+            },
+            ArgumentFlag::ARG_OUT,
+        );
+        dst_arg
+            .update_from(&received)
+            .expect("Update operation should succeed");
+        assert_eq!(dst_arg.downcast::<Extent>(), Ok(extent));
+    }
+
+    #[test]
+    fn test_cstr_roundtrip() {
+        let mut buf = vec![0u8; 256];
+
+        let argument = Argument::from_cstr(c"no error", ArgumentFlag::ARG_OUT);
+        assert_eq!(argument.len(), 9);
+        argument
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        let received = Argument::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert_eq!(received.downcast_cstr(), Ok(c"no error"));
+        assert_eq!(
+            received.downcast_slice::<u8>(),
+            Err(MessageError::ArgumentIsNotSlice)
+        );
+
+        // The nul must end the string
+        let bytes = b"no\0error";
+        let argument = Argument::from_slice(bytes, ArgumentFlag::ARG_IN);
+        assert_eq!(
+            argument.downcast_cstr(),
+            Err(MessageError::ArgumentIsNotCStr)
+        );
+        let mut argument = argument;
+        argument.meta.kind = ArgumentKind::CStr;
+        assert_eq!(argument.downcast_cstr(), Err(MessageError::InvalidCStr));
+    }
+
+    #[test]
+    fn test_out_buffer_update_from() {
+        let mut buf = vec![0u8; 256];
+        let mut name = MaybeUninit::<[u8; 16]>::uninit();
+
+        let mut argument = unsafe {
+            Argument::from_out_buffer(name.as_mut_ptr().cast::<u8>(), 16, ArgumentFlag::ARG_OUT)
+        };
+        assert_eq!(
+            unsafe { argument.downcast_out_buffer::<u8>() },
+            Err(MessageError::UnfilledOutBuffer)
+        );
+        let mut writer = BytewiseBuffer::new(&mut buf);
+        argument.write_to(&mut writer).unwrap();
+
+        // Only the capacity goes out, the buffer is never read
+        assert_eq!(writer.written_bytes(), size_of::<ArgumentDescriptor>());
+        assert_eq!(buf[STORAGE], STORAGE_RESERVED);
+
+        // The peer fills part of the room it allocated
+        let mut received = Argument::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        let out = unsafe { received.downcast_out_buffer::<u8>() }.unwrap();
+        assert_eq!(out, [0; 16]);
+        out[..4].copy_from_slice(b"gpu\0");
+        assert_eq!(
+            received.set_used(17),
+            Err(MessageError::OutBufferOverflow {
+                capacity: 16,
+                used: 17
+            })
+        );
+        received.set_used(4).unwrap();
+
+        // Only the used part comes back
+        let mut response = vec![0u8; 256];
+        let mut writer = BytewiseBuffer::new(&mut response);
+        received.write_to(&mut writer).unwrap();
+        assert_eq!(writer.written_bytes(), VALUE + 4);
+        let filled = Argument::read_from(&mut BytewiseBuffer::new(&response)).unwrap();
+
+        argument.update_from(&filled).unwrap();
+        assert_eq!(argument.len(), 4);
+        drop(argument);
+        let name = unsafe { slice::from_raw_parts(name.as_ptr().cast::<u8>(), 4) };
+        assert_eq!(name, b"gpu\0");
+
+        let mut scalar = Argument::from_value(1u8, ArgumentFlag::ARG_OUT);
+        assert_eq!(
+            scalar.set_used(0),
+            Err(MessageError::ArgumentIsNotOutBuffer)
+        );
+    }
+
+    #[test]
+    fn test_out_buffer_reserve_limit() {
+        let mut buf = vec![0u8; 256];
+
+        let argument = unsafe {
+            Argument::from_out_buffer(
+                ptr::NonNull::<u64>::dangling().as_ptr(),
+                MAX_RESERVED_SIZE / size_of::<u64>() + 1,
+                ArgumentFlag::ARG_OUT,
+            )
+        };
+        argument
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        // The peer does not get to allocate whatever it asks for
+        assert!(matches!(
+            Argument::read_from(&mut BytewiseBuffer::new(&buf)),
+            Err(BytewiseError::InvalidEncoding("reserved argument"))
+        ));
+    }
+
+    #[test]
+    fn test_null_roundtrip() {
+        let mut buf = vec![0u8; 256];
+
+        let argument = unsafe { Argument::from_ptr(ptr::null::<Point>(), ArgumentFlag::ARG_IN) };
+        assert!(argument.is_null());
+        argument
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        // Nothing follows the descriptor
+        assert_eq!(buf[STORAGE], STORAGE_NULL);
+        assert!(buf[VALUE..].iter().all(|&byte| byte == 0));
+
+        let received = Argument::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert!(received.is_null());
+        assert_eq!(received.downcast_opt_ref::<Point>(), Ok(None));
+        assert_eq!(
+            received.downcast_ref::<Point>(),
+            Err(MessageError::NullArgument)
+        );
+        assert_eq!(
+            received.downcast::<Point>(),
+            Err(MessageError::NullArgument)
+        );
+        assert_eq!(
+            received.downcast_opt_ref::<u64>(),
+            Err(MessageError::ArgumentTypeMismatch)
+        );
+
+        let point = Point { x: 1, y: 2 };
+        let argument = Argument::from_opt_ref(Some(&point), ArgumentFlag::ARG_IN);
+        assert_eq!(argument.downcast_opt_ref::<Point>(), Ok(Some(&point)));
+    }
+
+    #[test]
+    fn test_null_update_from() {
+        let src_value = Point { x: 1, y: 2 };
+        let src_arg = Argument::from_ref(&src_value, ArgumentFlag::ARG_OUT);
+
+        let mut dst_arg = Argument::from_opt_mut::<Point>(None, ArgumentFlag::ARG_OUT);
+        assert_eq!(dst_arg.update_from(&src_arg), Ok(()));
+        assert!(dst_arg.is_null());
+    }
+
+    #[test]
+    fn test_shared_roundtrip() {
+        let mut region = [0u8; 64];
+        let mut buf = vec![0u8; 4096];
+
+        let data_ptr = region[16..].as_mut_ptr();
+        let argument = unsafe { Argument::from_shared(16, data_ptr, 8, ArgumentFlag::ARG_OUT) };
+        argument
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        let mut received = Argument::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert_eq!(received.shared_offset(), Some(16));
+        assert_eq!(
+            received.downcast_slice::<u8>(),
+            Err(MessageError::UnresolvedShared)
+        );
+
+        // The peer maps the same region elsewhere
+        let mut mirror = [0u8; 64];
+        unsafe { received.resolve_shared(mirror.as_mut_ptr(), mirror.len()) }.unwrap();
+        unsafe { received.downcast_mut_slice::<u8>() }
+            .unwrap()
+            .fill(0xAB);
+        assert_eq!(&mirror[16..24], &[0xAB; 8]);
+
+        // Shared output needs no copy back
+        let mut argument = argument;
+        assert_eq!(argument.update_from(&received), Ok(()));
+    }
+
+    #[test]
+    fn test_shared_out_of_bounds() {
+        let mut region = [0u8; 64];
+
+        let mut argument =
+            unsafe { Argument::from_shared(60, region.as_mut_ptr(), 8, ArgumentFlag::ARG_IN) };
+        let result = unsafe { argument.resolve_shared(region.as_mut_ptr(), region.len()) };
+
+        assert_eq!(
+            result,
+            Err(MessageError::SharedOutOfBounds {
+                offset: 60,
+                len: 8,
+                region_len: 64
+            })
+        );
+    }
 }
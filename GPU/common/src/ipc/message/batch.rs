@@ -0,0 +1,334 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+//! Several messages packed into a single frame.
+//!
+//! A batch is a header, flagged `BATCH`, followed by its messages. A batch of
+//! one is sent as the plain message, and a plain message reads as a batch of
+//! one, so peers may always receive batches.
+
+use crate::ipc::bytewise::{
+    BytewiseError, BytewiseRead, BytewiseReadOwned, BytewiseReader, BytewiseWrite, BytewiseWriter,
+};
+
+use super::{
+    Argument, Request, RequestFlag, RequestMetadata, Response, ResponseFlag, ResponseMetadata,
+    ResponseStatus,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct RequestBatch<'a> {
+    requests: Vec<Request<'a>>,
+}
+
+impl<'a> RequestBatch<'a> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_requests<I>(requests: I) -> Self
+    where
+        I: IntoIterator<Item = Request<'a>>,
+    {
+        Self {
+            requests: Vec::from_iter(requests),
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, request: Request<'a>) {
+        self.requests.push(request);
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    #[inline]
+    pub fn requests(&self) -> &[Request<'a>] {
+        self.requests.as_slice()
+    }
+
+    #[inline]
+    pub fn requests_mut(&mut self) -> &mut [Request<'a>] {
+        self.requests.as_mut_slice()
+    }
+
+    #[inline]
+    pub fn into_requests(self) -> Vec<Request<'a>> {
+        self.requests
+    }
+
+    fn read_with<'b, R: BytewiseReader<'b>>(
+        reader: &mut R,
+        read_arg: fn(&mut R) -> Result<Argument<'a>, BytewiseError>,
+    ) -> Result<Self, BytewiseError> {
+        let metadata = RequestMetadata::read_ref(reader)?;
+        if !metadata.flag().contains(RequestFlag::BATCH) {
+            return Ok(Self {
+                requests: vec![Request::read_with(metadata, reader, read_arg)?],
+            });
+        }
+
+        let mut requests = Vec::with_capacity(metadata.arg_count());
+        for _ in 0..metadata.arg_count() {
+            let metadata = RequestMetadata::read_ref(reader)?;
+            requests.push(Request::read_with(metadata, reader, read_arg)?);
+        }
+
+        Ok(Self { requests })
+    }
+}
+
+impl BytewiseReadOwned for RequestBatch<'_> {
+    fn read_from<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
+        Self::read_with(reader, Argument::read_from)
+    }
+
+    fn read_from_mut<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
+        Self::read_with(reader, Argument::read_from_mut)
+    }
+}
+
+impl BytewiseWrite for RequestBatch<'_> {
+    fn write_to<W: BytewiseWriter>(&self, writer: &mut W) -> Result<(), BytewiseError> {
+        if let [request] = self.requests.as_slice() {
+            return request.write_to(writer);
+        }
+
+        let metadata = RequestMetadata::new(0, 0, self.requests.len(), RequestFlag::BATCH)?;
+        metadata.write_to(writer)?;
+
+        for request in &self.requests {
+            request.write_to(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ResponseBatch<'a> {
+    responses: Vec<Response<'a>>,
+}
+
+impl<'a> ResponseBatch<'a> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_responses<I>(responses: I) -> Self
+    where
+        I: IntoIterator<Item = Response<'a>>,
+    {
+        Self {
+            responses: Vec::from_iter(responses),
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, response: Response<'a>) {
+        self.responses.push(response);
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    #[inline]
+    pub fn responses(&self) -> &[Response<'a>] {
+        self.responses.as_slice()
+    }
+
+    #[inline]
+    pub fn into_responses(self) -> Vec<Response<'a>> {
+        self.responses
+    }
+
+    /// Reads a batch, returning the offset of each response relative to where
+    /// the reader started.
+    pub(crate) fn read_offsets<'b, R: BytewiseReader<'b>>(
+        reader: &mut R,
+    ) -> Result<Vec<(usize, Response<'a>)>, BytewiseError> {
+        let start = reader.read_bytes();
+        let metadata = ResponseMetadata::read_ref(reader)?;
+        if !metadata.flag().contains(ResponseFlag::BATCH) {
+            let response = Response::read_with(metadata, reader, Argument::read_from)?;
+            return Ok(vec![(0, response)]);
+        }
+
+        let mut responses = Vec::with_capacity(metadata.arg_count());
+        for _ in 0..metadata.arg_count() {
+            let offset = reader.read_bytes() - start;
+            let metadata = ResponseMetadata::read_ref(reader)?;
+            let response = Response::read_with(metadata, reader, Argument::read_from)?;
+            responses.push((offset, response));
+        }
+
+        Ok(responses)
+    }
+
+    fn read_with<'b, R: BytewiseReader<'b>>(
+        reader: &mut R,
+        read_arg: fn(&mut R) -> Result<Argument<'a>, BytewiseError>,
+    ) -> Result<Self, BytewiseError> {
+        let metadata = ResponseMetadata::read_ref(reader)?;
+        if !metadata.flag().contains(ResponseFlag::BATCH) {
+            return Ok(Self {
+                responses: vec![Response::read_with(metadata, reader, read_arg)?],
+            });
+        }
+
+        let mut responses = Vec::with_capacity(metadata.arg_count());
+        for _ in 0..metadata.arg_count() {
+            let metadata = ResponseMetadata::read_ref(reader)?;
+            responses.push(Response::read_with(metadata, reader, read_arg)?);
+        }
+
+        Ok(Self { responses })
+    }
+}
+
+impl BytewiseReadOwned for ResponseBatch<'_> {
+    fn read_from<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
+        Self::read_with(reader, Argument::read_from)
+    }
+
+    fn read_from_mut<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
+        Self::read_with(reader, Argument::read_from_mut)
+    }
+}
+
+impl BytewiseWrite for ResponseBatch<'_> {
+    fn write_to<W: BytewiseWriter>(&self, writer: &mut W) -> Result<(), BytewiseError> {
+        if let [response] = self.responses.as_slice() {
+            return response.write_to(writer);
+        }
+
+        let metadata = ResponseMetadata::new(
+            0,
+            0,
+            self.responses.len(),
+            ResponseFlag::BATCH,
+            ResponseStatus::Ok,
+        )?;
+        metadata.write_to(writer)?;
+
+        for response in &self.responses {
+            response.write_to(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ipc::{bytewise::BytewiseBuffer, message::ArgumentFlag};
+
+    use super::*;
+
+    #[test]
+    fn test_request_batch_roundtrip() {
+        let mut buf = vec![0u8; 4096];
+
+        let mut posted = Request::with_arg(0x1, Argument::from_value(1u8, ArgumentFlag::ARG_IN));
+        posted.set_flag(RequestFlag::NO_REPLY);
+        let batch = RequestBatch::with_requests([
+            posted,
+            Request::empty(0x2),
+            Request::with_arg(0x3, Argument::from_value(3u64, ArgumentFlag::ARG_IN)),
+        ]);
+        batch.write_to(&mut BytewiseBuffer::new(&mut buf)).unwrap();
+
+        let recv_batch = RequestBatch::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert_eq!(recv_batch.len(), 3);
+        for (send_req, recv_req) in batch.requests().iter().zip(recv_batch.requests()) {
+            assert_eq!(recv_req.request_id(), send_req.request_id());
+            assert_eq!(recv_req.method_id(), send_req.method_id());
+            assert_eq!(recv_req.flag(), send_req.flag());
+            assert_eq!(recv_req.argc(), send_req.argc());
+        }
+        assert_eq!(
+            recv_batch.requests()[2].args()[0].downcast::<u64>(),
+            Ok(3u64)
+        );
+    }
+
+    #[test]
+    fn test_single_request_batch_is_plain() {
+        let mut buf = vec![0u8; 4096];
+
+        let request = Request::with_arg(0x1, Argument::from_value(7u32, ArgumentFlag::ARG_IN));
+        RequestBatch::with_requests([request.clone()])
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        let recv_req = Request::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert_eq!(recv_req.request_id(), request.request_id());
+        assert_eq!(recv_req.args()[0].downcast::<u32>(), Ok(7u32));
+
+        request
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        let recv_batch = RequestBatch::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert_eq!(recv_batch.len(), 1);
+        assert_eq!(recv_batch.requests()[0].request_id(), request.request_id());
+    }
+
+    #[test]
+    fn test_response_batch_offsets() {
+        let mut buf = vec![0u8; 4096];
+
+        let batch = ResponseBatch::with_responses((1..=3u64).map(|request_id| {
+            let request = Request::with_arg(0x1, Argument::from_value(0u8, ArgumentFlag::ARG_OUT));
+            let mut response = Response::with_request(
+                &request,
+                Argument::from_value(request_id * 10, ArgumentFlag::ARG_OUT),
+            );
+            response.request_id = request_id;
+            response
+        }));
+        batch.write_to(&mut BytewiseBuffer::new(&mut buf)).unwrap();
+
+        let recv_batch = ResponseBatch::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert_eq!(recv_batch.len(), 3);
+
+        let offsets = ResponseBatch::read_offsets(&mut BytewiseBuffer::new(&buf)).unwrap();
+        for (request_id, (offset, response)) in (1..=3u64).zip(offsets) {
+            assert_eq!(response.request_id(), request_id);
+
+            let single = Response::read_from(&mut BytewiseBuffer::new(&buf[offset..])).unwrap();
+            assert_eq!(single.request_id(), request_id);
+            assert_eq!(single.ret_value().downcast::<u64>(), Ok(request_id * 10));
+        }
+    }
+}
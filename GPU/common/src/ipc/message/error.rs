@@ -14,11 +14,16 @@
 
 use thiserror::Error;
 
+use super::ResponseStatus;
+
 #[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
 pub enum MessageError {
     #[error("Request id mismatch (expect: {expect}, actual: {actual})")]
     RequestIdMismatch { expect: u64, actual: u64 },
 
+    #[error("Request failed on the server: {0}")]
+    RequestFailed(ResponseStatus),
+
     #[error("Argument count mismatch (expect: {expect}, actual: {actual})")]
     ArgumentCountMismatch { expect: usize, actual: usize },
 
@@ -43,6 +48,18 @@ pub enum MessageError {
     #[error("Attempted to downcast non-slice argument to slice")]
     ArgumentIsNotSlice,
 
+    #[error("Attempted to downcast non-string argument to string")]
+    ArgumentIsNotCStr,
+
+    #[error("Attempted to downcast non-out-buffer argument to out buffer")]
+    ArgumentIsNotOutBuffer,
+
+    #[error("String argument is not nul-terminated, or holds an inner nul")]
+    InvalidCStr,
+
+    #[error("Out buffer overflow (capacity: {capacity}, used: {used})")]
+    OutBufferOverflow { capacity: usize, used: usize },
+
     #[error("Attempted to access unaligned data")]
     UnalignedAccess,
 
@@ -51,4 +68,38 @@ pub enum MessageError {
 
     #[error("Attempted to reference inlined data")]
     IllegalBorrowOfInlined,
+
+    #[error("Attempted to access a null argument")]
+    NullArgument,
+
+    #[error("Attempted to access shared data before resolving it")]
+    UnresolvedShared,
+
+    #[error("Attempted to access an out buffer before the peer filled it")]
+    UnfilledOutBuffer,
+
+    #[error("Shared data out of bounds (offset: {offset}, len: {len}, region: {region_len})")]
+    SharedOutOfBounds {
+        offset: usize,
+        len: usize,
+        region_len: usize,
+    },
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeError {
+    #[error("Invalid handshake magic {actual:#x}")]
+    InvalidMagic { actual: u32 },
+
+    #[error("Endianness mismatch")]
+    EndiannessMismatch,
+
+    #[error("Pointer width mismatch (local: {local}, remote: {remote})")]
+    PointerWidthMismatch { local: u8, remote: u8 },
+
+    #[error("Protocol version mismatch (local: {local}, remote: {remote})")]
+    ProtocolVersionMismatch { local: u32, remote: u32 },
+
+    #[error("Api catalog mismatch (local: {local:#018x}, remote: {remote:#018x})")]
+    ApiCatalogMismatch { local: u64, remote: u64 },
 }
@@ -0,0 +1,265 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use bitflags::bitflags;
+
+use crate::{
+    ipc::bytewise::{
+        BytewiseError, BytewiseRead, BytewiseReadOwned, BytewiseReader, BytewiseWrite,
+        BytewiseWriter,
+    },
+    utils::api_name::API_CATALOG_HASH,
+};
+
+use super::HandshakeError;
+
+/// Version of the wire protocol, bumped on every incompatible message layout change.
+pub const PROTOCOL_VERSION: u32 = 13;
+
+/// Marks a handshake message, "XGHS".
+const HANDSHAKE_MAGIC: u32 = u32::from_le_bytes(*b"XGHS");
+
+mod endianness {
+    pub const LITTLE: u8 = 1;
+    pub const BIG: u8 = 2;
+
+    pub const NATIVE: u8 = if cfg!(target_endian = "little") {
+        LITTLE
+    } else {
+        BIG
+    };
+}
+
+bitflags! {
+    /// Optional protocol features, both sides use the ones they have in common.
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct HandshakeFeatures: u64 {
+        /// Frame payloads above a threshold may be compressed.
+        const COMPRESSION = 1 << 0;
+
+        // Unknown bits sent by a newer peer are kept and masked off by negotiation
+        const _ = !0;
+    }
+}
+
+impl HandshakeFeatures {
+    /// Features supported by this build.
+    pub const SUPPORTED: Self = Self::COMPRESSION;
+
+    /// Features implemented by the framer, offered only if the framer in use does.
+    pub const FRAMER: Self = Self::COMPRESSION;
+}
+
+/// The first message exchanged in each direction of a connection.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handshake {
+    // Single bytes come first, they read the same on either endianness
+    endianness: u8,
+    pointer_width: u8,
+    _reserved: [u8; 2],
+    magic: u32,
+    protocol_version: u32,
+    _padding: u32,
+    api_catalog_hash: u64,
+    features: HandshakeFeatures,
+}
+
+impl Handshake {
+    /// Describes the calling process, offering `features`.
+    pub const fn local(features: HandshakeFeatures) -> Self {
+        Self {
+            endianness: endianness::NATIVE,
+            pointer_width: size_of::<usize>() as u8,
+            _reserved: [0; 2],
+            magic: HANDSHAKE_MAGIC,
+            protocol_version: PROTOCOL_VERSION,
+            _padding: 0,
+            api_catalog_hash: API_CATALOG_HASH,
+            features,
+        }
+    }
+
+    #[inline]
+    pub const fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    #[inline]
+    pub const fn api_catalog_hash(&self) -> u64 {
+        self.api_catalog_hash
+    }
+
+    #[inline]
+    pub const fn pointer_width(&self) -> u8 {
+        self.pointer_width
+    }
+
+    #[inline]
+    pub const fn features(&self) -> HandshakeFeatures {
+        self.features
+    }
+
+    #[cfg(test)]
+    pub(crate) const fn with_protocol_version(mut self, version: u32) -> Self {
+        self.protocol_version = version;
+        self
+    }
+
+    /// Checks that `remote` can talk to this side, returning the features both sides offer.
+    pub fn negotiate(&self, remote: &Handshake) -> Result<HandshakeFeatures, HandshakeError> {
+        if remote.endianness != self.endianness {
+            return Err(HandshakeError::EndiannessMismatch);
+        }
+        if remote.magic != HANDSHAKE_MAGIC {
+            return Err(HandshakeError::InvalidMagic {
+                actual: remote.magic,
+            });
+        }
+        if remote.pointer_width != self.pointer_width {
+            return Err(HandshakeError::PointerWidthMismatch {
+                local: self.pointer_width,
+                remote: remote.pointer_width,
+            });
+        }
+        if remote.protocol_version != self.protocol_version {
+            return Err(HandshakeError::ProtocolVersionMismatch {
+                local: self.protocol_version,
+                remote: remote.protocol_version,
+            });
+        }
+        if remote.api_catalog_hash != self.api_catalog_hash {
+            return Err(HandshakeError::ApiCatalogMismatch {
+                local: self.api_catalog_hash,
+                remote: remote.api_catalog_hash,
+            });
+        }
+
+        Ok(self.features & remote.features & HandshakeFeatures::SUPPORTED)
+    }
+}
+
+impl Default for Handshake {
+    fn default() -> Self {
+        Self::local(HandshakeFeatures::SUPPORTED)
+    }
+}
+
+impl BytewiseRead for Handshake {
+    fn read_ref<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<&'a Self, BytewiseError> {
+        unsafe { reader.read_ref() }
+    }
+}
+
+impl BytewiseReadOwned for Handshake {
+    fn read_from<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
+        Self::read_ref(reader).copied()
+    }
+
+    fn read_from_mut<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
+        Self::read_ref(reader).copied()
+    }
+}
+
+impl BytewiseWrite for Handshake {
+    fn write_to<W: BytewiseWriter>(&self, writer: &mut W) -> Result<(), BytewiseError> {
+        writer.write_ref(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ipc::bytewise::BytewiseBuffer;
+
+    use super::*;
+
+    #[test]
+    fn test_handshake_roundtrip() {
+        let mut buf = vec![0u8; 256];
+        let handshake = Handshake::default();
+
+        handshake
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+        let received = Handshake::read_from(&mut BytewiseBuffer::new(&mut buf)).unwrap();
+
+        assert_eq!(received, handshake);
+        assert_eq!(received.api_catalog_hash(), API_CATALOG_HASH);
+        assert_eq!(
+            handshake.negotiate(&received),
+            Ok(HandshakeFeatures::SUPPORTED)
+        );
+    }
+
+    #[test]
+    fn test_handshake_mismatch() {
+        let local = Handshake::default();
+
+        let remote = Handshake {
+            protocol_version: PROTOCOL_VERSION + 1,
+            ..local
+        };
+        assert_eq!(
+            local.negotiate(&remote),
+            Err(HandshakeError::ProtocolVersionMismatch {
+                local: PROTOCOL_VERSION,
+                remote: PROTOCOL_VERSION + 1,
+            })
+        );
+
+        let remote = Handshake {
+            api_catalog_hash: !API_CATALOG_HASH,
+            ..local
+        };
+        assert!(matches!(
+            local.negotiate(&remote),
+            Err(HandshakeError::ApiCatalogMismatch { .. })
+        ));
+
+        let remote = Handshake {
+            pointer_width: 4,
+            ..local
+        };
+        assert!(matches!(
+            local.negotiate(&remote),
+            Err(HandshakeError::PointerWidthMismatch { remote: 4, .. })
+        ));
+
+        let remote = Handshake {
+            endianness: !local.endianness,
+            magic: local.magic.swap_bytes(),
+            ..local
+        };
+        assert_eq!(
+            local.negotiate(&remote),
+            Err(HandshakeError::EndiannessMismatch)
+        );
+
+        let remote = Handshake { magic: 0, ..local };
+        assert_eq!(
+            local.negotiate(&remote),
+            Err(HandshakeError::InvalidMagic { actual: 0 })
+        );
+    }
+
+    #[test]
+    fn test_handshake_features() {
+        let local = Handshake::local(HandshakeFeatures::all());
+        let remote = Handshake::local(HandshakeFeatures::from_bits_retain(1 << 63));
+
+        // Bits unknown to this build are never negotiated
+        assert_eq!(local.negotiate(&remote), Ok(HandshakeFeatures::empty()));
+    }
+}
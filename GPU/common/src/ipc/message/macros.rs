@@ -12,7 +12,7 @@
  * See the Mulan PSL v2 for more details.
  */
 
-use super::{Argument, ArgumentFlag};
+use super::{Argument, ArgumentFlag, TypeTag};
 
 pub mod internal {
     use super::*;
@@ -23,31 +23,31 @@ pub mod internal {
         fn into_arg(self, flag: ArgumentFlag) -> Argument<'a>;
     }
 
-    impl<T: Copy + 'static> IntoArgument<'static> for ArgValue<T> {
+    impl<T: Copy + TypeTag> IntoArgument<'static> for ArgValue<T> {
         fn into_arg(self, flag: ArgumentFlag) -> Argument<'static> {
             Argument::from_value(self.0, flag)
         }
     }
 
-    impl<'a, T: 'static> IntoArgument<'a> for &'a T {
+    impl<'a, T: TypeTag> IntoArgument<'a> for &'a T {
         fn into_arg(self, flag: ArgumentFlag) -> Argument<'a> {
             Argument::from_ref(self, flag)
         }
     }
 
-    impl<'a, T: 'static> IntoArgument<'a> for &'a mut T {
+    impl<'a, T: TypeTag> IntoArgument<'a> for &'a mut T {
         fn into_arg(self, flag: ArgumentFlag) -> Argument<'a> {
             Argument::from_mut(self, flag)
         }
     }
 
-    impl<'a, T: 'static> IntoArgument<'a> for &'a [T] {
+    impl<'a, T: TypeTag> IntoArgument<'a> for &'a [T] {
         fn into_arg(self, flag: ArgumentFlag) -> Argument<'a> {
             Argument::from_slice(self, flag)
         }
     }
 
-    impl<'a, T: 'static> IntoArgument<'a> for &'a mut [T] {
+    impl<'a, T: TypeTag> IntoArgument<'a> for &'a mut [T] {
         fn into_arg(self, flag: ArgumentFlag) -> Argument<'a> {
             Argument::from_mut_slice(self, flag)
         }
@@ -63,8 +63,9 @@ pub mod internal {
         ///
         /// Specifically, for the entire duration of the lifetime `'a`:
         ///
-        /// 1.  **Validity**: The pointer must be non-null and point to a single, properly
-        ///     initialized value of type `T`. It must not be dangling.
+        /// 1.  **Validity**: The pointer must be null, or point to a single, properly
+        ///     initialized value of type `T`. It must not be dangling. A null pointer
+        ///     makes a null argument.
         /// 2.  **Alignment**: The pointer must be properly aligned for the type `T`.
         /// 3.  **Lifetime and Access**:
         ///     *   If `self` is a `*const T`, the memory it points to must be **valid for reads**
@@ -80,13 +81,13 @@ pub mod internal {
         unsafe fn into_arg(self, flag: ArgumentFlag) -> Argument<'a>;
     }
 
-    impl<'a, T: 'static> UnsafeIntoArgument<'a> for *const T {
+    impl<'a, T: TypeTag> UnsafeIntoArgument<'a> for *const T {
         unsafe fn into_arg(self, flag: ArgumentFlag) -> Argument<'a> {
             unsafe { Argument::from_ptr(self, flag) }
         }
     }
 
-    impl<'a, T: 'static> UnsafeIntoArgument<'a> for *mut T {
+    impl<'a, T: TypeTag> UnsafeIntoArgument<'a> for *mut T {
         unsafe fn into_arg(self, flag: ArgumentFlag) -> Argument<'a> {
             unsafe { Argument::from_mut_ptr(self, flag) }
         }
@@ -15,6 +15,12 @@
 mod error;
 pub use error::*;
 
+mod type_tag;
+pub use type_tag::*;
+
+mod pointer_field;
+pub use pointer_field::*;
+
 mod argument;
 pub use argument::*;
 
@@ -24,4 +30,10 @@ pub use request::*;
 mod response;
 pub use response::*;
 
+mod handshake;
+pub use handshake::*;
+
+mod batch;
+pub use batch::*;
+
 pub mod macros;
@@ -0,0 +1,436 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::ptr;
+
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, LittleEndian, U32, U64};
+
+use crate::ipc::bytewise::{BytewiseError, BytewiseReader, BytewiseWriter};
+
+/// A pointer held in an argument's value, to `len` elements further in memory.
+///
+/// The sender copies the elements into the message right after the value, and
+/// the receiver points the field at its copy. Nested data only travels with the
+/// request, it is not written back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerField {
+    offset: usize,
+    type_size: usize,
+    type_align: usize,
+    len: usize,
+}
+
+impl PointerField {
+    /// A pointer `offset` bytes into the value, to `len` elements of `T`.
+    #[inline]
+    pub const fn new<T>(offset: usize, len: usize) -> Self {
+        Self {
+            offset,
+            type_size: size_of::<T>(),
+            type_align: align_of::<T>(),
+            len,
+        }
+    }
+
+    /// A pointer `offset` bytes into the value, to `len` untyped bytes aligned to
+    /// `align`, such as a kernel parameter.
+    #[inline]
+    pub const fn bytes(offset: usize, len: usize, align: usize) -> Self {
+        Self {
+            offset,
+            type_size: 1,
+            type_align: align,
+            len,
+        }
+    }
+
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.type_size == 0 || self.len == 0
+    }
+
+    #[inline]
+    pub const fn total_size(&self) -> usize {
+        self.type_size.saturating_mul(self.len)
+    }
+
+    /// Returns true if the pointer itself lies within a value of `value_len` bytes.
+    #[inline]
+    pub(super) fn fits_in(&self, value_len: usize) -> bool {
+        self.offset
+            .checked_add(size_of::<*mut u8>())
+            .is_some_and(|end| end <= value_len)
+    }
+
+    /// Reads the pointer out of the value at `value`.
+    ///
+    /// # Safety
+    ///
+    /// The field must fit in the value, see `fits_in`.
+    #[inline]
+    unsafe fn get(&self, value: ptr::NonNull<u8>) -> *mut u8 {
+        unsafe { value.add(self.offset).cast::<*mut u8>().read_unaligned() }
+    }
+
+    /// Stores `data` as the pointer of the value at `value`.
+    ///
+    /// # Safety
+    ///
+    /// The field must fit in the value, see `fits_in`.
+    #[inline]
+    unsafe fn set(&self, value: ptr::NonNull<u8>, data: *mut u8) {
+        unsafe {
+            value
+                .add(self.offset)
+                .cast::<*mut u8>()
+                .write_unaligned(data)
+        }
+    }
+}
+
+/// A type holding pointers to further memory, which are sent along with it.
+///
+/// Implement it with [`pointer_fields!`](crate::pointer_fields), and send values
+/// with `Argument::from_deep_ref` or `Argument::from_deep_mut`.
+pub trait PointerFields {
+    /// Returns the pointer fields of `self`, with the number of elements each
+    /// points to.
+    fn pointer_fields(&self) -> Vec<PointerField>;
+}
+
+/// Implements [`PointerFields`] for a struct, declaring each pointer field with
+/// its element type and a `usize` length expression over the value.
+///
+/// ```ignore
+/// pointer_fields!(CUlaunchConfig, |config| {
+///     attrs: [CUlaunchAttribute; config.numAttrs as usize],
+/// });
+/// ```
+#[macro_export]
+macro_rules! pointer_fields {
+    ($ty:ty, |$value:ident| { $($($field:ident).+ : [$elem:ty; $len:expr]),* $(,)? }) => {
+        impl $crate::ipc::message::PointerFields for $ty {
+            #[allow(unused_variables)]
+            fn pointer_fields(&self) -> ::std::vec::Vec<$crate::ipc::message::PointerField> {
+                let $value = self;
+                ::std::vec![$(
+                    $crate::ipc::message::PointerField::new::<$elem>(
+                        ::core::mem::offset_of!($ty, $($field).+),
+                        $len,
+                    ),
+                )*]
+            }
+        }
+    };
+}
+
+/// Wire form of a pointer field, followed by the elements it points to unless
+/// `len` is zero.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, KnownLayout, Immutable)]
+struct FieldDescriptor {
+    offset: U64<LittleEndian>,
+    type_size: U64<LittleEndian>,
+    len: U64<LittleEndian>,
+    type_align: U32<LittleEndian>,
+    _reserved: [u8; 4],
+}
+
+/// Writes the fields of the value at `value`, each followed by the elements it
+/// points to. Null pointers are sent as empty.
+///
+/// # Safety
+///
+/// Each field must fit in the value, and its pointer be null or valid for reads
+/// of the elements it declares.
+pub(super) unsafe fn write_fields<W: BytewiseWriter>(
+    writer: &mut W,
+    value: ptr::NonNull<u8>,
+    fields: &[PointerField],
+) -> Result<(), BytewiseError> {
+    for field in fields {
+        let data = ptr::NonNull::new(unsafe { field.get(value) }).filter(|_| !field.is_empty());
+        let type_align = u32::try_from(field.type_align)
+            .map_err(|_| BytewiseError::InvalidEncoding("pointer field alignment"))?;
+
+        writer.write_ref(&FieldDescriptor {
+            offset: U64::new(field.offset as u64),
+            type_size: U64::new(field.type_size as u64),
+            len: U64::new(data.map_or(0, |_| field.len as u64)),
+            type_align: U32::new(type_align),
+            _reserved: [0; 4],
+        })?;
+        if let Some(data) = data {
+            unsafe { writer.write_raw(data, field.total_size(), field.type_align)? };
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads `count` fields of the value at `value`, `value_len` bytes long, pointing
+/// each at the elements following it in the buffer. Empty fields become null.
+///
+/// # Safety
+///
+/// `value` must be valid for writes of `value_len` bytes.
+pub(super) unsafe fn read_fields<'a, R: BytewiseReader<'a>>(
+    reader: &mut R,
+    value: ptr::NonNull<u8>,
+    value_len: usize,
+    count: usize,
+) -> Result<Box<[PointerField]>, BytewiseError> {
+    (0..count)
+        .map(|_| {
+            let descriptor = unsafe { *reader.read_ref::<FieldDescriptor>()? };
+            let to_usize = |value: u64, what| {
+                usize::try_from(value).map_err(|_| BytewiseError::InvalidEncoding(what))
+            };
+
+            let field = PointerField {
+                offset: to_usize(descriptor.offset.get(), "pointer field offset")?,
+                type_size: to_usize(descriptor.type_size.get(), "pointer field size")?,
+                type_align: to_usize(
+                    descriptor.type_align.get().into(),
+                    "pointer field alignment",
+                )?,
+                len: to_usize(descriptor.len.get(), "pointer field length")?,
+            };
+            if !field.fits_in(value_len) {
+                return Err(BytewiseError::InvalidEncoding("pointer field offset"));
+            }
+            let total_size = field
+                .type_size
+                .checked_mul(field.len)
+                .ok_or(BytewiseError::InvalidEncoding("pointer field length"))?;
+
+            let data = match total_size {
+                0 => ptr::null_mut(),
+                _ => unsafe { reader.read_raw(total_size, field.type_align)? }.as_ptr(),
+            };
+            // SAFETY: The field was checked to fit in the value.
+            unsafe { field.set(value, data) };
+
+            Ok(field)
+        })
+        .collect()
+}
+
+/// Copies a value of `len` bytes from `src` to `dst`, keeping the pointers of
+/// `fields` in `dst` as they were.
+///
+/// # Safety
+///
+/// `src` must be valid for reads and `dst` for writes of `len` bytes, without
+/// overlapping, and each field must fit in the value.
+pub(super) unsafe fn copy_keeping_fields(
+    src: *const u8,
+    dst: ptr::NonNull<u8>,
+    len: usize,
+    fields: &[PointerField],
+) {
+    let pointers: Vec<_> = fields
+        .iter()
+        .map(|field| unsafe { field.get(dst) })
+        .collect();
+    unsafe { ptr::copy_nonoverlapping(src, dst.as_ptr(), len) };
+    for (field, pointer) in fields.iter().zip(pointers) {
+        unsafe { field.set(dst, pointer) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ffi::c_void;
+
+    use crate::ipc::{
+        bytewise::{BytewiseBuffer, BytewiseReadOwned, BytewiseWrite},
+        message::{Argument, ArgumentFlag, MessageError, Request, Response},
+    };
+
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Attribute {
+        id: u32,
+        value: u64,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    struct LaunchConfig {
+        grid: u32,
+        attrs: *const Attribute,
+        num_attrs: u32,
+    }
+
+    crate::type_tags!(LaunchConfig);
+    crate::pointer_fields!(LaunchConfig, |config| {
+        attrs: [Attribute; config.num_attrs as usize],
+    });
+
+    #[test]
+    fn test_deep_roundtrip() {
+        let mut buf = vec![0u8; 4096];
+        let attrs = [
+            Attribute { id: 1, value: 10 },
+            Attribute { id: 2, value: 20 },
+        ];
+        let config = LaunchConfig {
+            grid: 8,
+            attrs: attrs.as_ptr(),
+            num_attrs: 2,
+        };
+
+        let request = Request::with_arg(0x1234, unsafe {
+            Argument::from_deep_ref(&config, ArgumentFlag::ARG_IN)
+        });
+        assert_eq!(
+            request.args()[0].pointer_fields(),
+            [PointerField::new::<Attribute>(8, 2)]
+        );
+        request
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        // The received pointer leads to the copy in the buffer
+        let received = Request::read_from_mut(&mut BytewiseBuffer::new(&mut buf)).unwrap();
+        let recv_config = received.args()[0].downcast_ref::<LaunchConfig>().unwrap();
+        assert_eq!(recv_config.grid, 8);
+        assert_ne!(recv_config.attrs, attrs.as_ptr());
+        assert!(buf.as_ptr_range().contains(&recv_config.attrs.cast()));
+        let recv_attrs = unsafe {
+            std::slice::from_raw_parts(recv_config.attrs, recv_config.num_attrs as usize)
+        };
+        assert_eq!(recv_attrs, attrs);
+    }
+
+    #[test]
+    fn test_deep_null_field() {
+        let mut buf = vec![0u8; 4096];
+        let config = LaunchConfig {
+            grid: 1,
+            attrs: 0x10 as *const Attribute,
+            num_attrs: 0,
+        };
+
+        let argument = unsafe { Argument::from_deep_ref(&config, ArgumentFlag::ARG_IN) };
+        argument
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        // Empty fields arrive as null, whatever the sender held
+        let received = Argument::read_from_mut(&mut BytewiseBuffer::new(&mut buf)).unwrap();
+        let recv_config = received.downcast_ref::<LaunchConfig>().unwrap();
+        assert!(recv_config.attrs.is_null());
+
+        // Pointers cannot be rebuilt in an immutable buffer
+        assert!(matches!(
+            Argument::read_from(&mut BytewiseBuffer::new(&buf)),
+            Err(BytewiseError::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_kernel_params() {
+        let mut buf = vec![0u8; 4096];
+        let mut count = 7u32;
+        let mut scale = 0.5f64;
+        let mut params = [
+            ptr::from_mut(&mut count).cast::<c_void>(),
+            ptr::from_mut(&mut scale).cast::<c_void>(),
+        ];
+
+        let argument = unsafe {
+            Argument::from_mut_slice(&mut params, ArgumentFlag::ARG_IN).with_pointer_fields([
+                PointerField::bytes(0, size_of::<u32>(), align_of::<u32>()),
+                PointerField::bytes(8, size_of::<f64>(), align_of::<f64>()),
+            ])
+        };
+        argument
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        let received = Argument::read_from_mut(&mut BytewiseBuffer::new(&mut buf)).unwrap();
+        let recv_params = received.downcast_slice::<*mut c_void>().unwrap();
+        assert_eq!(unsafe { *recv_params[0].cast::<u32>() }, 7);
+        assert_eq!(unsafe { *recv_params[1].cast::<f64>() }, 0.5);
+    }
+
+    #[test]
+    fn test_deep_update_keeps_pointers() {
+        let mut buf = vec![0u8; 4096];
+        let attrs = [Attribute { id: 1, value: 10 }];
+        let mut config = LaunchConfig {
+            grid: 8,
+            attrs: attrs.as_ptr(),
+            num_attrs: 1,
+        };
+
+        let mut request = Request::with_arg(0x1234, unsafe {
+            Argument::from_deep_mut(&mut config, ArgumentFlag::ARG_OUT)
+        });
+        request
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        // The handler updates the value, its pointer leads into the buffer
+        let received = Request::read_from_mut(&mut BytewiseBuffer::new(&mut buf)).unwrap();
+        unsafe { received.args()[0].downcast_mut::<LaunchConfig>() }
+            .unwrap()
+            .grid = 16;
+        let response = Response::with_request(&received, Argument::empty());
+        assert!(response.args()[0].pointer_fields().is_empty());
+
+        request.update_from(&response).unwrap();
+        drop(request);
+        assert_eq!(config.grid, 16);
+        assert_eq!(config.attrs, attrs.as_ptr());
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds")]
+    fn test_field_out_of_bounds() {
+        let value = 0u64;
+        let _ = unsafe {
+            Argument::from_ref(&value, ArgumentFlag::ARG_IN)
+                .with_pointer_fields([PointerField::new::<u8>(4, 1)])
+        };
+    }
+
+    #[test]
+    fn test_unknown_field_type() {
+        let config = LaunchConfig {
+            grid: 1,
+            attrs: ptr::null(),
+            num_attrs: 0,
+        };
+        let argument = unsafe { Argument::from_deep_ref(&config, ArgumentFlag::ARG_IN) };
+        assert_eq!(
+            argument.downcast_ref::<u64>(),
+            Err(MessageError::ArgumentTypeMismatch)
+        );
+    }
+}
@@ -14,6 +14,9 @@
 
 use std::sync::atomic::{AtomicU64, Ordering};
 
+use bitflags::bitflags;
+use zerocopy::{FromBytes, Immutable, KnownLayout, LittleEndian, U32, U64};
+
 use crate::ipc::bytewise::{
     BytewiseError, BytewiseRead, BytewiseReadOwned, BytewiseReader, BytewiseWrite, BytewiseWriter,
 };
@@ -22,12 +25,64 @@ use super::{Argument, ArgumentFlag, MessageError, Response};
 
 static REQUEST_ID: AtomicU64 = AtomicU64::new(1);
 
+bitflags! {
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct RequestFlag: u32 {
+        /// The server sends no `Response`, errors are latched until queried.
+        const NO_REPLY = 0b0001;
+        /// Heads a `RequestBatch`, `arg_count` requests follow instead of arguments.
+        const BATCH = 0b0010;
+    }
+}
+
+/// Wire header of a request, fixed-width little-endian.
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
-struct RequestMetadata {
-    request_id: u64,
-    method_id: u64,
-    arg_count: usize,
+#[derive(Debug, Clone, Copy, FromBytes, zerocopy::IntoBytes, KnownLayout, Immutable)]
+pub(super) struct RequestMetadata {
+    request_id: U64<LittleEndian>,
+    method_id: U64<LittleEndian>,
+    arg_count: U32<LittleEndian>,
+    flag: U32<LittleEndian>,
+}
+
+impl RequestMetadata {
+    pub(super) fn new(
+        request_id: u64,
+        method_id: u64,
+        arg_count: usize,
+        flag: RequestFlag,
+    ) -> Result<Self, BytewiseError> {
+        let arg_count = u32::try_from(arg_count)
+            .map_err(|_| BytewiseError::InvalidEncoding("request argument count"))?;
+
+        Ok(Self {
+            request_id: U64::new(request_id),
+            method_id: U64::new(method_id),
+            arg_count: U32::new(arg_count),
+            flag: U32::new(flag.bits()),
+        })
+    }
+
+    #[inline]
+    pub(super) fn request_id(&self) -> u64 {
+        self.request_id.get()
+    }
+
+    #[inline]
+    pub(super) fn method_id(&self) -> u64 {
+        self.method_id.get()
+    }
+
+    #[inline]
+    pub(super) fn arg_count(&self) -> usize {
+        self.arg_count.get() as usize
+    }
+
+    #[inline]
+    pub(super) fn flag(&self) -> RequestFlag {
+        RequestFlag::from_bits_retain(self.flag.get())
+    }
 }
 
 impl BytewiseRead for RequestMetadata {
@@ -47,6 +102,7 @@ pub struct Request<'a> {
     request_id: u64,
     method_id: u64,
     arg_list: Vec<Argument<'a>>,
+    flag: RequestFlag,
 }
 
 impl<'a> Request<'a> {
@@ -56,6 +112,7 @@ impl<'a> Request<'a> {
             request_id: REQUEST_ID.fetch_add(1, Ordering::Relaxed),
             method_id,
             arg_list: vec![],
+            flag: RequestFlag::empty(),
         }
     }
 
@@ -65,6 +122,7 @@ impl<'a> Request<'a> {
             request_id: REQUEST_ID.fetch_add(1, Ordering::Relaxed),
             method_id,
             arg_list: vec![arg],
+            flag: RequestFlag::empty(),
         }
     }
 
@@ -77,6 +135,7 @@ impl<'a> Request<'a> {
             request_id: REQUEST_ID.fetch_add(1, Ordering::Relaxed),
             method_id,
             arg_list: Vec::from_iter(args),
+            flag: RequestFlag::empty(),
         }
     }
 
@@ -90,6 +149,22 @@ impl<'a> Request<'a> {
         self.method_id
     }
 
+    #[inline]
+    pub const fn flag(&self) -> RequestFlag {
+        self.flag
+    }
+
+    #[inline]
+    pub fn set_flag(&mut self, flag: RequestFlag) {
+        self.flag = flag;
+    }
+
+    /// Whether the server sends no `Response` for this request.
+    #[inline]
+    pub const fn is_no_reply(&self) -> bool {
+        self.flag.contains(RequestFlag::NO_REPLY)
+    }
+
     #[inline]
     pub const fn argc(&self) -> usize {
         self.arg_list.len()
@@ -107,6 +182,25 @@ impl<'a> Request<'a> {
 }
 
 impl Request<'_> {
+    /// Points the shared arguments received from the peer into this process' mapping
+    /// of the region, see `Argument::resolve_shared`.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be valid for both reads and writes of `region_len` bytes for the
+    /// entire lifetime of the request, and map the region the peer referenced.
+    pub unsafe fn resolve_shared(
+        &mut self,
+        base: *mut u8,
+        region_len: usize,
+    ) -> Result<(), MessageError> {
+        for arg in &mut self.arg_list {
+            // SAFETY: Guaranteed by the caller.
+            unsafe { arg.resolve_shared(base, region_len)? };
+        }
+        Ok(())
+    }
+
     pub fn update_from(&mut self, response: &Response) -> Result<(), MessageError> {
         if self.request_id() != response.request_id() {
             return Err(MessageError::RequestIdMismatch {
@@ -115,6 +209,10 @@ impl Request<'_> {
             });
         }
 
+        if !response.status().is_ok() {
+            return Err(MessageError::RequestFailed(response.status()));
+        }
+
         if self.argc() != response.argc() {
             return Err(MessageError::ArgumentCountMismatch {
                 expect: self.argc(),
@@ -134,11 +232,12 @@ impl Request<'_> {
 
 impl BytewiseWrite for Request<'_> {
     fn write_to<W: BytewiseWriter>(&self, writer: &mut W) -> Result<(), BytewiseError> {
-        let metadata = RequestMetadata {
-            request_id: self.request_id,
-            method_id: self.method_id,
-            arg_count: self.arg_list.len(),
-        };
+        let metadata = RequestMetadata::new(
+            self.request_id,
+            self.method_id,
+            self.arg_list.len(),
+            self.flag,
+        )?;
 
         // Write metadata
         metadata.write_to(writer)?;
@@ -152,22 +251,34 @@ impl BytewiseWrite for Request<'_> {
     }
 }
 
+impl<'x> Request<'x> {
+    /// Reads the arguments following `metadata` with `read_arg`.
+    pub(super) fn read_with<'a, R: BytewiseReader<'a>>(
+        metadata: &RequestMetadata,
+        reader: &mut R,
+        read_arg: fn(&mut R) -> Result<Argument<'x>, BytewiseError>,
+    ) -> Result<Self, BytewiseError> {
+        let mut arg_list = Vec::with_capacity(metadata.arg_count());
+        for _ in 0..metadata.arg_count() {
+            arg_list.push(read_arg(reader)?);
+        }
+
+        Ok(Self {
+            request_id: metadata.request_id(),
+            method_id: metadata.method_id(),
+            arg_list,
+            flag: metadata.flag(),
+        })
+    }
+}
+
 impl BytewiseReadOwned for Request<'_> {
     fn read_from<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
         // Read metadata
         let metadata = RequestMetadata::read_ref(reader)?;
 
         // Read argument list
-        let mut arg_list = Vec::with_capacity(metadata.arg_count);
-        for _ in 0..metadata.arg_count {
-            arg_list.push(Argument::read_from(reader)?);
-        }
-
-        Ok(Self {
-            request_id: metadata.request_id,
-            method_id: metadata.method_id,
-            arg_list,
-        })
+        Self::read_with(metadata, reader, Argument::read_from)
     }
 
     fn read_from_mut<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
@@ -175,24 +286,18 @@ impl BytewiseReadOwned for Request<'_> {
         let metadata = RequestMetadata::read_ref(reader)?;
 
         // Read argument list
-        let mut arg_list = Vec::with_capacity(metadata.arg_count);
-        for _ in 0..metadata.arg_count {
-            arg_list.push(Argument::read_from_mut(reader)?);
-        }
-
-        Ok(Self {
-            request_id: metadata.request_id,
-            method_id: metadata.method_id,
-            arg_list,
-        })
+        Self::read_with(metadata, reader, Argument::read_from_mut)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fmt::Debug;
+    use std::{fmt::Debug, ptr};
 
-    use crate::ipc::{bytewise::BytewiseBuffer, message::ArgumentFlag};
+    use crate::ipc::{
+        bytewise::BytewiseBuffer,
+        message::{ArgumentFlag, ResponseStatus},
+    };
 
     use super::*;
 
@@ -245,6 +350,7 @@ mod tests {
     fn test_zst_argument_roundtrip() {
         #[derive(Debug)]
         struct Zst;
+        crate::type_tags!(Zst);
 
         roundtrip_test(Request::with_args(
             0xDEADBEEF,
@@ -282,6 +388,7 @@ mod tests {
             _value1: usize,
             _value2: usize,
         }
+        crate::type_tags!(TestValue);
 
         roundtrip_test(Request::with_args(
             0xFFFF,
@@ -333,4 +440,97 @@ mod tests {
         assert_eq!(request.args()[3].downcast::<u8>(), Ok(9u8));
         assert_eq!(request.args()[4].downcast::<()>(), Ok(()));
     }
+
+    #[test]
+    fn test_update_from_failed() {
+        let mut out = 1u8;
+        let mut request =
+            Request::with_arg(0xFFFF, Argument::from_mut(&mut out, ArgumentFlag::ARG_OUT));
+
+        let response = Response::with_status(&request, ResponseStatus::UnknownMethod);
+        assert_eq!(
+            request.update_from(&response),
+            Err(MessageError::RequestFailed(ResponseStatus::UnknownMethod))
+        );
+        drop(request);
+        assert_eq!(out, 1);
+    }
+
+    #[test]
+    fn test_null_out_argument() {
+        let mut buf = vec![0u8; 4096];
+        let mut out = 0i32;
+
+        let mut request = Request::with_args(
+            0xFFFF,
+            vec![
+                unsafe { Argument::from_mut_ptr(ptr::null_mut::<i32>(), ArgumentFlag::ARG_OUT) },
+                Argument::from_mut(&mut out, ArgumentFlag::ARG_OUT),
+            ],
+        );
+        request
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        // The handler sees the absent argument as `None`
+        let recv_req = Request::read_from_mut(&mut BytewiseBuffer::new(&mut buf)).unwrap();
+        assert!(recv_req.args()[0].is_null());
+        assert_eq!(
+            unsafe { recv_req.args()[0].downcast_opt_mut::<i32>() },
+            Ok(None)
+        );
+        assert_eq!(
+            unsafe { recv_req.args()[0].downcast_mut::<i32>() },
+            Err(MessageError::NullArgument)
+        );
+        let value = unsafe { recv_req.args()[1].downcast_opt_mut::<i32>() };
+        *value.unwrap().unwrap() = 7;
+
+        // Nothing is written back through the null pointer
+        let response = Response::with_request(&recv_req, Argument::empty());
+        request
+            .update_from(&response)
+            .expect("Update operation should succeed");
+        drop(request);
+        assert_eq!(out, 7);
+    }
+
+    #[test]
+    fn test_metadata_wire_format() {
+        let mut buf = vec![0u8; 4096];
+
+        let mut request = Request::with_arg(
+            0x0102_0304,
+            Argument::from_value(1u32, ArgumentFlag::ARG_IN),
+        );
+        request.set_flag(RequestFlag::NO_REPLY);
+        request
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+
+        // Little-endian whatever the host, with a 32-bit argument count
+        assert_eq!(buf[..8], request.request_id().to_le_bytes());
+        assert_eq!(buf[8..16], 0x0102_0304u64.to_le_bytes());
+        assert_eq!(buf[16..20], 1u32.to_le_bytes());
+        assert_eq!(buf[20..24], RequestFlag::NO_REPLY.bits().to_le_bytes());
+    }
+
+    #[test]
+    fn test_flag_roundtrip() {
+        let mut buf = vec![0u8; 4096];
+
+        let mut request =
+            Request::with_arg(0xABCD, Argument::from_value(1u32, ArgumentFlag::ARG_IN));
+        assert!(!request.is_no_reply());
+        request.set_flag(RequestFlag::NO_REPLY);
+
+        request
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+        let recv_req = Request::read_from(&mut BytewiseBuffer::new(&mut buf)).unwrap();
+
+        assert_eq!(recv_req.flag(), RequestFlag::NO_REPLY);
+        assert!(recv_req.is_no_reply());
+        assert_eq!(recv_req.args()[0].downcast::<u32>(), Ok(1u32));
+    }
 }
@@ -12,18 +12,138 @@
  * See the Mulan PSL v2 for more details.
  */
 
+use std::fmt;
+
+use bitflags::bitflags;
+use zerocopy::{FromBytes, Immutable, KnownLayout, LittleEndian, U32, U64};
+
 use crate::ipc::bytewise::{
     BytewiseError, BytewiseRead, BytewiseReadOwned, BytewiseReader, BytewiseWrite, BytewiseWriter,
 };
 
 use super::{Argument, ArgumentFlag, Request};
 
+bitflags! {
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct ResponseFlag: u32 {
+        /// Heads a `ResponseBatch`, `arg_count` responses follow instead of arguments.
+        const BATCH = 0b0001;
+    }
+}
+
+/// Outcome of a request on the server, apart from the return value of the API.
+///
+/// Only an `Ok` response carries out arguments and a return value, the others
+/// come back empty for the client to report an error of its own.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseStatus {
+    /// The API was called, its return value tells how it went.
+    #[default]
+    Ok = 0,
+    /// No handler is registered for the method.
+    UnknownMethod = 1,
+    /// An argument is not of the type the handler expects.
+    TypeMismatch = 2,
+    /// The handler panicked.
+    HandlerPanic = 3,
+    /// The library backing the API cannot serve it, also what the client reports
+    /// when the server is out of reach.
+    BackendUnavailable = 4,
+}
+
+impl ResponseStatus {
+    #[inline]
+    pub const fn is_ok(self) -> bool {
+        matches!(self, Self::Ok)
+    }
+}
+
+impl fmt::Display for ResponseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::UnknownMethod => write!(f, "unknown method"),
+            Self::TypeMismatch => write!(f, "argument type mismatch"),
+            Self::HandlerPanic => write!(f, "handler panicked"),
+            Self::BackendUnavailable => write!(f, "backend unavailable"),
+        }
+    }
+}
+
+impl TryFrom<u32> for ResponseStatus {
+    type Error = BytewiseError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Ok),
+            1 => Ok(Self::UnknownMethod),
+            2 => Ok(Self::TypeMismatch),
+            3 => Ok(Self::HandlerPanic),
+            4 => Ok(Self::BackendUnavailable),
+            _ => Err(BytewiseError::InvalidEncoding("response status")),
+        }
+    }
+}
+
+/// Wire header of a response, fixed-width little-endian.
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
-struct ResponseMetadata {
-    request_id: u64,
-    method_id: u64,
-    arg_count: usize,
+#[derive(Debug, Clone, Copy, FromBytes, zerocopy::IntoBytes, KnownLayout, Immutable)]
+pub(super) struct ResponseMetadata {
+    request_id: U64<LittleEndian>,
+    method_id: U64<LittleEndian>,
+    arg_count: U32<LittleEndian>,
+    flag: U32<LittleEndian>,
+    status: U32<LittleEndian>,
+    _reserved: [u8; 4],
+}
+
+impl ResponseMetadata {
+    pub(super) fn new(
+        request_id: u64,
+        method_id: u64,
+        arg_count: usize,
+        flag: ResponseFlag,
+        status: ResponseStatus,
+    ) -> Result<Self, BytewiseError> {
+        let arg_count = u32::try_from(arg_count)
+            .map_err(|_| BytewiseError::InvalidEncoding("response argument count"))?;
+
+        Ok(Self {
+            request_id: U64::new(request_id),
+            method_id: U64::new(method_id),
+            arg_count: U32::new(arg_count),
+            flag: U32::new(flag.bits()),
+            status: U32::new(status as u32),
+            _reserved: [0; 4],
+        })
+    }
+
+    #[inline]
+    pub(super) fn request_id(&self) -> u64 {
+        self.request_id.get()
+    }
+
+    #[inline]
+    pub(super) fn method_id(&self) -> u64 {
+        self.method_id.get()
+    }
+
+    #[inline]
+    pub(super) fn arg_count(&self) -> usize {
+        self.arg_count.get() as usize
+    }
+
+    #[inline]
+    pub(super) fn flag(&self) -> ResponseFlag {
+        ResponseFlag::from_bits_retain(self.flag.get())
+    }
+
+    #[inline]
+    pub(super) fn status(&self) -> Result<ResponseStatus, BytewiseError> {
+        ResponseStatus::try_from(self.status.get())
+    }
 }
 
 impl BytewiseRead for ResponseMetadata {
@@ -42,6 +162,7 @@ impl BytewiseWrite for ResponseMetadata {
 pub struct Response<'a> {
     pub(super) request_id: u64,
     pub(super) method_id: u64,
+    pub(super) status: ResponseStatus,
     pub(super) arg_list: Vec<Argument<'a>>,
     pub(super) ret_value: Argument<'a>,
 }
@@ -52,11 +173,21 @@ impl<'a> Response<'a> {
         Self {
             request_id,
             method_id,
+            status: ResponseStatus::Ok,
             arg_list: vec![],
             ret_value: Argument::empty(),
         }
     }
 
+    /// Creates a response reporting that `request` could not be served.
+    #[inline]
+    pub fn with_status(request: &Request<'_>, status: ResponseStatus) -> Self {
+        Self {
+            status,
+            ..Self::empty(request.request_id(), request.method_id())
+        }
+    }
+
     #[inline]
     pub fn with_request<'b: 'a>(request: &Request<'a>, ret_value: Argument<'b>) -> Self {
         let request_id = request.request_id();
@@ -67,7 +198,7 @@ impl<'a> Response<'a> {
             .map(|argument| {
                 // Replace non-out argument to empty to save memory, but keep it's index
                 if argument.flag().contains(ArgumentFlag::ARG_OUT) {
-                    *argument
+                    argument.clone().without_pointer_fields()
                 } else {
                     Argument::empty()
                 }
@@ -77,6 +208,7 @@ impl<'a> Response<'a> {
         Self {
             request_id,
             method_id,
+            status: ResponseStatus::Ok,
             ret_value,
             arg_list,
         }
@@ -92,6 +224,11 @@ impl<'a> Response<'a> {
         self.method_id
     }
 
+    #[inline]
+    pub const fn status(&self) -> ResponseStatus {
+        self.status
+    }
+
     #[inline]
     pub const fn argc(&self) -> usize {
         self.arg_list.len()
@@ -113,57 +250,61 @@ impl<'a> Response<'a> {
     }
 }
 
-impl BytewiseReadOwned for Response<'_> {
-    fn read_from<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
-        // Read metadata
-        let metadata = ResponseMetadata::read_ref(reader)?;
+impl<'x> Response<'x> {
+    /// Reads the arguments and return value following `metadata` with `read_arg`.
+    pub(super) fn read_with<'a, R: BytewiseReader<'a>>(
+        metadata: &ResponseMetadata,
+        reader: &mut R,
+        read_arg: fn(&mut R) -> Result<Argument<'x>, BytewiseError>,
+    ) -> Result<Self, BytewiseError> {
+        let status = metadata.status()?;
 
         // Read argument list
-        let mut arg_list = Vec::with_capacity(metadata.arg_count);
-        for _ in 0..metadata.arg_count {
-            arg_list.push(Argument::read_from(reader)?);
+        let mut arg_list = Vec::with_capacity(metadata.arg_count());
+        for _ in 0..metadata.arg_count() {
+            arg_list.push(read_arg(reader)?);
         }
 
         // Read return value
-        let ret_value = Argument::read_from(reader)?;
+        let ret_value = read_arg(reader)?;
 
         Ok(Self {
-            request_id: metadata.request_id,
-            method_id: metadata.method_id,
+            request_id: metadata.request_id(),
+            method_id: metadata.method_id(),
+            status,
             arg_list,
             ret_value,
         })
     }
+}
 
-    fn read_from_mut<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
+impl BytewiseReadOwned for Response<'_> {
+    fn read_from<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
         // Read metadata
         let metadata = ResponseMetadata::read_ref(reader)?;
 
-        // Read argument list
-        let mut arg_list = Vec::with_capacity(metadata.arg_count);
-        for _ in 0..metadata.arg_count {
-            arg_list.push(Argument::read_from_mut(reader)?);
-        }
+        // Read argument list and return value
+        Self::read_with(metadata, reader, Argument::read_from)
+    }
 
-        // Read return value
-        let ret_value = Argument::read_from_mut(reader)?;
+    fn read_from_mut<'a, R: BytewiseReader<'a>>(reader: &mut R) -> Result<Self, BytewiseError> {
+        // Read metadata
+        let metadata = ResponseMetadata::read_ref(reader)?;
 
-        Ok(Self {
-            request_id: metadata.request_id,
-            method_id: metadata.method_id,
-            arg_list,
-            ret_value,
-        })
+        // Read argument list and return value
+        Self::read_with(metadata, reader, Argument::read_from_mut)
     }
 }
 
 impl BytewiseWrite for Response<'_> {
     fn write_to<W: BytewiseWriter>(&self, writer: &mut W) -> Result<(), BytewiseError> {
-        let metadata = ResponseMetadata {
-            request_id: self.request_id,
-            method_id: self.method_id,
-            arg_count: self.arg_list.len(),
-        };
+        let metadata = ResponseMetadata::new(
+            self.request_id,
+            self.method_id,
+            self.arg_list.len(),
+            ResponseFlag::empty(),
+            self.status,
+        )?;
 
         // Write metadata
         metadata.write_to(writer)?;
@@ -182,7 +323,7 @@ impl BytewiseWrite for Response<'_> {
 
 #[cfg(test)]
 mod tests {
-    use std::ptr;
+    use std::{mem::offset_of, ptr};
 
     use crate::ipc::{bytewise::BytewiseBuffer, message::ArgumentFlag};
 
@@ -244,13 +385,13 @@ mod tests {
             0xABCD,
             vec![
                 Argument::from_ref(&42u32, ArgumentFlag::ARG_OUT),
-                Argument::from_ref(&"test", ArgumentFlag::ARG_OUT),
+                Argument::from_ref(b"test", ArgumentFlag::ARG_OUT),
             ],
         );
         println!("request: {:#?}", request);
 
         let ret_value = Argument::from_ref(&true, ArgumentFlag::ARG_OUT);
-        let response = Response::with_request(&request, ret_value);
+        let response = Response::with_request(&request, ret_value.clone());
         println!("response: {:#?}", response);
 
         response_roundtrip_test(Response::with_request(&request, ret_value));
@@ -281,6 +422,7 @@ mod tests {
             field1: u32,
             field2: f64,
         }
+        crate::type_tags!(TestStruct);
 
         let request = Request::with_args(
             0xBEEF,
@@ -330,4 +472,31 @@ mod tests {
         args_mut[0] = Argument::from_ref(&30u32, ArgumentFlag::ARG_OUT);
         assert_eq!(args_mut[0].downcast::<u32>().unwrap(), 30);
     }
+
+    #[test]
+    fn response_status_roundtrip() {
+        let request = Request::with_arg(0x42, Argument::from_ref(&10u32, ArgumentFlag::ARG_OUT));
+        assert_eq!(
+            Response::with_request(&request, Argument::empty()).status(),
+            ResponseStatus::Ok
+        );
+
+        let response = Response::with_status(&request, ResponseStatus::TypeMismatch);
+        assert_eq!(response.request_id(), request.request_id());
+        assert_eq!(response.method_id(), 0x42);
+        assert_eq!(response.argc(), 0);
+
+        let mut buf = vec![0u8; 256];
+        response
+            .write_to(&mut BytewiseBuffer::new(&mut buf))
+            .unwrap();
+        let recv_resp = Response::read_from(&mut BytewiseBuffer::new(&buf)).unwrap();
+        assert_eq!(recv_resp.status(), ResponseStatus::TypeMismatch);
+        assert!(!recv_resp.status().is_ok());
+        assert!(recv_resp.ret_value().is_empty());
+
+        // Statuses from a newer peer are rejected
+        buf[offset_of!(ResponseMetadata, status)] = 0xFF;
+        assert!(Response::read_from(&mut BytewiseBuffer::new(&buf)).is_err());
+    }
 }
@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use core::ffi::c_void;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+const fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
+
+/// Hashes a canonical type name together with its layout, so that types with the
+/// same name but a different size or alignment on the two sides never match.
+pub const fn type_tag(name: &str, size: usize, align: usize) -> u64 {
+    let hash = fnv1a(FNV_OFFSET_BASIS, name.as_bytes());
+    let hash = fnv1a(hash, &(size as u64).to_le_bytes());
+    fnv1a(hash, &(align as u64).to_le_bytes())
+}
+
+/// Derives the tag of a type built from `inner`, such as a pointer to it.
+const fn type_tag_of(name: &str, inner: u64, size: usize, align: usize) -> u64 {
+    fnv1a(type_tag(name, size, align), &inner.to_le_bytes())
+}
+
+/// A type that may be sent as an argument.
+///
+/// Unlike `TypeId`, the tag only depends on the type name and layout, so it is the
+/// same in separately compiled binaries. Implement it with [`type_tags!`](crate::type_tags).
+pub trait TypeTag: 'static {
+    const TYPE_TAG: u64;
+}
+
+/// Implements [`TypeTag`] for each listed type, tagged by its name as written.
+///
+/// Both sides of a connection must list a type under the same name, so a type is
+/// best registered once, next to its definition.
+#[macro_export]
+macro_rules! type_tags {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl $crate::ipc::message::TypeTag for $ty {
+                const TYPE_TAG: u64 = $crate::ipc::message::type_tag(
+                    stringify!($ty),
+                    ::core::mem::size_of::<$ty>(),
+                    ::core::mem::align_of::<$ty>(),
+                );
+            }
+        )*
+    };
+}
+
+crate::type_tags!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    c_void,
+);
+
+impl<T: TypeTag> TypeTag for *const T {
+    const TYPE_TAG: u64 = type_tag_of("*const", T::TYPE_TAG, size_of::<Self>(), align_of::<Self>());
+}
+
+impl<T: TypeTag> TypeTag for *mut T {
+    const TYPE_TAG: u64 = type_tag_of("*mut", T::TYPE_TAG, size_of::<Self>(), align_of::<Self>());
+}
+
+impl<T: TypeTag, const N: usize> TypeTag for [T; N] {
+    const TYPE_TAG: u64 = type_tag_of("[]", T::TYPE_TAG, size_of::<Self>(), align_of::<Self>());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C)]
+    struct Pair {
+        a: u32,
+        b: u32,
+    }
+
+    crate::type_tags!(Pair);
+
+    #[test]
+    fn test_type_tag_is_stable() {
+        // FNV-1a of "u32", 4 and 4; must never change between builds
+        assert_eq!(u32::TYPE_TAG, type_tag("u32", 4, 4));
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b""), FNV_OFFSET_BASIS);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_type_tag_distinguishes_types() {
+        let tags = [
+            u32::TYPE_TAG,
+            i32::TYPE_TAG,
+            f32::TYPE_TAG,
+            u64::TYPE_TAG,
+            Pair::TYPE_TAG,
+            <*const u32>::TYPE_TAG,
+            <*mut u32>::TYPE_TAG,
+            <*mut i32>::TYPE_TAG,
+            <[u32; 2]>::TYPE_TAG,
+            <[u32; 3]>::TYPE_TAG,
+        ];
+        for (i, lhs) in tags.iter().enumerate() {
+            for rhs in &tags[i + 1..] {
+                assert_ne!(lhs, rhs);
+            }
+        }
+
+        // Same name, different layout
+        assert_ne!(type_tag("Pair", 8, 4), type_tag("Pair", 16, 8));
+        assert_eq!(Pair::TYPE_TAG, type_tag("Pair", 8, 4));
+    }
+}
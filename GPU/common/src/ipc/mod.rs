@@ -19,16 +19,20 @@ pub mod transport;
 pub mod bytewise;
 pub mod message;
 
+pub mod dispatcher;
+pub mod fragment;
 pub mod peer;
 
 #[cfg(test)]
 mod tests {
     use std::{
         ffi::OsStr,
+        net::TcpListener,
         process,
         sync::{
             Once, OnceLock,
             atomic::{AtomicUsize, Ordering},
+            mpsc,
         },
         thread,
         time::{Duration, SystemTime, UNIX_EPOCH},
@@ -37,10 +41,25 @@ mod tests {
     use tracing::debug;
 
     use crate::ipc::{
+        bytewise::{AlignedBuffer, BytewiseBuffer, BytewiseWrite, BytewiseWriter},
+        dispatcher::Dispatcher,
+        error::IpcError,
+        fragment::FragmentError,
         framer::LengthPrefixFramer,
-        message::{Argument, ArgumentFlag, Request, Response},
-        peer::{Client, Server},
-        transport::shmem::ShmemTransportBuilder,
+        framer::{CompressingFramer, CompressingFramerError, Frame, FrameBuf, Framer},
+        message::{
+            Argument, ArgumentFlag, Handshake, HandshakeError, HandshakeFeatures, PROTOCOL_VERSION,
+            Request, RequestBatch, RequestFlag, Response, ResponseBatch,
+        },
+        peer::Peer,
+        peer::{Client, Listener, Server},
+        transport::{
+            Endpoint, Transport, WriteBuf,
+            loopback::{LoopbackTransport, LoopbackTransportBuilder},
+            shmem::ShmemTransportBuilder,
+            tcp::TcpTransportBuilder,
+            uds::UdsTransportBuilder,
+        },
     };
 
     fn init_test_logger() {
@@ -82,8 +101,27 @@ mod tests {
         )
     }
 
-    #[test]
-    fn test_invoke() {
+    fn unique_loopback_addr() -> String {
+        static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+        format!("ipc_{}", SEQ.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn unique_socket_path() -> String {
+        std::env::temp_dir()
+            .join(format!("{}.sock", &unique_shmem_addr()[1..]))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn unused_tcp_addr() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind tcp socket");
+        let addr = listener.local_addr().expect("Failed to get local address");
+
+        addr.to_string()
+    }
+
+    fn invoke_suite<T: Transport>(transport: T, addr: &T::Address) {
         mod method_id {
             pub const ADD_U64: u64 = 0xCAFE;
             pub const SHUTDOWN: u64 = 0xFFFF;
@@ -92,19 +130,22 @@ mod tests {
         self::init_test_logger();
 
         let framer = LengthPrefixFramer::new(4096);
-        let transport = ShmemTransportBuilder::new().build();
-        let addr = unique_shmem_addr();
 
-        let mut server = Server::create(framer, &transport, &addr).unwrap();
+        let mut server = Server::create(framer, &transport, addr).unwrap();
         debug!("{:#?}", server);
 
-        let mut client = Client::connect(framer, &transport, &addr).unwrap();
+        let mut client = Client::connect(framer, &transport, addr).unwrap();
         debug!("{:#?}", client);
 
         let server_thread = std::thread::spawn(move || {
             debug!("[Server] Thread started");
 
-            while let Ok(Some(request)) = server.receive_message::<Request>() {
+            loop {
+                let request = match server.receive_message::<Request>() {
+                    Ok(Some(request)) => request,
+                    Ok(None) => continue,
+                    Err(_) => break,
+                };
                 debug!(
                     "[Server] Received request: request_id={}, method_id={}, argc={}",
                     request.request_id(),
@@ -203,4 +244,971 @@ mod tests {
 
         let _ = server_thread.join();
     }
+
+    #[test]
+    fn test_invoke() {
+        let transport = ShmemTransportBuilder::new().build();
+        let addr = unique_shmem_addr();
+
+        invoke_suite(transport, addr.as_str());
+    }
+
+    #[test]
+    fn test_invoke_uds() {
+        let transport = UdsTransportBuilder::new().build();
+        let addr = unique_socket_path();
+
+        invoke_suite(transport, addr.as_str());
+    }
+
+    #[test]
+    fn test_invoke_tcp() {
+        let transport = TcpTransportBuilder::new().build();
+        let addr = unused_tcp_addr();
+
+        invoke_suite(transport, addr.as_str());
+    }
+
+    #[test]
+    fn test_invoke_loopback() {
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+
+        invoke_suite(transport, addr.as_str());
+    }
+
+    #[test]
+    fn test_receive_partial_frame() {
+        const METHOD_ID: u64 = 0xBEEF;
+
+        self::init_test_logger();
+
+        let framer = LengthPrefixFramer::new(4096);
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+
+        let endpoint = transport.create(&addr).unwrap();
+        let mut server = Peer::<_, LoopbackTransport>::new(framer, endpoint);
+        let mut client = transport.connect(&addr).unwrap();
+
+        // Encode a frame into a page-aligned scratch buffer, like the transport does
+        let value = 42u64;
+        let request = Request::with_args(
+            METHOD_ID,
+            vec![Argument::from_ref(&value, ArgumentFlag::ARG_IN)],
+        );
+        let mut frame = AlignedBuffer::new(4096, 4096);
+        let frame_len = {
+            let mut frame_buf = framer.encode_frame(&mut frame);
+            let mut writer = BytewiseBuffer::new(frame_buf.as_mut());
+            request.write_to(&mut writer).unwrap();
+
+            let payload_len = writer.written_bytes();
+            frame_buf.finalize(payload_len).unwrap()
+        };
+
+        // Deliver the frame in two halves
+        let mut send_raw = |bytes: &[u8]| {
+            let mut write_buf = client.write().unwrap();
+            write_buf[..bytes.len()].copy_from_slice(bytes);
+            write_buf.submit(bytes.len()).unwrap();
+        };
+        let (first, second) = frame[..frame_len].split_at(frame_len / 2);
+
+        send_raw(first);
+        assert!(server.receive_message::<Request>().unwrap().is_none());
+
+        send_raw(second);
+        let received = server
+            .receive_message::<Request>()
+            .unwrap()
+            .expect("Frame should be complete");
+        assert_eq!(received.request_id(), request.request_id());
+        assert_eq!(received.method_id(), METHOD_ID);
+        assert_eq!(received.args()[0].downcast::<u64>().unwrap(), value);
+    }
+
+    #[test]
+    fn test_invoke_timeout() {
+        const TIMEOUT: Duration = Duration::from_millis(50);
+
+        self::init_test_logger();
+
+        let framer = LengthPrefixFramer::new(4096);
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+
+        // The server never answers
+        let _server = Server::create(framer, &transport, addr.as_str()).unwrap();
+        let mut client = Client::connect(framer, &transport, addr.as_str()).unwrap();
+        client.set_timeout(Some(TIMEOUT));
+
+        let request = Request::empty(0xDEAD);
+        let start_time = std::time::Instant::now();
+        let result = client.invoke(&request);
+
+        assert!(matches!(result, Err(IpcError::Timeout(timeout)) if timeout == TIMEOUT));
+        assert!(start_time.elapsed() >= TIMEOUT);
+    }
+
+    #[test]
+    fn test_compressed_frame() {
+        const LEN: usize = 8192;
+
+        let mut framer = CompressingFramer::new(LengthPrefixFramer::new(16384)).threshold(1024);
+        let payload: Vec<u8> = (0..LEN).map(|i| (i % 7) as u8).collect();
+
+        let encode = |framer: &CompressingFramer<LengthPrefixFramer>| {
+            let mut frame = AlignedBuffer::new(16384, 4096);
+            let frame_len = {
+                let mut frame_buf = framer.encode_frame(&mut frame);
+                frame_buf[..LEN].copy_from_slice(&payload);
+                frame_buf.finalize(LEN).unwrap()
+            };
+
+            let decoded = framer.decode_frame(&frame).unwrap().unwrap();
+            assert_eq!(decoded.frame_len(), frame_len);
+            assert_eq!(&decoded[..], &payload[..]);
+            frame_len
+        };
+
+        // Sent as is until both sides agree on compression
+        assert!(encode(&framer) > LEN);
+        framer.set_features(HandshakeFeatures::COMPRESSION);
+        assert!(framer.is_enabled());
+        assert!(encode(&framer) < LEN);
+    }
+
+    #[test]
+    fn test_compressed_frame_in_use() {
+        const LEN: usize = 8192;
+
+        let mut framer = CompressingFramer::new(LengthPrefixFramer::new(16384)).threshold(1024);
+        framer.set_features(HandshakeFeatures::COMPRESSION);
+
+        let mut frame = AlignedBuffer::new(16384, 4096);
+        {
+            let mut frame_buf = framer.encode_frame(&mut frame);
+            frame_buf[..LEN].fill(1);
+            frame_buf.finalize(LEN).unwrap();
+        }
+
+        // The payload of the first frame is not overwritten while it is alive
+        let first = framer.decode_frame(&frame).unwrap().unwrap();
+        assert!(matches!(
+            framer.decode_frame(&frame),
+            Err(CompressingFramerError::FrameInUse)
+        ));
+        assert!(first.iter().all(|&byte| byte == 1));
+
+        drop(first);
+        let second = framer.decode_frame(&frame).unwrap().unwrap();
+        assert_eq!(second.len(), LEN);
+    }
+
+    fn compression_suite<T: Transport>(transport: T, addr: &T::Address, offer: bool) {
+        const REVERSE: u64 = 0xC0DE;
+        // Below the threshold, compressed in one frame, then fragmented
+        const SIZES: [usize; 3] = [16, 32 * 1024, 256 * 1024];
+
+        self::init_test_logger();
+
+        let framer = CompressingFramer::new(LengthPrefixFramer::new(64 * 1024)).threshold(1024);
+
+        let mut server = Server::create(framer.clone(), &transport, addr).unwrap();
+        let mut client = Client::connect(framer.offer(offer), &transport, addr).unwrap();
+
+        let server_thread = thread::spawn(move || {
+            let mut served = 0;
+            while served < SIZES.len() {
+                let request = match server.receive_message::<Request>() {
+                    Ok(Some(request)) => request,
+                    Ok(None) => continue,
+                    Err(e) => panic!("Failed to receive request, {}", e),
+                };
+
+                let mut data = request.args()[0].downcast_slice::<u8>().unwrap().to_vec();
+                data.reverse();
+                let response = Response::with_request(
+                    &request,
+                    Argument::from_slice(&data, ArgumentFlag::default()),
+                );
+                server
+                    .send_message(&response)
+                    .expect("Failed to send response");
+                served += 1;
+            }
+        });
+
+        for size in SIZES {
+            let data: Vec<u8> = (0..size).map(|i| (i / 64 % 13) as u8).collect();
+            let request =
+                Request::with_arg(REVERSE, Argument::from_slice(&data, ArgumentFlag::ARG_IN));
+            let response = client.invoke(&request).expect("Invoke failed");
+
+            let reversed = response.ret_value().downcast_slice::<u8>().unwrap();
+            assert!(reversed.iter().rev().eq(data.iter()));
+        }
+        assert_eq!(
+            client
+                .features()
+                .unwrap()
+                .contains(HandshakeFeatures::COMPRESSION),
+            offer
+        );
+
+        server_thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_compression_tcp() {
+        let transport = TcpTransportBuilder::new().build();
+        let addr = unused_tcp_addr();
+
+        compression_suite(transport, addr.as_str(), true);
+    }
+
+    #[test]
+    fn test_compression_loopback() {
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+
+        compression_suite(transport, addr.as_str(), true);
+    }
+
+    #[test]
+    fn test_compression_not_offered() {
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+
+        compression_suite(transport, addr.as_str(), false);
+    }
+
+    fn listener_suite<T: Transport>(transport: T, addr: &T::Address) {
+        const ADD_U64: u64 = 0xCAFE;
+        const CLIENTS: u64 = 4;
+        const REQUESTS: u64 = 100;
+
+        self::init_test_logger();
+
+        let framer = LengthPrefixFramer::new(4096);
+        let listener = Listener::bind(framer, &transport, addr).unwrap();
+
+        thread::scope(|scope| {
+            // One worker per accepted client
+            scope.spawn(|| {
+                for _ in 0..CLIENTS {
+                    let mut server = listener.accept().expect("Accept failed");
+                    scope.spawn(move || {
+                        let mut served = 0;
+                        while served < REQUESTS {
+                            let request = match server.receive_message::<Request>() {
+                                Ok(Some(request)) => request,
+                                Ok(None) => continue,
+                                Err(e) => panic!("Failed to receive request, {}", e),
+                            };
+                            assert_eq!(request.method_id(), ADD_U64);
+
+                            let lhs = request.args()[0].downcast::<u64>().unwrap();
+                            let rhs = request.args()[1].downcast::<u64>().unwrap();
+                            let result = lhs + rhs;
+                            let response = Response::with_request(
+                                &request,
+                                Argument::from_ref(&result, ArgumentFlag::default()),
+                            );
+                            server
+                                .send_message(&response)
+                                .expect("Failed to send response");
+                            served += 1;
+                        }
+                        debug!("[Server] Worker finished");
+                    });
+                }
+            });
+
+            // Every client sums its own series concurrently
+            for id in 1..=CLIENTS {
+                let transport = &transport;
+                scope.spawn(move || {
+                    let mut client = Client::connect(framer, transport, addr).unwrap();
+
+                    let mut value = 0u64;
+                    for _ in 0..REQUESTS {
+                        let request = Request::with_args(
+                            ADD_U64,
+                            vec![
+                                Argument::from_ref(&value, ArgumentFlag::ARG_IN),
+                                Argument::from_ref(&id, ArgumentFlag::ARG_IN),
+                            ],
+                        );
+                        let response = client.invoke(&request).expect("Invoke failed");
+                        value = response.ret_value().downcast::<u64>().unwrap();
+                    }
+                    assert_eq!(value, id * REQUESTS);
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn test_listener() {
+        let transport = ShmemTransportBuilder::new().build();
+        let addr = unique_shmem_addr();
+
+        listener_suite(transport, addr.as_str());
+    }
+
+    #[test]
+    fn test_listener_uds() {
+        let transport = UdsTransportBuilder::new().build();
+        let addr = unique_socket_path();
+
+        listener_suite(transport, addr.as_str());
+    }
+
+    fn fragment_suite<T: Transport>(transport: T, addr: &T::Address) {
+        const REVERSE: u64 = 0xF00D;
+        // Around the frame limit, then well beyond the transport buffer
+        const SIZES: [usize; 5] = [16, 4000, 4096, 5000, 1024 * 1024];
+
+        self::init_test_logger();
+
+        let framer = LengthPrefixFramer::new(4096);
+
+        let mut server = Server::create(framer, &transport, addr).unwrap();
+        let mut client = Client::connect(framer, &transport, addr).unwrap();
+
+        let server_thread = thread::spawn(move || {
+            let mut served = 0;
+            while served < SIZES.len() {
+                let request = match server.receive_message::<Request>() {
+                    Ok(Some(request)) => request,
+                    Ok(None) => continue,
+                    Err(e) => panic!("Failed to receive request, {}", e),
+                };
+                assert_eq!(request.method_id(), REVERSE);
+
+                let mut data = request.args()[0].downcast_slice::<u8>().unwrap().to_vec();
+                data.reverse();
+                let response = Response::with_request(
+                    &request,
+                    Argument::from_slice(&data, ArgumentFlag::default()),
+                );
+                server
+                    .send_message(&response)
+                    .expect("Failed to send response");
+                served += 1;
+            }
+        });
+
+        for size in SIZES {
+            let data: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+            let request =
+                Request::with_arg(REVERSE, Argument::from_slice(&data, ArgumentFlag::ARG_IN));
+            let response = client.invoke(&request).expect("Invoke failed");
+
+            let reversed = response.ret_value().downcast_slice::<u8>().unwrap();
+            assert_eq!(reversed.len(), size);
+            assert!(reversed.iter().rev().eq(data.iter()));
+        }
+
+        server_thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_fragment() {
+        let transport = ShmemTransportBuilder::new().build();
+        let addr = unique_shmem_addr();
+
+        fragment_suite(transport, addr.as_str());
+    }
+
+    #[test]
+    fn test_fragment_uds() {
+        let transport = UdsTransportBuilder::new().build();
+        let addr = unique_socket_path();
+
+        fragment_suite(transport, addr.as_str());
+    }
+
+    #[test]
+    fn test_fragment_loopback() {
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+
+        fragment_suite(transport, addr.as_str());
+    }
+
+    #[test]
+    fn test_fragment_too_large() {
+        const LIMIT: usize = 64 * 1024;
+        const OVERSIZED: u64 = 0xB16;
+        const SMALL: u64 = 0x5A11;
+
+        self::init_test_logger();
+
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+        let framer = LengthPrefixFramer::new(4096);
+
+        let mut server = Server::create(framer, &transport, addr.as_str()).unwrap();
+        let mut client = Client::connect(framer, &transport, addr.as_str()).unwrap();
+        server.set_max_message_len(LIMIT);
+        assert_eq!(server.max_message_len(), LIMIT);
+
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                let data = vec![0u8; 4 * LIMIT];
+                let request =
+                    Request::with_arg(OVERSIZED, Argument::from_slice(&data, ArgumentFlag::ARG_IN));
+                client.send_message(&request).unwrap();
+                client.send_message(&Request::empty(SMALL)).unwrap();
+            });
+
+            // The oversized message is dropped, the connection stays usable
+            let mut rejected = false;
+            let request = loop {
+                match server.receive_message::<Request>() {
+                    Ok(Some(request)) => break request,
+                    Ok(None) => {}
+                    Err(IpcError::FragmentError(FragmentError::MessageTooLarge {
+                        limit, ..
+                    })) => {
+                        assert_eq!(limit, LIMIT);
+                        rejected = true;
+                    }
+                    // The rest of the oversized message
+                    Err(IpcError::FragmentError(FragmentError::OutOfOrder { .. })) => {
+                        assert!(rejected)
+                    }
+                    Err(e) => panic!("Failed to receive request, {}", e),
+                }
+            };
+            assert!(rejected);
+            assert_eq!(request.method_id(), SMALL);
+        });
+    }
+
+    #[test]
+    fn test_fragment_only_when_too_large() {
+        const BUFFER_SIZE: usize = 64 * 1024;
+        const SIZE: usize = 24 * 1024;
+        const COUNT: u64 = 8;
+
+        self::init_test_logger();
+
+        let transport = LoopbackTransportBuilder::new()
+            .buffer_size(BUFFER_SIZE)
+            .build();
+        let addr = unique_loopback_addr();
+        let framer = LengthPrefixFramer::new(BUFFER_SIZE);
+
+        let mut server = Server::create(framer, &transport, addr.as_str()).unwrap();
+        let mut client = Client::connect(framer, &transport, addr.as_str()).unwrap();
+        // Any message sent in fragments is rejected
+        server.set_max_message_len(SIZE / 2);
+
+        // Messages not fitting in the room left wait for the buffer to drain
+        let server_thread = thread::spawn(move || {
+            let mut received = 0;
+            while received < COUNT {
+                match server.receive_message::<Request>() {
+                    Ok(Some(request)) => {
+                        assert_eq!(request.method_id(), received);
+                        assert_eq!(
+                            request.args()[0].downcast_slice::<u8>().unwrap().len(),
+                            SIZE
+                        );
+                        received += 1;
+                    }
+                    Ok(None) => thread::sleep(Duration::from_millis(1)),
+                    Err(e) => panic!("Failed to receive request, {}", e),
+                }
+            }
+        });
+
+        let data = vec![0u8; SIZE];
+        for method_id in 0..COUNT {
+            let request =
+                Request::with_arg(method_id, Argument::from_slice(&data, ArgumentFlag::ARG_IN));
+            client
+                .send_message(&request)
+                .expect("Failed to send request");
+        }
+
+        server_thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_shared_arena() {
+        const REVERSE: u64 = 0xBEEF;
+        const SIZE: usize = 64 * 1024;
+
+        self::init_test_logger();
+
+        let transport = ShmemTransportBuilder::new().arena_size(1024 * 1024).build();
+        let addr = unique_shmem_addr();
+        let framer = LengthPrefixFramer::new(4096);
+
+        let mut server = Server::create(framer, &transport, addr.as_str()).unwrap();
+        let mut client = Client::connect(framer, &transport, addr.as_str()).unwrap();
+
+        let server_thread = thread::spawn(move || {
+            let arena = server.arena().expect("server should create the arena");
+            let mut request = loop {
+                match server.receive_message::<Request>() {
+                    Ok(Some(request)) => break request,
+                    Ok(None) => continue,
+                    Err(e) => panic!("Failed to receive request, {}", e),
+                }
+            };
+            assert_eq!(request.method_id(), REVERSE);
+            assert!(request.args()[0].downcast_slice::<u8>().is_err());
+
+            unsafe { request.resolve_shared(arena.as_mut_ptr(), arena.len()) }.unwrap();
+            let data = unsafe { request.args()[0].downcast_mut_slice::<u8>().unwrap() };
+            data.reverse();
+
+            let response = Response::with_request(
+                &request,
+                Argument::from_value(data.len(), ArgumentFlag::default()),
+            );
+            server
+                .send_message(&response)
+                .expect("Failed to send response");
+        });
+
+        let arena = client.arena().expect("client should map the arena");
+        let mut buf = arena.alloc(SIZE).unwrap();
+        buf.iter_mut()
+            .enumerate()
+            .for_each(|(i, byte)| *byte = (i % 251) as u8);
+        {
+            let offset = buf.offset();
+            let mut request = Request::with_arg(
+                REVERSE,
+                Argument::from_shared_slice(
+                    offset,
+                    &mut buf,
+                    ArgumentFlag::ARG_IN | ArgumentFlag::ARG_OUT,
+                ),
+            );
+            let response = client.invoke(&request).expect("Invoke failed");
+            assert_eq!(response.ret_value().downcast::<usize>().unwrap(), SIZE);
+            request.update_from(&response).unwrap();
+        }
+        assert!(
+            buf.iter()
+                .rev()
+                .enumerate()
+                .all(|(i, &byte)| byte == (i % 251) as u8)
+        );
+
+        server_thread.join().unwrap();
+    }
+
+    fn dispatcher_suite<T: Transport>(transport: T, addr: &T::Address) {
+        const ADD_U64: u64 = 0xCAFE;
+        const BATCH: usize = 4;
+        const ROUNDS: u64 = 25;
+        const THREADS: u64 = 4;
+        const REQUESTS: u64 = 50;
+
+        self::init_test_logger();
+
+        let framer = LengthPrefixFramer::new(4096);
+        let mut server = Server::create(framer, &transport, addr).unwrap();
+        let client = Client::connect(framer, &transport, addr).unwrap();
+        let dispatcher = Dispatcher::new(client, BATCH);
+
+        thread::scope(|scope| {
+            // Answers every batch of requests in reverse order
+            scope.spawn(move || {
+                let total = ROUNDS as usize * BATCH + (THREADS * REQUESTS) as usize;
+                let mut served = 0;
+                let mut batch = Vec::with_capacity(BATCH);
+                while served < total {
+                    let request = match server.receive_message::<Request>() {
+                        Ok(Some(request)) => request,
+                        Ok(None) => continue,
+                        Err(e) => panic!("Failed to receive request, {}", e),
+                    };
+                    assert_eq!(request.method_id(), ADD_U64);
+                    batch.push(request);
+                    if batch.len() < BATCH {
+                        continue;
+                    }
+
+                    while let Some(request) = batch.pop() {
+                        let lhs = request.args()[0].downcast::<u64>().unwrap();
+                        let rhs = request.args()[1].downcast::<u64>().unwrap();
+                        let response = Response::with_request(
+                            &request,
+                            Argument::from_value(lhs + rhs, ArgumentFlag::default()),
+                        );
+                        server
+                            .send_message(&response)
+                            .expect("Failed to send response");
+                        served += 1;
+                    }
+                }
+                debug!("[Server] Served {} requests", served);
+            });
+
+            let add_u64 = |lhs: u64, rhs: u64| {
+                Request::with_args(
+                    ADD_U64,
+                    vec![
+                        Argument::from_value(lhs, ArgumentFlag::ARG_IN),
+                        Argument::from_value(rhs, ArgumentFlag::ARG_IN),
+                    ],
+                )
+            };
+
+            // Responses arrive in reverse, tickets are redeemed in order
+            for round in 0..ROUNDS {
+                let tickets = (0..BATCH as u64)
+                    .map(|i| {
+                        let ticket = dispatcher.invoke_async(&add_u64(round, i)).unwrap();
+                        assert!(dispatcher.in_flight() <= dispatcher.window());
+                        (i, ticket)
+                    })
+                    .collect::<Vec<_>>();
+
+                for (i, ticket) in tickets {
+                    let request_id = ticket.request_id();
+                    let frame = dispatcher.wait(ticket).expect("Wait failed");
+                    assert_eq!(frame.request_id(), request_id);
+
+                    let response = frame.response().unwrap();
+                    assert_eq!(response.request_id(), request_id);
+                    assert_eq!(response.ret_value().downcast::<u64>().unwrap(), round + i);
+                }
+            }
+            assert_eq!(dispatcher.in_flight(), 0);
+
+            // Concurrent callers share the connection
+            let dispatcher = &dispatcher;
+            for id in 1..=THREADS {
+                scope.spawn(move || {
+                    let mut value = 0u64;
+                    for _ in 0..REQUESTS {
+                        let frame = dispatcher
+                            .invoke(&add_u64(value, id))
+                            .expect("Invoke failed");
+                        value = frame
+                            .response()
+                            .unwrap()
+                            .ret_value()
+                            .downcast::<u64>()
+                            .unwrap();
+                    }
+                    assert_eq!(value, id * REQUESTS);
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn test_dispatcher() {
+        let transport = ShmemTransportBuilder::new().build();
+        let addr = unique_shmem_addr();
+
+        dispatcher_suite(transport, addr.as_str());
+    }
+
+    #[test]
+    fn test_dispatcher_loopback() {
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+
+        dispatcher_suite(transport, addr.as_str());
+    }
+
+    #[test]
+    fn test_dispatcher_wait_timeout() {
+        const TIMEOUT: Duration = Duration::from_millis(50);
+
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+        let framer = LengthPrefixFramer::new(4096);
+
+        let mut server = Server::create(framer, &transport, addr.as_str()).unwrap();
+        let client = Client::connect(framer, &transport, addr.as_str()).unwrap();
+        let dispatcher = Dispatcher::new(client, 1);
+
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+        thread::scope(|scope| {
+            // Receives the request but never answers it
+            scope.spawn(move || {
+                while server.receive_message::<Request>().unwrap().is_none() {}
+                let _ = done_rx.recv();
+            });
+
+            let ticket = dispatcher.invoke_async(&Request::empty(0xDEAD)).unwrap();
+            let result = dispatcher.wait_timeout(ticket, TIMEOUT);
+
+            assert!(matches!(result, Err(IpcError::Timeout(timeout)) if timeout == TIMEOUT));
+            assert_eq!(dispatcher.in_flight(), 0);
+            drop(done_tx);
+        });
+    }
+
+    #[test]
+    fn test_dispatcher_post() {
+        const POSTED: u64 = 10;
+
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+        let framer = LengthPrefixFramer::new(4096);
+
+        let mut server = Server::create(framer, &transport, addr.as_str()).unwrap();
+        let client = Client::connect(framer, &transport, addr.as_str()).unwrap();
+        let dispatcher = Dispatcher::new(client, 1);
+
+        thread::scope(|scope| {
+            // Counts no-reply requests, answering only the final query
+            scope.spawn(move || {
+                let mut posted = 0u64;
+                loop {
+                    let request = match server.receive_message::<Request>() {
+                        Ok(Some(request)) => request,
+                        Ok(None) => continue,
+                        Err(e) => panic!("Failed to receive request, {}", e),
+                    };
+                    if request.is_no_reply() {
+                        posted += 1;
+                        continue;
+                    }
+
+                    let response = Response::with_request(
+                        &request,
+                        Argument::from_value(posted, ArgumentFlag::default()),
+                    );
+                    server
+                        .send_message(&response)
+                        .expect("Failed to send response");
+                    break;
+                }
+            });
+
+            for _ in 0..POSTED {
+                let mut request = Request::empty(0xCAFE);
+                request.set_flag(RequestFlag::NO_REPLY);
+                dispatcher.post(&request).expect("Post failed");
+                assert_eq!(dispatcher.in_flight(), 0);
+            }
+
+            let frame = dispatcher
+                .invoke(&Request::empty(0xBEEF))
+                .expect("Invoke failed");
+            let response = frame.response().unwrap();
+            assert_eq!(response.ret_value().downcast::<u64>(), Ok(POSTED));
+        });
+    }
+
+    #[test]
+    fn test_dispatcher_fragments_both_ways() {
+        const DOWNLOAD: u64 = 0xD0;
+        const UPLOAD: u64 = 0x0D;
+        // Both well beyond the transport buffer
+        const SIZE: usize = 1024 * 1024;
+
+        self::init_test_logger();
+
+        let transport = LoopbackTransportBuilder::new()
+            .buffer_size(16 * 1024)
+            .build();
+        let addr = unique_loopback_addr();
+        let framer = LengthPrefixFramer::new(4096);
+
+        let mut server = Server::create(framer, &transport, addr.as_str()).unwrap();
+        let client = Client::connect(framer, &transport, addr.as_str()).unwrap();
+        let dispatcher = Dispatcher::new(client, 2);
+        let data: Vec<u8> = (0..SIZE).map(|i| (i % 251) as u8).collect();
+
+        thread::scope(|scope| {
+            // Serves requests in order, reading nothing while sending a response
+            let data = &data;
+            scope.spawn(move || {
+                for _ in 0..2 {
+                    let request = loop {
+                        match server.receive_message::<Request>() {
+                            Ok(Some(request)) => break request,
+                            Ok(None) => continue,
+                            Err(e) => panic!("Failed to receive request, {}", e),
+                        }
+                    };
+
+                    let response = match request.method_id() {
+                        DOWNLOAD => Response::with_request(
+                            &request,
+                            Argument::from_slice(data, ArgumentFlag::default()),
+                        ),
+                        UPLOAD => {
+                            let upload = request.args()[0].downcast_slice::<u8>().unwrap();
+                            assert!(upload.iter().eq(data.iter()));
+                            Response::with_request(
+                                &request,
+                                Argument::from_value(upload.len(), ArgumentFlag::default()),
+                            )
+                        }
+                        method_id => panic!("Unexpected method {}", method_id),
+                    };
+                    server
+                        .send_message(&response)
+                        .expect("Failed to send response");
+                }
+            });
+
+            // The download response fills the buffer towards the client while the
+            // upload request still streams towards the server
+            let download = dispatcher.invoke_async(&Request::empty(DOWNLOAD)).unwrap();
+            let dispatcher = &dispatcher;
+            let uploader = scope.spawn(move || {
+                let request =
+                    Request::with_arg(UPLOAD, Argument::from_slice(data, ArgumentFlag::ARG_IN));
+                let frame = dispatcher.invoke(&request).expect("Upload failed");
+                assert_eq!(
+                    frame.response().unwrap().ret_value().downcast::<usize>(),
+                    Ok(SIZE)
+                );
+            });
+
+            let frame = dispatcher.wait(download).expect("Download failed");
+            let response = frame.response().unwrap();
+            assert_eq!(response.ret_value().downcast_slice::<u8>(), Ok(&data[..]));
+
+            uploader.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn test_dispatcher_batch() {
+        const ADD_U64: u64 = 0xCAFE;
+        const ROUNDS: u64 = 20;
+
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+        let framer = LengthPrefixFramer::new(4096);
+
+        let mut server = Server::create(framer, &transport, addr.as_str()).unwrap();
+        let client = Client::connect(framer, &transport, addr.as_str()).unwrap();
+        let dispatcher = Dispatcher::new(client, 2);
+
+        thread::scope(|scope| {
+            // Answers every batch with a single batch, no-reply requests add to a total
+            scope.spawn(move || {
+                let mut total = 0u64;
+                for _ in 0..ROUNDS {
+                    let batch = loop {
+                        match server.receive_message::<RequestBatch>() {
+                            Ok(Some(batch)) => break batch,
+                            Ok(None) => continue,
+                            Err(e) => panic!("Failed to receive batch, {}", e),
+                        }
+                    };
+
+                    let mut responses = ResponseBatch::new();
+                    for request in batch.requests() {
+                        assert_eq!(request.method_id(), ADD_U64);
+                        let lhs = request.args()[0].downcast::<u64>().unwrap();
+                        let rhs = request.args()[1].downcast::<u64>().unwrap();
+                        if request.is_no_reply() {
+                            total += lhs + rhs;
+                            continue;
+                        }
+                        responses.push(Response::with_request(
+                            request,
+                            Argument::from_value(total + lhs + rhs, ArgumentFlag::default()),
+                        ));
+                    }
+                    server
+                        .send_message(&responses)
+                        .expect("Failed to send responses");
+                }
+            });
+
+            let add_u64 = |lhs: u64, rhs: u64, flag: RequestFlag| {
+                let mut request = Request::with_args(
+                    ADD_U64,
+                    vec![
+                        Argument::from_value(lhs, ArgumentFlag::ARG_IN),
+                        Argument::from_value(rhs, ArgumentFlag::ARG_IN),
+                    ],
+                );
+                request.set_flag(flag);
+                request
+            };
+
+            let mut total = 0u64;
+            for round in 0..ROUNDS {
+                // More replies than the window, deferred calls ahead of them
+                let batch = RequestBatch::with_requests([
+                    add_u64(round, 1, RequestFlag::NO_REPLY),
+                    add_u64(round, 2, RequestFlag::NO_REPLY),
+                    add_u64(0, 1, RequestFlag::empty()),
+                    add_u64(0, 2, RequestFlag::empty()),
+                    add_u64(0, 3, RequestFlag::empty()),
+                ]);
+                total += 2 * round + 3;
+
+                let tickets = dispatcher.invoke_batch_async(&batch).unwrap();
+                assert_eq!(tickets.len(), 3);
+
+                for (rhs, ticket) in (1..).zip(tickets) {
+                    let frame = dispatcher.wait(ticket).expect("Wait failed");
+                    let response = frame.response().unwrap();
+                    assert_eq!(response.ret_value().downcast::<u64>(), Ok(total + rhs));
+                }
+                assert_eq!(dispatcher.in_flight(), 0);
+            }
+        });
+    }
+
+    #[test]
+    fn test_handshake() {
+        self::init_test_logger();
+
+        let framer = LengthPrefixFramer::new(4096);
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+
+        let mut server = Server::create(framer, &transport, addr.as_str()).unwrap();
+        let mut client = Client::connect(framer, &transport, addr.as_str()).unwrap();
+        assert_eq!(server.features(), None);
+
+        // Both sides send first, so one thread can drive both
+        server.set_timeout(Some(Duration::from_millis(10)));
+        assert!(matches!(server.handshake(), Err(IpcError::Timeout(_))));
+        // A plain framer offers none of the framer features
+        let expected = HandshakeFeatures::SUPPORTED - HandshakeFeatures::FRAMER;
+        assert_eq!(client.handshake().unwrap(), expected);
+        assert_eq!(server.handshake().unwrap(), expected);
+        assert_eq!(server.features(), client.features());
+    }
+
+    #[test]
+    fn test_handshake_mismatch() {
+        self::init_test_logger();
+
+        let framer = LengthPrefixFramer::new(4096);
+        let transport = LoopbackTransportBuilder::new().build();
+        let addr = unique_loopback_addr();
+
+        let mut server = Server::create(framer, &transport, addr.as_str()).unwrap();
+        let endpoint = transport.connect(&addr).unwrap();
+        let mut client = Peer::<_, LoopbackTransport>::new(framer, endpoint);
+
+        let handshake = Handshake::default().with_protocol_version(PROTOCOL_VERSION + 1);
+        client.send_message(&handshake).unwrap();
+
+        let result = server.receive_message::<Request>();
+        assert!(matches!(
+            result,
+            Err(IpcError::HandshakeError(
+                HandshakeError::ProtocolVersionMismatch { .. }
+            ))
+        ));
+    }
 }
@@ -12,57 +12,400 @@
  * See the Mulan PSL v2 for more details.
  */
 
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use super::{
-    bytewise::{BytewiseBuffer, BytewiseReadOwned, BytewiseWrite, BytewiseWriter},
+    bytewise::{BytewiseBuffer, BytewiseError, BytewiseReadOwned, BytewiseWrite, BytewiseWriter},
+    dispatcher::ResponseFrame,
     error::IpcError,
+    fragment::{self, Fragmentation, Reassembly},
     framer::{Frame, FrameBuf, Framer},
-    message::{Request, Response},
-    transport::{Endpoint, ReadBuf, Transport, WriteBuf},
+    message::{Handshake, HandshakeFeatures, Request, Response},
+    transport::{self, BulkRegion, Endpoint, ReadBuf, Transport, WriteBuf},
 };
 
+/// Progress of the handshake which opens a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HandshakeState {
+    /// Nothing exchanged yet, offering the given features.
+    Pending(HandshakeFeatures),
+    /// Our handshake is sent, waiting for the peer's.
+    Sent(HandshakeFeatures),
+    /// Both sides agreed on the given features.
+    Done(HandshakeFeatures),
+}
+
 #[derive(Debug)]
 pub struct Peer<F: Framer, T: Transport> {
     framer: F,
     endpoint: T::Endpoint,
+    timeout: Option<Duration>,
+    handshake: HandshakeState,
+    reassembly: Reassembly,
+    /// Fragments of a sent message still to go out.
+    fragments: Option<Fragmentation>,
 }
 
 impl<F: Framer, T: Transport> Peer<F, T> {
+    /// Wraps an endpoint which exchanges messages right away, without a handshake.
     #[inline]
     pub fn new(framer: F, endpoint: T::Endpoint) -> Self {
-        Self { framer, endpoint }
+        Self {
+            framer,
+            endpoint,
+            timeout: None,
+            handshake: HandshakeState::Done(HandshakeFeatures::empty()),
+            reassembly: Reassembly::default(),
+            fragments: None,
+        }
+    }
+
+    /// Wraps an endpoint whose first message in each direction is a `Handshake`.
+    ///
+    /// The handshake runs on first use, or explicitly by `handshake`.
+    #[inline]
+    pub fn with_handshake(framer: F, endpoint: T::Endpoint, features: HandshakeFeatures) -> Self {
+        // Framer features are offered only if the framer implements them
+        let features =
+            features.difference(HandshakeFeatures::FRAMER) | (features & framer.features());
+
+        Self {
+            handshake: HandshakeState::Pending(features),
+            ..Self::new(framer, endpoint)
+        }
+    }
+
+    /// Returns the negotiated features, or `None` while the handshake is incomplete.
+    #[inline]
+    pub fn features(&self) -> Option<HandshakeFeatures> {
+        match self.handshake {
+            HandshakeState::Done(features) => Some(features),
+            _ => None,
+        }
+    }
+
+    /// Exchanges handshakes with the peer if not done yet, returning the negotiated features.
+    ///
+    /// Fails with `IpcError::HandshakeError` if the peer is incompatible.
+    pub fn handshake(&mut self) -> Result<HandshakeFeatures, IpcError<F, T>> {
+        let deadline = Self::deadline(self.timeout);
+        self.handshake_until(deadline)
+    }
+
+    /// Returns the bulk data arena shared with the peer, if the transport provides one.
+    ///
+    /// Shared arguments received from the peer are resolved with `Request::resolve_shared`.
+    #[inline]
+    pub fn arena(&self) -> Option<Arc<dyn BulkRegion>> {
+        self.endpoint.arena()
+    }
+
+    /// Returns the largest message accepted from the peer in fragments.
+    #[inline]
+    pub fn max_message_len(&self) -> usize {
+        self.reassembly.max_len()
+    }
+
+    /// Sets the largest message accepted from the peer in fragments. A message
+    /// growing beyond it is discarded with `FragmentError::MessageTooLarge`.
+    #[inline]
+    pub fn set_max_message_len(&mut self, value: usize) {
+        self.reassembly = Reassembly::new(value);
+    }
+
+    /// Returns the timeout applied to every send, receive and invoke.
+    #[inline]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
     }
 
+    /// Sets the timeout applied to every send, receive and invoke, `None` blocks forever.
+    ///
+    /// Operations that do not complete in time fail with `IpcError::Timeout`.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Sends a message, in fragments if it does not fit in one frame.
+    ///
+    /// A fragmented message counts as sent once its first fragment is out. If the
+    /// timeout expires before the others, they stay queued and go out ahead of the
+    /// next message, or by `flush`.
     pub fn send_message<B: BytewiseWrite>(&mut self, message: &B) -> Result<(), IpcError<F, T>> {
-        let mut write_buf = self
-            .endpoint
-            .write()
-            .map_err(|e| IpcError::TransportError(e))?;
+        let deadline = Self::deadline(self.timeout);
+
+        self.handshake_until(deadline)?;
+        self.send_message_until(message, deadline)
+    }
+
+    /// Sends the queued fragments of a message, failing with `IpcError::Timeout`
+    /// if some are still left once the timeout expires.
+    pub fn flush(&mut self) -> Result<(), IpcError<F, T>> {
+        let deadline = Self::deadline(self.timeout);
+        self.flush_until(deadline)
+    }
 
-        let mut frame_buf = self.framer.encode_frame(&mut write_buf);
+    /// Returns `true` unless fragments of a message are still queued, see `flush`.
+    #[inline]
+    pub fn is_flushed(&self) -> bool {
+        self.fragments.is_none()
+    }
+
+    pub fn receive_message<B: BytewiseReadOwned>(&mut self) -> Result<Option<B>, IpcError<F, T>> {
+        let deadline = Self::deadline(self.timeout);
 
-        let mut writer = BytewiseBuffer::new(frame_buf.as_mut());
-        message.write_to(&mut writer)?;
+        self.handshake_until(deadline)?;
+        self.receive_message_until(deadline)
+    }
 
-        let payload_len = writer.written_bytes();
-        let frame_len = frame_buf
-            .finalize(payload_len)
-            .map_err(|e| IpcError::FramerError(e))?;
+    pub fn invoke(&mut self, request: &Request) -> Result<Response<'_>, IpcError<F, T>> {
+        self.invoke_timeout(request, self.timeout)
+    }
 
-        write_buf
-            .submit(frame_len)
-            .map_err(|e| IpcError::TransportError(e))?;
+    /// Invokes a request, failing with `IpcError::Timeout` if the whole round trip
+    /// does not complete within `timeout`.
+    pub fn invoke_timeout(
+        &mut self,
+        request: &Request,
+        timeout: Option<Duration>,
+    ) -> Result<Response<'_>, IpcError<F, T>> {
+        let deadline = Self::deadline(timeout);
+
+        self.handshake_until(deadline)?;
+        self.send_message_until(request, deadline)?;
+
+        let response = loop {
+            match self.receive_message_until(deadline)? {
+                Some(resp) => break resp,
+                None => continue,
+            }
+        };
+
+        Ok(response)
+    }
+
+    /// Sends our handshake, then waits for the peer's one.
+    ///
+    /// Both sides send before receiving, so neither waits for the other to speak first.
+    fn handshake_until(
+        &mut self,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<HandshakeFeatures, IpcError<F, T>> {
+        if let HandshakeState::Pending(features) = self.handshake {
+            self.send_message_until(&Handshake::local(features), deadline)?;
+            self.handshake = HandshakeState::Sent(features);
+        }
+
+        match self.handshake {
+            HandshakeState::Sent(features) => {
+                let remote = loop {
+                    if let Some(remote) = self.receive_message_until::<Handshake>(deadline)? {
+                        break remote;
+                    }
+                };
+                let features = Handshake::local(features).negotiate(&remote)?;
+                self.framer.set_features(features);
+
+                self.handshake = HandshakeState::Done(features);
+                Ok(features)
+            }
+            HandshakeState::Done(features) => Ok(features),
+            HandshakeState::Pending(_) => unreachable!(),
+        }
+    }
+
+    #[inline]
+    fn deadline(timeout: Option<Duration>) -> Option<(Instant, Duration)> {
+        timeout.map(|timeout| (Instant::now() + timeout, timeout))
+    }
+
+    /// Sends a message in a single frame, or in fragments if it does not fit.
+    ///
+    /// A message that does not fit in the room left waits for the peer to drain the
+    /// transport buffer, it is only fragmented if it does not fit in an empty one.
+    fn send_message_until<B: BytewiseWrite>(
+        &mut self,
+        message: &B,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<(), IpcError<F, T>> {
+        // Fragments of the previous message go first, the peer expects them next
+        self.flush_until(deadline)?;
+
+        let mut min_len = 1;
+        loop {
+            let mut write_buf = Self::write_buf_until(&mut self.endpoint, min_len, deadline)?;
+            let mut frame_buf = self.framer.encode_frame(&mut write_buf);
+
+            let mut writer = BytewiseBuffer::new(frame_buf.as_mut());
+            let capacity = match message.write_to(&mut writer) {
+                Ok(()) => {
+                    let payload_len = writer.written_bytes();
+                    let frame_len = frame_buf
+                        .finalize(payload_len)
+                        .map_err(|e| IpcError::FramerError(e))?;
+
+                    write_buf
+                        .submit(frame_len)
+                        .map_err(|e| IpcError::TransportError(e))?;
+
+                    return Ok(());
+                }
+                Err(BytewiseError::InsufficientBuffer { capacity, .. }) => capacity,
+                Err(e) => return Err(e.into()),
+            };
+            drop(frame_buf);
+            drop(write_buf);
+
+            if min_len == usize::MAX {
+                return self.send_fragmented_until(message, capacity.saturating_mul(2), deadline);
+            }
+            min_len = usize::MAX;
+        }
+    }
+
+    /// Stages a message too large for one frame, then streams it as fragments
+    /// through whatever buffer space the transport offers.
+    ///
+    /// Every fragment is bound by the deadline, though fragments keep going out
+    /// past it while the transport has room. Once the first one is out the
+    /// message counts as sent: the others are queued if time runs out, for
+    /// `flush_until` to send later.
+    fn send_fragmented_until<B: BytewiseWrite>(
+        &mut self,
+        message: &B,
+        hint: usize,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<(), IpcError<F, T>> {
+        let mut fragments = Fragmentation::new(fragment::stage(message, hint)?);
+
+        match self.send_fragments_until(&mut fragments, deadline) {
+            Err(IpcError::Timeout(_)) if fragments.is_started() => {
+                self.fragments = Some(fragments);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Sends the fragments still queued of an earlier message, if any.
+    fn flush_until(&mut self, deadline: Option<(Instant, Duration)>) -> Result<(), IpcError<F, T>> {
+        let Some(mut fragments) = self.fragments.take() else {
+            return Ok(());
+        };
+
+        let result = self.send_fragments_until(&mut fragments, deadline);
+        if !fragments.is_done() {
+            self.fragments = Some(fragments);
+        }
+        result
+    }
+
+    fn send_fragments_until(
+        &mut self,
+        fragments: &mut Fragmentation,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<(), IpcError<F, T>> {
+        while !fragments.is_done() {
+            // Waits for room for a sizeable fragment, rather than sending slivers
+            let mut write_buf =
+                Self::write_buf_until(&mut self.endpoint, fragment::MIN_FRAGMENT_LEN, deadline)?;
+            let mut frame_buf = self.framer.encode_frame(&mut write_buf);
+
+            let (fragment, chunk) = fragments.next(frame_buf.len());
+            if chunk.is_empty() {
+                // Not even an empty transport buffer holds more than a frame header
+                return Err(IpcError::BytewiseError(BytewiseError::InsufficientBuffer {
+                    required: 1,
+                    capacity: 0,
+                }));
+            }
+
+            let chunk_len = chunk.len();
+            frame_buf[..chunk_len].copy_from_slice(chunk);
+            frame_buf.set_fragment(fragment);
+
+            let frame_len = frame_buf
+                .finalize(chunk_len)
+                .map_err(|e| IpcError::FramerError(e))?;
+
+            write_buf
+                .submit(frame_len)
+                .map_err(|e| IpcError::TransportError(e))?;
+
+            fragments.advance(chunk_len);
+        }
 
         Ok(())
     }
 
-    pub fn receive_message<B: BytewiseReadOwned>(&mut self) -> Result<Option<B>, IpcError<F, T>> {
-        let read_buf = self
-            .endpoint
-            .read()
-            .map_err(|e| IpcError::TransportError(e))?;
+    /// Acquires a write buffer of at least `min_len` bytes, or as large as the
+    /// transport ever offers.
+    fn write_buf_until(
+        endpoint: &mut T::Endpoint,
+        min_len: usize,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<<T::Endpoint as Endpoint>::WriteBuf<'_>, IpcError<F, T>> {
+        Ok(match deadline {
+            Some((instant, timeout)) => endpoint
+                .write_at_least_timeout(min_len, instant.saturating_duration_since(Instant::now()))
+                .map_err(|e| IpcError::TransportError(e))?
+                .ok_or(IpcError::Timeout(timeout))?,
+            None => endpoint
+                .write_at_least(min_len)
+                .map_err(|e| IpcError::TransportError(e))?,
+        })
+    }
+
+    /// Receives the next responses, copied out of the transport buffer so that they
+    /// outlive later reads. A frame holds several responses if the peer batched them.
+    pub(super) fn receive_response_frames(
+        &mut self,
+    ) -> Result<Option<Vec<ResponseFrame>>, IpcError<F, T>> {
+        let deadline = Self::deadline(self.timeout);
+
+        self.handshake_until(deadline)?;
+        self.receive_until(deadline, ResponseFrame::split_from)
+    }
+
+    fn read_buf_until(
+        endpoint: &mut T::Endpoint,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<<T::Endpoint as Endpoint>::ReadBuf<'_>, IpcError<F, T>> {
+        Ok(match deadline {
+            Some((instant, timeout)) => endpoint
+                .read_timeout(instant.saturating_duration_since(Instant::now()))
+                .map_err(|e| IpcError::TransportError(e))?
+                .ok_or(IpcError::Timeout(timeout))?,
+            None => endpoint.read().map_err(|e| IpcError::TransportError(e))?,
+        })
+    }
+
+    fn receive_message_until<B: BytewiseReadOwned>(
+        &mut self,
+        deadline: Option<(Instant, Duration)>,
+    ) -> Result<Option<B>, IpcError<F, T>> {
+        self.receive_until(deadline, |payload| {
+            B::read_from_mut(&mut BytewiseBuffer::new(payload))
+        })
+    }
+
+    /// Receives the next frame and decodes its payload with `decode`.
+    ///
+    /// Fragments are collected until the last one, returning `Ok(None)` meanwhile,
+    /// then the reassembled message is decoded.
+    fn receive_until<R>(
+        &mut self,
+        deadline: Option<(Instant, Duration)>,
+        decode: impl FnOnce(&[u8]) -> Result<R, BytewiseError>,
+    ) -> Result<Option<R>, IpcError<F, T>> {
+        self.reassembly.release();
 
+        let read_buf = Self::read_buf_until(&mut self.endpoint, deadline)?;
         let frame = match self
             .framer
             .decode_frame(&read_buf)
@@ -71,31 +414,31 @@ impl<F: Framer, T: Transport> Peer<F, T> {
             Some(result) => result,
             None => return Ok(None),
         };
+        let frame_len = frame.frame_len();
 
-        let mut reader = BytewiseBuffer::new(frame.as_ref());
-        let message = B::read_from_mut(&mut reader)?;
+        let reassembled = match frame.fragment() {
+            None => {
+                let message = decode(frame.as_ref())?;
+                drop(frame);
 
-        let frame_len = frame.frame_len();
+                read_buf
+                    .consume(frame_len)
+                    .map_err(|e| IpcError::TransportError(e))?;
+
+                return Ok(Some(message));
+            }
+            Some(fragment) => self.reassembly.push(fragment, frame.as_ref()),
+        };
         drop(frame);
 
         read_buf
             .consume(frame_len)
             .map_err(|e| IpcError::TransportError(e))?;
 
-        Ok(Some(message))
-    }
-
-    pub fn invoke(&mut self, request: &Request) -> Result<Response<'_>, IpcError<F, T>> {
-        self.send_message(request)?;
-
-        let response = loop {
-            match self.receive_message()? {
-                Some(resp) => break resp,
-                None => continue,
-            }
-        };
-
-        Ok(response)
+        match reassembled? {
+            Some(message) => Ok(Some(decode(message)?)),
+            None => Ok(None),
+        }
     }
 }
 
@@ -110,7 +453,11 @@ impl<F: Framer, T: Transport> Server<F, T> {
             .create(addr)
             .map_err(|e| IpcError::TransportError(e))?;
 
-        Ok(Self(Peer::new(framer, endpoint)))
+        Ok(Self(Peer::with_handshake(
+            framer,
+            endpoint,
+            HandshakeFeatures::SUPPORTED,
+        )))
     }
 }
 
@@ -128,6 +475,54 @@ impl<F: Framer, T: Transport> DerefMut for Server<F, T> {
     }
 }
 
+/// Accepts any number of clients on one well-known address, each as its own `Server`.
+#[derive(Debug)]
+pub struct Listener<F: Framer, T: Transport> {
+    framer: F,
+    listener: T::Listener,
+}
+
+impl<F: Framer + Clone, T: Transport> Listener<F, T> {
+    #[inline]
+    pub fn bind(framer: F, transport: &T, addr: &T::Address) -> Result<Self, IpcError<F, T>> {
+        let listener = transport
+            .listen(addr)
+            .map_err(|e| IpcError::TransportError(e))?;
+
+        Ok(Self { framer, listener })
+    }
+
+    /// Accepts the next client. Blocks until a client connects.
+    pub fn accept(&self) -> Result<Server<F, T>, IpcError<F, T>> {
+        let endpoint =
+            transport::Listener::accept(&self.listener).map_err(|e| IpcError::TransportError(e))?;
+
+        Ok(self.server(endpoint))
+    }
+
+    /// Accepts the next client. Blocks until a client connects or `timeout` elapses.
+    ///
+    /// Returns `Ok(None)` if no client connected in time.
+    pub fn accept_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<Option<Server<F, T>>, IpcError<F, T>> {
+        let endpoint = transport::Listener::accept_timeout(&self.listener, timeout)
+            .map_err(|e| IpcError::TransportError(e))?;
+
+        Ok(endpoint.map(|endpoint| self.server(endpoint)))
+    }
+
+    #[inline]
+    fn server(&self, endpoint: T::Endpoint) -> Server<F, T> {
+        Server(Peer::with_handshake(
+            self.framer.clone(),
+            endpoint,
+            HandshakeFeatures::SUPPORTED,
+        ))
+    }
+}
+
 #[repr(transparent)]
 #[derive(Debug)]
 pub struct Client<F: Framer, T: Transport>(Peer<F, T>);
@@ -139,7 +534,11 @@ impl<F: Framer, T: Transport> Client<F, T> {
             .connect(addr)
             .map_err(|e| IpcError::TransportError(e))?;
 
-        Ok(Self(Peer::new(framer, endpoint)))
+        Ok(Self(Peer::with_handshake(
+            framer,
+            endpoint,
+            HandshakeFeatures::SUPPORTED,
+        )))
     }
 }
 
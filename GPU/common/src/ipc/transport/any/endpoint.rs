@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::ipc::transport::{
+    BulkRegion, Endpoint, ReadBuf, WriteBuf, loopback::LoopbackEndpoint, shmem::ShmemEndpoint,
+    tcp::TcpEndpoint, uds::UdsEndpoint,
+};
+
+use super::error::AnyTransportError;
+
+/// Dispatches an expression over every variant of an `Any*` enum.
+macro_rules! dispatch {
+    ($enum:ident, $value:expr, $inner:ident => $body:expr) => {
+        match $value {
+            $enum::Shmem($inner) => $body,
+            $enum::Uds($inner) => $body,
+            $enum::Tcp($inner) => $body,
+            $enum::Loopback($inner) => $body,
+        }
+    };
+}
+
+/// An endpoint of any of the supported transports.
+#[derive(Debug)]
+pub enum AnyEndpoint {
+    Shmem(ShmemEndpoint),
+    Uds(UdsEndpoint),
+    Tcp(TcpEndpoint),
+    Loopback(LoopbackEndpoint),
+}
+
+impl Endpoint for AnyEndpoint {
+    type Error = AnyTransportError;
+    type ReadBuf<'a> = AnyReadBuf<'a>;
+    type WriteBuf<'a> = AnyWriteBuf<'a>;
+
+    fn read(&mut self) -> Result<Self::ReadBuf<'_>, Self::Error> {
+        Ok(match self {
+            Self::Shmem(endpoint) => AnyReadBuf::Shmem(endpoint.read()?),
+            Self::Uds(endpoint) => AnyReadBuf::Uds(endpoint.read()?),
+            Self::Tcp(endpoint) => AnyReadBuf::Tcp(endpoint.read()?),
+            Self::Loopback(endpoint) => AnyReadBuf::Loopback(endpoint.read()?),
+        })
+    }
+
+    fn write(&mut self) -> Result<Self::WriteBuf<'_>, Self::Error> {
+        Ok(match self {
+            Self::Shmem(endpoint) => AnyWriteBuf::Shmem(endpoint.write()?),
+            Self::Uds(endpoint) => AnyWriteBuf::Uds(endpoint.write()?),
+            Self::Tcp(endpoint) => AnyWriteBuf::Tcp(endpoint.write()?),
+            Self::Loopback(endpoint) => AnyWriteBuf::Loopback(endpoint.write()?),
+        })
+    }
+
+    fn read_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::ReadBuf<'_>>, Self::Error> {
+        Ok(match self {
+            Self::Shmem(endpoint) => endpoint.read_timeout(timeout)?.map(AnyReadBuf::Shmem),
+            Self::Uds(endpoint) => endpoint.read_timeout(timeout)?.map(AnyReadBuf::Uds),
+            Self::Tcp(endpoint) => endpoint.read_timeout(timeout)?.map(AnyReadBuf::Tcp),
+            Self::Loopback(endpoint) => endpoint.read_timeout(timeout)?.map(AnyReadBuf::Loopback),
+        })
+    }
+
+    fn write_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        Ok(match self {
+            Self::Shmem(endpoint) => endpoint.write_timeout(timeout)?.map(AnyWriteBuf::Shmem),
+            Self::Uds(endpoint) => endpoint.write_timeout(timeout)?.map(AnyWriteBuf::Uds),
+            Self::Tcp(endpoint) => endpoint.write_timeout(timeout)?.map(AnyWriteBuf::Tcp),
+            Self::Loopback(endpoint) => endpoint.write_timeout(timeout)?.map(AnyWriteBuf::Loopback),
+        })
+    }
+
+    fn write_at_least(&mut self, len: usize) -> Result<Self::WriteBuf<'_>, Self::Error> {
+        Ok(match self {
+            Self::Shmem(endpoint) => AnyWriteBuf::Shmem(endpoint.write_at_least(len)?),
+            Self::Uds(endpoint) => AnyWriteBuf::Uds(endpoint.write_at_least(len)?),
+            Self::Tcp(endpoint) => AnyWriteBuf::Tcp(endpoint.write_at_least(len)?),
+            Self::Loopback(endpoint) => AnyWriteBuf::Loopback(endpoint.write_at_least(len)?),
+        })
+    }
+
+    fn write_at_least_timeout(
+        &mut self,
+        len: usize,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        Ok(match self {
+            Self::Shmem(endpoint) => endpoint
+                .write_at_least_timeout(len, timeout)?
+                .map(AnyWriteBuf::Shmem),
+            Self::Uds(endpoint) => endpoint
+                .write_at_least_timeout(len, timeout)?
+                .map(AnyWriteBuf::Uds),
+            Self::Tcp(endpoint) => endpoint
+                .write_at_least_timeout(len, timeout)?
+                .map(AnyWriteBuf::Tcp),
+            Self::Loopback(endpoint) => endpoint
+                .write_at_least_timeout(len, timeout)?
+                .map(AnyWriteBuf::Loopback),
+        })
+    }
+
+    #[inline]
+    fn arena(&self) -> Option<Arc<dyn BulkRegion>> {
+        dispatch!(Self, self, endpoint => endpoint.arena())
+    }
+}
+
+#[derive(Debug)]
+pub enum AnyReadBuf<'a> {
+    Shmem(<ShmemEndpoint as Endpoint>::ReadBuf<'a>),
+    Uds(<UdsEndpoint as Endpoint>::ReadBuf<'a>),
+    Tcp(<TcpEndpoint as Endpoint>::ReadBuf<'a>),
+    Loopback(<LoopbackEndpoint as Endpoint>::ReadBuf<'a>),
+}
+
+impl ReadBuf for AnyReadBuf<'_> {
+    type Error = AnyTransportError;
+
+    fn consume(self, bytes: usize) -> Result<(), Self::Error> {
+        dispatch!(Self, self, buf => Ok(buf.consume(bytes)?))
+    }
+}
+
+impl Deref for AnyReadBuf<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        dispatch!(Self, self, buf => buf)
+    }
+}
+
+impl DerefMut for AnyReadBuf<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        dispatch!(Self, self, buf => buf)
+    }
+}
+
+#[derive(Debug)]
+pub enum AnyWriteBuf<'a> {
+    Shmem(<ShmemEndpoint as Endpoint>::WriteBuf<'a>),
+    Uds(<UdsEndpoint as Endpoint>::WriteBuf<'a>),
+    Tcp(<TcpEndpoint as Endpoint>::WriteBuf<'a>),
+    Loopback(<LoopbackEndpoint as Endpoint>::WriteBuf<'a>),
+}
+
+impl WriteBuf for AnyWriteBuf<'_> {
+    type Error = AnyTransportError;
+
+    fn submit(self, bytes: usize) -> Result<(), Self::Error> {
+        dispatch!(Self, self, buf => Ok(buf.submit(bytes)?))
+    }
+}
+
+impl Deref for AnyWriteBuf<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        dispatch!(Self, self, buf => buf)
+    }
+}
+
+impl DerefMut for AnyWriteBuf<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        dispatch!(Self, self, buf => buf)
+    }
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use thiserror::Error;
+
+use crate::ipc::transport::{
+    loopback::LoopbackTransportError, shmem::ShmemTransportError, tcp::TcpTransportError,
+    uds::UdsTransportError,
+};
+
+/// Transport URI parsing error types.
+#[derive(Error, Debug)]
+pub enum TransportUriError {
+    #[error("Missing scheme in transport uri '{uri}'")]
+    MissingScheme { uri: String },
+
+    #[error("Unsupported transport scheme '{scheme}'")]
+    UnsupportedScheme { scheme: String },
+
+    #[error("Missing address in transport uri '{uri}'")]
+    MissingAddress { uri: String },
+
+    #[error("Unknown option '{key}' for scheme '{scheme}'")]
+    UnknownOption { scheme: String, key: String },
+
+    #[error("Invalid value '{value}' for option '{key}'")]
+    InvalidOption { key: String, value: String },
+}
+
+/// Dynamically selected transport error types.
+#[derive(Error, Debug)]
+pub enum AnyTransportError {
+    #[error(transparent)]
+    Shmem(#[from] ShmemTransportError),
+
+    #[error(transparent)]
+    Uds(#[from] UdsTransportError),
+
+    #[error(transparent)]
+    Tcp(#[from] TcpTransportError),
+
+    #[error(transparent)]
+    Loopback(#[from] LoopbackTransportError),
+}
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+use std::time::Duration;
+
+use crate::ipc::transport::{
+    Listener, loopback::LoopbackListener, shmem::ShmemListener, tcp::TcpListener, uds::UdsListener,
+};
+
+use super::{endpoint::AnyEndpoint, error::AnyTransportError};
+
+/// A listener of any of the supported transports.
+#[derive(Debug)]
+pub enum AnyListener {
+    Shmem(ShmemListener),
+    Uds(UdsListener),
+    Tcp(TcpListener),
+    Loopback(LoopbackListener),
+}
+
+impl Listener for AnyListener {
+    type Error = AnyTransportError;
+    type Endpoint = AnyEndpoint;
+
+    fn accept(&self) -> Result<Self::Endpoint, Self::Error> {
+        Ok(match self {
+            Self::Shmem(listener) => AnyEndpoint::Shmem(listener.accept()?),
+            Self::Uds(listener) => AnyEndpoint::Uds(listener.accept()?),
+            Self::Tcp(listener) => AnyEndpoint::Tcp(listener.accept()?),
+            Self::Loopback(listener) => AnyEndpoint::Loopback(listener.accept()?),
+        })
+    }
+
+    fn accept_timeout(&self, timeout: Duration) -> Result<Option<Self::Endpoint>, Self::Error> {
+        Ok(match self {
+            Self::Shmem(listener) => listener.accept_timeout(timeout)?.map(AnyEndpoint::Shmem),
+            Self::Uds(listener) => listener.accept_timeout(timeout)?.map(AnyEndpoint::Uds),
+            Self::Tcp(listener) => listener.accept_timeout(timeout)?.map(AnyEndpoint::Tcp),
+            Self::Loopback(listener) => {
+                listener.accept_timeout(timeout)?.map(AnyEndpoint::Loopback)
+            }
+        })
+    }
+}
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+//! A dynamically selected transport.
+//!
+//! This module lets applications pick a transport at runtime from a single
+//! address syntax, e.g. read from an environment variable or a command line
+//! flag:
+//!
+//! - `shm:///name?buf=4M` selects the shared memory transport
+//! - `unix:///run/xgpu.sock` selects the unix domain socket transport
+//! - `tcp://host:port?keepalive=10s` selects the tcp transport
+//! - `loopback://name` selects the in-process loopback transport
+//!
+//! Options shared by all schemes are `buf` (buffer size, `K`/`M`/`G` suffix)
+//! and `timeout` (connect timeout, `ms`/`s` suffix). The shm scheme also
+//! accepts `reclaim` (remove segments left behind by a dead creator) and `arena`
+//! (size of the bulk data arena of each connection), and the tcp scheme accepts
+//! `nodelay` and `keepalive` (`off` disables keepalive).
+
+mod error;
+pub use error::*;
+
+mod uri;
+pub use uri::*;
+
+mod endpoint;
+pub use endpoint::*;
+
+mod listener;
+pub use listener::*;
+
+mod transport;
+pub use transport::*;
+
+/// Environment variable carrying the transport URI for proxy and server.
+pub const TRANSPORT_URI_ENV: &str = "XGPU_TRANSPORT";
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use crate::ipc::transport::{
+    Transport, loopback::LoopbackTransport, shmem::ShmemTransport, tcp::TcpTransport,
+    uds::UdsTransport,
+};
+
+use super::{endpoint::AnyEndpoint, error::AnyTransportError, listener::AnyListener};
+
+/// A transport selected at runtime, usually built from a `TransportUri`.
+#[derive(Debug, Clone, Copy)]
+pub enum AnyTransport {
+    Shmem(ShmemTransport),
+    Uds(UdsTransport),
+    Tcp(TcpTransport),
+    Loopback(LoopbackTransport),
+}
+
+impl Transport for AnyTransport {
+    type Error = AnyTransportError;
+    type Endpoint = AnyEndpoint;
+    type Listener = AnyListener;
+    type Address = str;
+
+    fn create(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
+        Ok(match self {
+            Self::Shmem(transport) => AnyEndpoint::Shmem(transport.create(addr)?),
+            Self::Uds(transport) => AnyEndpoint::Uds(transport.create(addr)?),
+            Self::Tcp(transport) => AnyEndpoint::Tcp(transport.create(addr)?),
+            Self::Loopback(transport) => AnyEndpoint::Loopback(transport.create(addr)?),
+        })
+    }
+
+    fn listen(&self, addr: &Self::Address) -> Result<Self::Listener, Self::Error> {
+        Ok(match self {
+            Self::Shmem(transport) => AnyListener::Shmem(transport.listen(addr)?),
+            Self::Uds(transport) => AnyListener::Uds(transport.listen(addr)?),
+            Self::Tcp(transport) => AnyListener::Tcp(transport.listen(addr)?),
+            Self::Loopback(transport) => AnyListener::Loopback(transport.listen(addr)?),
+        })
+    }
+
+    fn connect(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
+        Ok(match self {
+            Self::Shmem(transport) => AnyEndpoint::Shmem(transport.connect(addr)?),
+            Self::Uds(transport) => AnyEndpoint::Uds(transport.connect(addr)?),
+            Self::Tcp(transport) => AnyEndpoint::Tcp(transport.connect(addr)?),
+            Self::Loopback(transport) => AnyEndpoint::Loopback(transport.connect(addr)?),
+        })
+    }
+}
+
+impl From<ShmemTransport> for AnyTransport {
+    fn from(value: ShmemTransport) -> Self {
+        Self::Shmem(value)
+    }
+}
+
+impl From<UdsTransport> for AnyTransport {
+    fn from(value: UdsTransport) -> Self {
+        Self::Uds(value)
+    }
+}
+
+impl From<TcpTransport> for AnyTransport {
+    fn from(value: TcpTransport) -> Self {
+        Self::Tcp(value)
+    }
+}
+
+impl From<LoopbackTransport> for AnyTransport {
+    fn from(value: LoopbackTransport) -> Self {
+        Self::Loopback(value)
+    }
+}
@@ -0,0 +1,435 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::ipc::transport::{
+    loopback::LoopbackTransportBuilder, shmem::ShmemTransportBuilder, tcp::TcpTransportBuilder,
+    uds::UdsTransportBuilder,
+};
+
+use super::{error::TransportUriError, transport::AnyTransport};
+
+/// Transport kind selected by a URI scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportScheme {
+    Shmem,
+    Uds,
+    Tcp,
+    Loopback,
+}
+
+impl TransportScheme {
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Shmem => "shm",
+            Self::Uds => "unix",
+            Self::Tcp => "tcp",
+            Self::Loopback => "loopback",
+        }
+    }
+}
+
+impl FromStr for TransportScheme {
+    type Err = TransportUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "shm" | "shmem" => Ok(Self::Shmem),
+            "unix" | "uds" => Ok(Self::Uds),
+            "tcp" => Ok(Self::Tcp),
+            "loopback" => Ok(Self::Loopback),
+            _ => Err(TransportUriError::UnsupportedScheme {
+                scheme: s.to_owned(),
+            }),
+        }
+    }
+}
+
+impl Display for TransportScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A parsed transport address, e.g. `shm:///name?buf=4M` or `tcp://host:port`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportUri {
+    scheme: TransportScheme,
+    address: String,
+    buffer_size: Option<usize>,
+    conn_timeout: Option<Duration>,
+    nodelay: Option<bool>,
+    keepalive: Option<Option<Duration>>,
+    reclaim: Option<bool>,
+    arena_size: Option<usize>,
+}
+
+impl TransportUri {
+    #[inline]
+    pub fn scheme(&self) -> TransportScheme {
+        self.scheme
+    }
+
+    /// Returns the transport specific address, i.e. shmem name, socket path or `host:port`.
+    #[inline]
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    #[inline]
+    pub fn buffer_size(&self) -> Option<usize> {
+        self.buffer_size
+    }
+
+    #[inline]
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.conn_timeout
+    }
+
+    /// Returns the size of the shared memory data arena created for each connection.
+    #[inline]
+    pub fn arena_size(&self) -> Option<usize> {
+        self.arena_size
+    }
+
+    /// Sets the buffer size used when the URI does not specify one.
+    #[inline]
+    pub fn default_buffer_size(mut self, value: usize) -> Self {
+        self.buffer_size.get_or_insert(value);
+        self
+    }
+
+    /// Sets the arena size used when a shm URI does not specify one, other schemes
+    /// have no arena.
+    #[inline]
+    pub fn default_arena_size(mut self, value: usize) -> Self {
+        if self.scheme == TransportScheme::Shmem {
+            self.arena_size.get_or_insert(value);
+        }
+        self
+    }
+
+    /// Builds the transport selected by this URI.
+    pub fn transport(&self) -> AnyTransport {
+        macro_rules! configure {
+            ($builder:expr) => {{
+                let mut builder = $builder;
+                if let Some(value) = self.buffer_size {
+                    builder = builder.buffer_size(value);
+                }
+                if let Some(value) = self.conn_timeout {
+                    builder = builder.connect_timeout(value);
+                }
+                builder
+            }};
+        }
+
+        match self.scheme {
+            TransportScheme::Shmem => {
+                let mut builder = configure!(ShmemTransportBuilder::new());
+                if let Some(value) = self.reclaim {
+                    builder = builder.reclaim_stale(value);
+                }
+                if let Some(value) = self.arena_size {
+                    builder = builder.arena_size(value);
+                }
+                builder.build().into()
+            }
+            TransportScheme::Uds => configure!(UdsTransportBuilder::new()).build().into(),
+            TransportScheme::Loopback => configure!(LoopbackTransportBuilder::new()).build().into(),
+            TransportScheme::Tcp => {
+                let mut builder = configure!(TcpTransportBuilder::new());
+                if let Some(value) = self.nodelay {
+                    builder = builder.nodelay(value);
+                }
+                if let Some(value) = self.keepalive {
+                    builder = builder.keepalive(value);
+                }
+                builder.build().into()
+            }
+        }
+    }
+
+    fn parse_option(&mut self, key: &str, value: &str) -> Result<(), TransportUriError> {
+        let invalid = || TransportUriError::InvalidOption {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        };
+
+        match (self.scheme, key) {
+            (_, "buf") => self.buffer_size = Some(parse_size(value).ok_or_else(invalid)?),
+            (_, "timeout") => self.conn_timeout = Some(parse_duration(value).ok_or_else(invalid)?),
+            (TransportScheme::Shmem, "reclaim") => {
+                self.reclaim = Some(parse_bool(value).ok_or_else(invalid)?)
+            }
+            (TransportScheme::Shmem, "arena") => {
+                self.arena_size = Some(parse_size(value).ok_or_else(invalid)?)
+            }
+            (TransportScheme::Tcp, "nodelay") => {
+                self.nodelay = Some(parse_bool(value).ok_or_else(invalid)?)
+            }
+            (TransportScheme::Tcp, "keepalive") => {
+                self.keepalive = Some(match value {
+                    "off" | "0" => None,
+                    _ => Some(parse_duration(value).ok_or_else(invalid)?),
+                })
+            }
+            _ => {
+                return Err(TransportUriError::UnknownOption {
+                    scheme: self.scheme.to_string(),
+                    key: key.to_owned(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for TransportUri {
+    type Err = TransportUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let uri = s.trim();
+        let (scheme, rest) =
+            uri.split_once("://")
+                .ok_or_else(|| TransportUriError::MissingScheme {
+                    uri: uri.to_owned(),
+                })?;
+        let scheme = scheme.parse::<TransportScheme>()?;
+
+        let (target, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let address = match scheme {
+            // `shm:///name` and `loopback:///name` carry a plain name
+            TransportScheme::Shmem | TransportScheme::Loopback => target.trim_start_matches('/'),
+            // `unix:///run/xgpu.sock` carries an absolute path
+            TransportScheme::Uds | TransportScheme::Tcp => target,
+        };
+        if address.is_empty() {
+            return Err(TransportUriError::MissingAddress {
+                uri: uri.to_owned(),
+            });
+        }
+
+        let mut result = Self {
+            scheme,
+            address: address.to_owned(),
+            buffer_size: None,
+            conn_timeout: None,
+            nodelay: None,
+            keepalive: None,
+            reclaim: None,
+            arena_size: None,
+        };
+        for option in query.split('&').filter(|s| !s.is_empty()) {
+            let (key, value) = option.split_once('=').unwrap_or((option, ""));
+            result.parse_option(key, value)?;
+        }
+
+        Ok(result)
+    }
+}
+
+impl Display for TransportUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.scheme {
+            TransportScheme::Shmem | TransportScheme::Loopback => {
+                write!(f, "{}:///{}", self.scheme, self.address)?
+            }
+            TransportScheme::Uds | TransportScheme::Tcp => {
+                write!(f, "{}://{}", self.scheme, self.address)?
+            }
+        }
+
+        let mut options = Vec::new();
+        if let Some(value) = self.buffer_size {
+            options.push(format!("buf={}", value));
+        }
+        if let Some(value) = self.conn_timeout {
+            options.push(format!("timeout={}ms", value.as_millis()));
+        }
+        if let Some(value) = self.reclaim {
+            options.push(format!("reclaim={}", value));
+        }
+        if let Some(value) = self.arena_size {
+            options.push(format!("arena={}", value));
+        }
+        if let Some(value) = self.nodelay {
+            options.push(format!("nodelay={}", value));
+        }
+        match self.keepalive {
+            Some(Some(value)) => options.push(format!("keepalive={}s", value.as_secs())),
+            Some(None) => options.push("keepalive=off".to_owned()),
+            None => {}
+        }
+
+        if !options.is_empty() {
+            write!(f, "?{}", options.join("&"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a byte size with an optional binary suffix, e.g. `4096`, `64K`, `4M`, `1GiB`.
+fn parse_size(value: &str) -> Option<usize> {
+    let value = value.trim();
+    let digits = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(digits);
+
+    let shift = match suffix.to_ascii_uppercase().as_str() {
+        "" | "B" => 0,
+        "K" | "KB" | "KIB" => 10,
+        "M" | "MB" | "MIB" => 20,
+        "G" | "GB" | "GIB" => 30,
+        _ => return None,
+    };
+
+    number.parse::<usize>().ok()?.checked_mul(1 << shift)
+}
+
+/// Parses a duration with an optional unit suffix, e.g. `100ms`, `3s`, plain numbers are milliseconds.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let digits = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(digits);
+    let number = number.parse::<u64>().ok()?;
+
+    match suffix {
+        "us" => Some(Duration::from_micros(number)),
+        "" | "ms" => Some(Duration::from_millis(number)),
+        "s" => Some(Duration::from_secs(number)),
+        "m" => Some(Duration::from_secs(number.checked_mul(60)?)),
+        _ => None,
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "" | "1" | "true" | "on" => Some(true),
+        "0" | "false" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shmem_uri() {
+        let uri = "shm:///1234?buf=4M&timeout=2s"
+            .parse::<TransportUri>()
+            .unwrap();
+        assert_eq!(uri.scheme(), TransportScheme::Shmem);
+        assert_eq!(uri.address(), "1234");
+        assert_eq!(uri.buffer_size(), Some(4 * 1024 * 1024));
+        assert_eq!(uri.connect_timeout(), Some(Duration::from_secs(2)));
+        assert_eq!(uri.to_string(), "shm:///1234?buf=4194304&timeout=2000ms");
+    }
+
+    #[test]
+    fn test_parse_shmem_reclaim() {
+        let uri = "shm:///1234?reclaim=1".parse::<TransportUri>().unwrap();
+        assert_eq!(uri.reclaim, Some(true));
+        assert_eq!(uri.to_string(), "shm:///1234?reclaim=true");
+
+        assert!(matches!(
+            "tcp://host:1?reclaim=1".parse::<TransportUri>(),
+            Err(TransportUriError::UnknownOption { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_shmem_arena() {
+        let uri = "shm:///1234?arena=64M".parse::<TransportUri>().unwrap();
+        assert_eq!(uri.arena_size(), Some(64 * 1024 * 1024));
+        assert_eq!(uri.to_string(), "shm:///1234?arena=67108864");
+
+        let uri = "shm:///1234".parse::<TransportUri>().unwrap();
+        assert_eq!(uri.default_arena_size(4096).arena_size(), Some(4096));
+
+        let uri = "unix:///run/xgpu.sock".parse::<TransportUri>().unwrap();
+        assert_eq!(uri.default_arena_size(4096).arena_size(), None);
+        assert!(matches!(
+            "unix:///run/xgpu.sock?arena=4K".parse::<TransportUri>(),
+            Err(TransportUriError::UnknownOption { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_uds_uri() {
+        let uri = "unix:///run/xgpu.sock".parse::<TransportUri>().unwrap();
+        assert_eq!(uri.scheme(), TransportScheme::Uds);
+        assert_eq!(uri.address(), "/run/xgpu.sock");
+        assert_eq!(uri.buffer_size(), None);
+        assert_eq!(uri.to_string(), "unix:///run/xgpu.sock");
+    }
+
+    #[test]
+    fn test_parse_tcp_uri() {
+        let uri = "tcp://gpu-node:7000?nodelay=0&keepalive=off"
+            .parse::<TransportUri>()
+            .unwrap();
+        assert_eq!(uri.scheme(), TransportScheme::Tcp);
+        assert_eq!(uri.address(), "gpu-node:7000");
+        assert_eq!(uri.nodelay, Some(false));
+        assert_eq!(uri.keepalive, Some(None));
+
+        let reparsed = uri.to_string().parse::<TransportUri>().unwrap();
+        assert_eq!(reparsed, uri);
+    }
+
+    #[test]
+    fn test_default_buffer_size() {
+        let uri = "loopback://test".parse::<TransportUri>().unwrap();
+        assert_eq!(uri.default_buffer_size(64).buffer_size(), Some(64));
+
+        let uri = "loopback://test?buf=1K".parse::<TransportUri>().unwrap();
+        assert_eq!(uri.default_buffer_size(64).buffer_size(), Some(1024));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            "1234".parse::<TransportUri>(),
+            Err(TransportUriError::MissingScheme { .. })
+        ));
+        assert!(matches!(
+            "udp://host:1".parse::<TransportUri>(),
+            Err(TransportUriError::UnsupportedScheme { .. })
+        ));
+        assert!(matches!(
+            "shm:///".parse::<TransportUri>(),
+            Err(TransportUriError::MissingAddress { .. })
+        ));
+        assert!(matches!(
+            "shm:///1234?buf=4X".parse::<TransportUri>(),
+            Err(TransportUriError::InvalidOption { .. })
+        ));
+        assert!(matches!(
+            "unix:///tmp/a.sock?nodelay=1".parse::<TransportUri>(),
+            Err(TransportUriError::UnknownOption { .. })
+        ));
+    }
+}
@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+    sync::MutexGuard,
+};
+
+use tracing::debug;
+
+use crate::ipc::transport::{ReadBuf, WriteBuf};
+
+use super::{
+    channel::{LoopbackChannel, LoopbackChannelState},
+    error::LoopbackTransportError,
+};
+
+pub struct LoopbackReadBuffer<'a> {
+    guard: MutexGuard<'a, LoopbackChannelState>,
+    channel: &'a LoopbackChannel,
+    consumed: usize,
+}
+
+impl<'a> LoopbackReadBuffer<'a> {
+    /// Creates a new read buffer.
+    #[inline]
+    pub fn new(guard: MutexGuard<'a, LoopbackChannelState>, channel: &'a LoopbackChannel) -> Self {
+        Self {
+            guard,
+            channel,
+            consumed: 0,
+        }
+    }
+}
+
+impl ReadBuf for LoopbackReadBuffer<'_> {
+    type Error = LoopbackTransportError;
+
+    fn consume(mut self, bytes: usize) -> Result<(), Self::Error> {
+        if bytes > self.len() {
+            return Err(LoopbackTransportError::ReadOverflow {
+                attempted: bytes,
+                capacity: self.len(),
+            });
+        }
+
+        self.consumed = bytes;
+        Ok(())
+    }
+}
+
+impl Deref for LoopbackReadBuffer<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        let state = &*self.guard;
+        &state.buf[state.head..state.tail]
+    }
+}
+
+impl DerefMut for LoopbackReadBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let state = &mut *self.guard;
+        &mut state.buf[state.head..state.tail]
+    }
+}
+
+impl Debug for LoopbackReadBuffer<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoopbackReadBuffer")
+            .field("channel", &self.channel.to_string())
+            .field("len", &self.len())
+            .field("consumed", &self.consumed)
+            .finish()
+    }
+}
+
+impl Drop for LoopbackReadBuffer<'_> {
+    fn drop(&mut self) {
+        debug!(
+            "[Loopback] '{}': Read {} bytes",
+            self.channel, self.consumed
+        );
+
+        if self.consumed > 0 {
+            self.guard.head += self.consumed;
+            self.guard.stalled_at = None;
+
+            debug!("[Loopback] '{}': Notify writable", self.channel);
+            self.channel.notify_writable();
+        } else {
+            // Nothing could be decoded, wait for more data on next read.
+            self.guard.stalled_at = Some(self.guard.tail);
+        }
+    }
+}
+
+pub struct LoopbackWriteBuffer<'a> {
+    guard: MutexGuard<'a, LoopbackChannelState>,
+    channel: &'a LoopbackChannel,
+    submitted: usize,
+}
+
+impl<'a> LoopbackWriteBuffer<'a> {
+    /// Creates a new write buffer.
+    #[inline]
+    pub fn new(guard: MutexGuard<'a, LoopbackChannelState>, channel: &'a LoopbackChannel) -> Self {
+        Self {
+            guard,
+            channel,
+            submitted: 0,
+        }
+    }
+}
+
+impl WriteBuf for LoopbackWriteBuffer<'_> {
+    type Error = LoopbackTransportError;
+
+    fn submit(mut self, bytes: usize) -> Result<(), Self::Error> {
+        if bytes > self.len() {
+            return Err(LoopbackTransportError::WriteOverflow {
+                attempted: bytes,
+                capacity: self.len(),
+            });
+        }
+
+        self.submitted = bytes;
+        Ok(())
+    }
+}
+
+impl Deref for LoopbackWriteBuffer<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        let state = &*self.guard;
+        &state.buf[state.tail..]
+    }
+}
+
+impl DerefMut for LoopbackWriteBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let state = &mut *self.guard;
+        &mut state.buf[state.tail..]
+    }
+}
+
+impl Debug for LoopbackWriteBuffer<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoopbackWriteBuffer")
+            .field("channel", &self.channel.to_string())
+            .field("len", &self.len())
+            .field("submitted", &self.submitted)
+            .finish()
+    }
+}
+
+impl Drop for LoopbackWriteBuffer<'_> {
+    fn drop(&mut self) {
+        debug!(
+            "[Loopback] '{}': Wrote {} bytes",
+            self.channel, self.submitted
+        );
+
+        if self.submitted > 0 {
+            self.guard.tail += self.submitted;
+
+            debug!("[Loopback] '{}': Notify readable", self.channel);
+            self.channel.notify_readable();
+        }
+    }
+}
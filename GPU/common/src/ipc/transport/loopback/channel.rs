@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    fmt::Display,
+    sync::{Condvar, Mutex, MutexGuard},
+    time::Instant,
+};
+
+use tracing::debug;
+
+use crate::{ipc::bytewise::AlignedBuffer, sys::page};
+
+use super::{
+    buffer::{LoopbackReadBuffer, LoopbackWriteBuffer},
+    error::LoopbackTransportError,
+};
+
+/// Buffer state of a loopback channel.
+///
+/// Unread data is only ever moved by whole pages, so every frame is read at the
+/// same address alignment it was encoded at. Consumed bytes are reclaimed on the
+/// next read, because decoded messages may still borrow from them.
+#[derive(Debug)]
+pub struct LoopbackChannelState {
+    pub buf: AlignedBuffer,
+    /// Read cursor. Advanced by the reader.
+    pub head: usize,
+    /// Write cursor. Advanced by the writer.
+    pub tail: usize,
+    /// Write cursor observed by a read that consumed nothing.
+    pub stalled_at: Option<usize>,
+    /// Whether either endpoint has been dropped.
+    pub closed: bool,
+}
+
+impl LoopbackChannelState {
+    /// Moves unread data towards the buffer start by whole pages, rewinding both
+    /// cursors once all of it has been consumed.
+    ///
+    /// Returns `true` if any space was reclaimed.
+    fn compact(&mut self) -> bool {
+        let shift = if self.head == self.tail {
+            self.head
+        } else {
+            self.head - self.head % page::page_size()
+        };
+        if shift == 0 {
+            return false;
+        }
+
+        self.buf
+            .copy_within(self.head..self.tail, self.head - shift);
+        self.head -= shift;
+        self.tail -= shift;
+        self.stalled_at = self.stalled_at.map(|stalled_at| stalled_at - shift);
+        true
+    }
+}
+
+/// Represents a single direction of communication within the process.
+#[derive(Debug)]
+pub struct LoopbackChannel {
+    name: String,
+    state: Mutex<LoopbackChannelState>,
+    readable: Condvar,
+    writable: Condvar,
+}
+
+impl LoopbackChannel {
+    pub fn new(name: String, buffer_size: usize) -> Self {
+        Self {
+            name,
+            state: Mutex::new(LoopbackChannelState {
+                buf: AlignedBuffer::new(buffer_size, page::page_size()),
+                head: 0,
+                tail: 0,
+                stalled_at: None,
+                closed: false,
+            }),
+            readable: Condvar::new(),
+            writable: Condvar::new(),
+        }
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, LoopbackChannelState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn read_buf(&self) -> Result<LoopbackReadBuffer<'_>, LoopbackTransportError> {
+        self.read_buf_until(None)
+            .map(|buf| buf.expect("Untimed read never times out"))
+    }
+
+    /// Acquires a read buffer, giving up with `Ok(None)` once `deadline` has passed.
+    pub fn read_buf_until(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<LoopbackReadBuffer<'_>>, LoopbackTransportError> {
+        let mut guard = self.lock();
+        if guard.compact() {
+            self.notify_writable();
+        }
+
+        loop {
+            let has_data = guard.head < guard.tail && guard.stalled_at != Some(guard.tail);
+            if has_data {
+                debug!("[Loopback] '{}': Reading...", self);
+                return Ok(Some(LoopbackReadBuffer::new(guard, self)));
+            }
+            if guard.closed {
+                return Err(LoopbackTransportError::ConnectionClosed);
+            }
+
+            debug!("[Loopback] '{}': Waiting readable...", self);
+            guard = match Self::wait(&self.readable, guard, deadline) {
+                Some(guard) => guard,
+                None => return Ok(None),
+            };
+        }
+    }
+
+    pub fn write_buf(&self) -> Result<LoopbackWriteBuffer<'_>, LoopbackTransportError> {
+        self.write_buf_until(1, None)
+            .map(|buf| buf.expect("Untimed write never times out"))
+    }
+
+    /// Acquires a write buffer of at least `min_len` bytes, or of the whole buffer if
+    /// smaller, giving up with `Ok(None)` once `deadline` has passed.
+    pub fn write_buf_until(
+        &self,
+        min_len: usize,
+        deadline: Option<Instant>,
+    ) -> Result<Option<LoopbackWriteBuffer<'_>>, LoopbackTransportError> {
+        let mut guard = self.lock();
+        let min_len = min_len.clamp(1, guard.buf.len());
+
+        loop {
+            if guard.closed {
+                return Err(LoopbackTransportError::ConnectionClosed);
+            }
+            if guard.buf.len() - guard.tail >= min_len {
+                debug!("[Loopback] '{}': Writting...", self);
+                return Ok(Some(LoopbackWriteBuffer::new(guard, self)));
+            }
+
+            debug!("[Loopback] '{}': Waiting writable...", self);
+            guard = match Self::wait(&self.writable, guard, deadline) {
+                Some(guard) => guard,
+                None => return Ok(None),
+            };
+        }
+    }
+
+    /// Waits on `cond`, returns `None` if `deadline` has already passed.
+    fn wait<'a>(
+        cond: &Condvar,
+        guard: MutexGuard<'a, LoopbackChannelState>,
+        deadline: Option<Instant>,
+    ) -> Option<MutexGuard<'a, LoopbackChannelState>> {
+        let guard = match deadline {
+            Some(deadline) => {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+                if timeout.is_zero() {
+                    return None;
+                }
+                cond.wait_timeout(guard, timeout)
+                    .map(|(guard, _)| guard)
+                    .unwrap_or_else(|e| e.into_inner().0)
+            }
+            None => cond.wait(guard).unwrap_or_else(|e| e.into_inner()),
+        };
+
+        Some(guard)
+    }
+
+    #[inline]
+    pub fn notify_readable(&self) {
+        self.readable.notify_all();
+    }
+
+    #[inline]
+    pub fn notify_writable(&self) {
+        self.writable.notify_all();
+    }
+
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.notify_readable();
+        self.notify_writable();
+    }
+}
+
+impl Display for LoopbackChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.name, f)
+    }
+}
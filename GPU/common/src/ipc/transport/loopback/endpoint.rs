@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::ipc::transport::Endpoint;
+
+use super::{
+    buffer::{LoopbackReadBuffer, LoopbackWriteBuffer},
+    channel::LoopbackChannel,
+    error::LoopbackTransportError,
+    transport,
+};
+
+/// A `Transport` implementation that uses two in-process channels for bidirectional communication.
+pub struct LoopbackEndpoint {
+    tx: Arc<LoopbackChannel>,
+    rx: Arc<LoopbackChannel>,
+    is_owner: bool,
+}
+
+impl LoopbackEndpoint {
+    #[inline]
+    pub(crate) fn new(tx: Arc<LoopbackChannel>, rx: Arc<LoopbackChannel>, is_owner: bool) -> Self {
+        Self { tx, rx, is_owner }
+    }
+}
+
+impl Endpoint for LoopbackEndpoint {
+    type Error = LoopbackTransportError;
+    type ReadBuf<'a> = LoopbackReadBuffer<'a>;
+    type WriteBuf<'a> = LoopbackWriteBuffer<'a>;
+
+    fn read(&mut self) -> Result<Self::ReadBuf<'_>, Self::Error> {
+        self.rx.read_buf()
+    }
+
+    fn write(&mut self) -> Result<Self::WriteBuf<'_>, Self::Error> {
+        self.tx.write_buf()
+    }
+
+    fn read_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::ReadBuf<'_>>, Self::Error> {
+        self.rx.read_buf_until(Some(Instant::now() + timeout))
+    }
+
+    fn write_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        self.tx.write_buf_until(1, Some(Instant::now() + timeout))
+    }
+
+    fn write_at_least(&mut self, len: usize) -> Result<Self::WriteBuf<'_>, Self::Error> {
+        self.tx
+            .write_buf_until(len, None)
+            .map(|buf| buf.expect("Untimed write never times out"))
+    }
+
+    fn write_at_least_timeout(
+        &mut self,
+        len: usize,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        self.tx.write_buf_until(len, Some(Instant::now() + timeout))
+    }
+}
+
+impl Debug for LoopbackEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoopbackEndpoint")
+            .field("tx", &self.tx)
+            .field("rx", &self.rx)
+            .field("is_owner", &self.is_owner)
+            .finish()
+    }
+}
+
+impl Drop for LoopbackEndpoint {
+    fn drop(&mut self) {
+        if self.is_owner {
+            transport::unregister(&self.tx);
+        }
+        self.tx.close();
+        self.rx.close();
+    }
+}
@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use tracing::debug;
+
+use crate::ipc::transport::Listener;
+
+use super::{
+    channel::LoopbackChannel,
+    endpoint::LoopbackEndpoint,
+    error::LoopbackTransportError,
+    transport::{self, C2S_SUFFIX, S2C_SUFFIX},
+};
+
+#[derive(Debug, Default)]
+struct LoopbackBacklogState {
+    /// Connected `(s2c, c2s)` channel pairs waiting to be accepted.
+    pending: VecDeque<(Arc<LoopbackChannel>, Arc<LoopbackChannel>)>,
+    /// Sequence number of the next connection, used to name its channels.
+    next_id: u64,
+    /// Whether the listener has been dropped.
+    closed: bool,
+}
+
+/// Connections made to a listener which have not been accepted yet.
+#[derive(Debug)]
+pub(super) struct LoopbackBacklog {
+    addr: String,
+    buffer_size: usize,
+    state: Mutex<LoopbackBacklogState>,
+    ready: Condvar,
+}
+
+impl LoopbackBacklog {
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, LoopbackBacklogState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queues a new connection and returns its client side endpoint.
+    pub fn connect(&self) -> Result<LoopbackEndpoint, LoopbackTransportError> {
+        let mut state = self.lock();
+        if state.closed {
+            return Err(LoopbackTransportError::ConnectionClosed);
+        }
+
+        let id = state.next_id;
+        state.next_id += 1;
+
+        let s2c = Arc::new(LoopbackChannel::new(
+            format!("{}.{}{}", self.addr, id, S2C_SUFFIX),
+            self.buffer_size,
+        ));
+        let c2s = Arc::new(LoopbackChannel::new(
+            format!("{}.{}{}", self.addr, id, C2S_SUFFIX),
+            self.buffer_size,
+        ));
+        state.pending.push_back((s2c.clone(), c2s.clone()));
+        self.ready.notify_one();
+
+        Ok(LoopbackEndpoint::new(c2s, s2c, false))
+    }
+
+    fn accept_until(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<LoopbackEndpoint>, LoopbackTransportError> {
+        let mut state = self.lock();
+
+        loop {
+            if let Some((tx, rx)) = state.pending.pop_front() {
+                debug!("[Loopback] '{}': Accepted", self.addr);
+                return Ok(Some(LoopbackEndpoint::new(tx, rx, false)));
+            }
+            if state.closed {
+                return Err(LoopbackTransportError::ConnectionClosed);
+            }
+
+            state = match deadline {
+                Some(deadline) => {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    if timeout.is_zero() {
+                        return Ok(None);
+                    }
+                    self.ready
+                        .wait_timeout(state, timeout)
+                        .map(|(state, _)| state)
+                        .unwrap_or_else(|e| e.into_inner().0)
+                }
+                None => self.ready.wait(state).unwrap_or_else(|e| e.into_inner()),
+            };
+        }
+    }
+
+    /// Rejects further connections and closes those never accepted.
+    fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        for (tx, rx) in state.pending.drain(..) {
+            tx.close();
+            rx.close();
+        }
+        self.ready.notify_all();
+    }
+}
+
+/// A `Listener` implementation that hands out a pair of in-process channels per connection.
+#[derive(Debug)]
+pub struct LoopbackListener {
+    backlog: Arc<LoopbackBacklog>,
+}
+
+impl LoopbackListener {
+    #[inline]
+    pub(crate) fn new(addr: String, buffer_size: usize) -> Self {
+        Self {
+            backlog: Arc::new(LoopbackBacklog {
+                addr,
+                buffer_size,
+                state: Mutex::new(LoopbackBacklogState::default()),
+                ready: Condvar::new(),
+            }),
+        }
+    }
+
+    #[inline]
+    pub(super) fn backlog(&self) -> &Arc<LoopbackBacklog> {
+        &self.backlog
+    }
+}
+
+impl Listener for LoopbackListener {
+    type Error = LoopbackTransportError;
+    type Endpoint = LoopbackEndpoint;
+
+    fn accept(&self) -> Result<Self::Endpoint, Self::Error> {
+        self.backlog
+            .accept_until(None)
+            .map(|endpoint| endpoint.expect("Untimed accept never times out"))
+    }
+
+    fn accept_timeout(&self, timeout: Duration) -> Result<Option<Self::Endpoint>, Self::Error> {
+        self.backlog.accept_until(Some(Instant::now() + timeout))
+    }
+}
+
+impl Drop for LoopbackListener {
+    fn drop(&mut self) {
+        transport::unregister_listener(&self.backlog);
+        self.backlog.close();
+    }
+}
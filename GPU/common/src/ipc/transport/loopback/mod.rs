@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+//! An in-process loopback transport layer implementation.
+//!
+//! This module provides a transport that never touches the operating system:
+//! both endpoints live in the same process and exchange bytes through a pair
+//! of in-memory buffers guarded by a `Mutex`/`Condvar`. Endpoints find each
+//! other through a process-wide registry keyed by address, which makes it a
+//! deterministic drop-in for unit testing framer, message and peer logic.
+
+mod error;
+pub use error::*;
+
+mod buffer;
+mod channel;
+
+mod endpoint;
+pub use endpoint::*;
+
+mod listener;
+pub use listener::*;
+
+mod transport;
+pub use transport::*;
@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    collections::HashMap,
+    ptr,
+    sync::{Arc, LazyLock, Mutex, Weak},
+    thread,
+    time::{Duration, Instant},
+};
+
+use tracing::debug;
+
+use crate::ipc::transport::Transport;
+
+use super::{
+    channel::LoopbackChannel,
+    endpoint::LoopbackEndpoint,
+    error::LoopbackTransportError,
+    listener::{LoopbackBacklog, LoopbackListener},
+};
+
+pub(super) const S2C_SUFFIX: &str = "_s2c";
+pub(super) const C2S_SUFFIX: &str = "_c2s";
+
+/// An address bound in the registry.
+enum LoopbackBinding {
+    /// Channels of a created endpoint waiting for its peer to connect.
+    Endpoint {
+        s2c: Weak<LoopbackChannel>,
+        c2s: Weak<LoopbackChannel>,
+    },
+    /// Backlog of a listener, accepting any number of peers.
+    Listener(Weak<LoopbackBacklog>),
+}
+
+/// Process-wide registry of created loopback endpoints and listeners.
+static REGISTRY: LazyLock<Mutex<HashMap<String, LoopbackBinding>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Removes the binding owning `s2c` from the registry, if not yet connected.
+pub(super) fn unregister(s2c: &Arc<LoopbackChannel>) {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    registry.retain(|_, binding| match binding {
+        LoopbackBinding::Endpoint { s2c: bound, .. } => !ptr::eq(bound.as_ptr(), Arc::as_ptr(s2c)),
+        LoopbackBinding::Listener(_) => true,
+    });
+}
+
+/// Removes the binding of the listener owning `backlog` from the registry.
+pub(super) fn unregister_listener(backlog: &Arc<LoopbackBacklog>) {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    registry.retain(|_, binding| match binding {
+        LoopbackBinding::Endpoint { .. } => true,
+        LoopbackBinding::Listener(bound) => !ptr::eq(bound.as_ptr(), Arc::as_ptr(backlog)),
+    });
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LoopbackTransport {
+    buffer_size: usize,
+    conn_timeout: Duration,
+}
+
+impl Transport for LoopbackTransport {
+    type Error = LoopbackTransportError;
+    type Endpoint = LoopbackEndpoint;
+    type Listener = LoopbackListener;
+    type Address = str;
+
+    fn create(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        if registry.contains_key(addr) {
+            return Err(LoopbackTransportError::AddressInUse {
+                name: addr.to_owned(),
+            });
+        }
+
+        let tx = Arc::new(LoopbackChannel::new(
+            format!("{}{}", addr, S2C_SUFFIX),
+            self.buffer_size,
+        ));
+        let rx = Arc::new(LoopbackChannel::new(
+            format!("{}{}", addr, C2S_SUFFIX),
+            self.buffer_size,
+        ));
+        registry.insert(
+            addr.to_owned(),
+            LoopbackBinding::Endpoint {
+                s2c: Arc::downgrade(&tx),
+                c2s: Arc::downgrade(&rx),
+            },
+        );
+
+        debug!("[Loopback] '{}': Ready", addr);
+        Ok(LoopbackEndpoint::new(tx, rx, true))
+    }
+
+    fn listen(&self, addr: &Self::Address) -> Result<Self::Listener, Self::Error> {
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        if registry.contains_key(addr) {
+            return Err(LoopbackTransportError::AddressInUse {
+                name: addr.to_owned(),
+            });
+        }
+
+        let listener = LoopbackListener::new(addr.to_owned(), self.buffer_size);
+        registry.insert(
+            addr.to_owned(),
+            LoopbackBinding::Listener(Arc::downgrade(listener.backlog())),
+        );
+
+        debug!("[Loopback] '{}': Listening", addr);
+        Ok(listener)
+    }
+
+    fn connect(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
+        const RETRY_DELAY: Duration = Duration::from_millis(10);
+
+        let start_time = Instant::now();
+        loop {
+            if start_time.elapsed() >= self.conn_timeout {
+                return Err(LoopbackTransportError::ConnectionTimeout);
+            }
+
+            let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+            match registry.get(addr) {
+                Some(LoopbackBinding::Endpoint { s2c, c2s }) => {
+                    let channels = (c2s.upgrade(), s2c.upgrade());
+                    registry.remove(addr);
+                    return match channels {
+                        (Some(tx), Some(rx)) => {
+                            debug!("[Loopback] '{}': Connected", addr);
+                            Ok(LoopbackEndpoint::new(tx, rx, false))
+                        }
+                        _ => Err(LoopbackTransportError::ConnectionClosed),
+                    };
+                }
+                Some(LoopbackBinding::Listener(backlog)) => {
+                    let backlog = backlog
+                        .upgrade()
+                        .ok_or(LoopbackTransportError::ConnectionClosed)?;
+                    drop(registry);
+
+                    let endpoint = backlog.connect()?;
+                    debug!("[Loopback] '{}': Connected", addr);
+                    return Ok(endpoint);
+                }
+                None => drop(registry),
+            }
+
+            thread::sleep(RETRY_DELAY);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoopbackTransportBuilder {
+    buffer_size: usize,
+    conn_timeout: Duration,
+}
+
+impl LoopbackTransportBuilder {
+    pub fn new() -> Self {
+        const DEFAULT_BUFF_SIZE: usize = 4096;
+        const DEFAULT_CONN_TIMEOUT: Duration = Duration::from_millis(100);
+
+        Self {
+            buffer_size: DEFAULT_BUFF_SIZE,
+            conn_timeout: DEFAULT_CONN_TIMEOUT,
+        }
+    }
+
+    #[inline]
+    pub fn buffer_size(mut self, value: usize) -> Self {
+        self.buffer_size = value;
+        self
+    }
+
+    #[inline]
+    pub fn connect_timeout(mut self, value: Duration) -> Self {
+        self.conn_timeout = value;
+        self
+    }
+
+    #[inline]
+    pub fn build(self) -> LoopbackTransport {
+        LoopbackTransport {
+            buffer_size: self.buffer_size,
+            conn_timeout: self.conn_timeout,
+        }
+    }
+}
+
+impl Default for LoopbackTransportBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
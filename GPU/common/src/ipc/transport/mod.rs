@@ -16,6 +16,9 @@ use std::{
     error::Error as StdError,
     fmt::{Debug, Display},
     ops::{Deref, DerefMut},
+    slice,
+    sync::Arc,
+    time::Duration,
 };
 
 /// A buffer trait for reading bytes from an endpoint.
@@ -36,6 +39,82 @@ pub trait WriteBuf: Debug + Deref<Target = [u8]> + DerefMut {
     fn submit(self, bytes: usize) -> Result<(), Self::Error>;
 }
 
+/// A memory region mapped by both sides of a connection, through which bulk data is
+/// passed by offset instead of being copied through the endpoint.
+pub trait BulkRegion: Debug + Send + Sync + 'static {
+    /// Returns the start of the region as mapped in this process.
+    fn as_mut_ptr(&self) -> *mut u8;
+
+    /// Returns the size of the region.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the region has no room at all.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reserves `len` bytes, returns their offset or `None` if no free range is large
+    /// enough.
+    fn reserve(&self, len: usize) -> Option<usize>;
+
+    /// Returns `len` bytes at `offset`, reserved earlier, to the region.
+    fn release(&self, offset: usize, len: usize);
+}
+
+impl dyn BulkRegion {
+    /// Allocates `len` bytes, returns `None` if no free range is large enough.
+    ///
+    /// The buffer is returned to the region when dropped.
+    pub fn alloc(&self, len: usize) -> Option<BulkBuf<'_>> {
+        let offset = self.reserve(len)?;
+
+        Some(BulkBuf {
+            region: self,
+            offset,
+            len,
+        })
+    }
+}
+
+/// A buffer allocated in a `BulkRegion`, returned to it on drop.
+#[derive(Debug)]
+pub struct BulkBuf<'a> {
+    region: &'a dyn BulkRegion,
+    offset: usize,
+    len: usize,
+}
+
+impl BulkBuf<'_> {
+    /// Returns the offset of the buffer within the region.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Deref for BulkBuf<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The range was reserved for this buffer alone.
+        unsafe { slice::from_raw_parts(self.region.as_mut_ptr().add(self.offset), self.len) }
+    }
+}
+
+impl DerefMut for BulkBuf<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: The range was reserved for this buffer alone.
+        unsafe { slice::from_raw_parts_mut(self.region.as_mut_ptr().add(self.offset), self.len) }
+    }
+}
+
+impl Drop for BulkBuf<'_> {
+    fn drop(&mut self) {
+        self.region.release(self.offset, self.len);
+    }
+}
+
 /// A bidirectional communication endpoint for IPC.
 pub trait Endpoint: Debug + Send + Sync + 'static {
     /// The error type for transport operations.
@@ -56,6 +135,69 @@ pub trait Endpoint: Debug + Send + Sync + 'static {
 
     /// Acquires a write buffer. Blocks until space is available.
     fn write(&mut self) -> Result<Self::WriteBuf<'_>, Self::Error>;
+
+    /// Acquires a read buffer. Blocks until data is available or `timeout` elapses.
+    ///
+    /// Returns `Ok(None)` if no data became available in time.
+    fn read_timeout(&mut self, timeout: Duration)
+    -> Result<Option<Self::ReadBuf<'_>>, Self::Error>;
+
+    /// Acquires a write buffer. Blocks until space is available or `timeout` elapses.
+    ///
+    /// Returns `Ok(None)` if no space became available in time.
+    fn write_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error>;
+
+    /// Acquires a write buffer of at least `len` bytes, or of the most the endpoint
+    /// can ever offer if that is less. Blocks until that much space is available.
+    fn write_at_least(&mut self, len: usize) -> Result<Self::WriteBuf<'_>, Self::Error>;
+
+    /// Acquires a write buffer of at least `len` bytes, see `write_at_least`. Blocks
+    /// until that much space is available or `timeout` elapses.
+    ///
+    /// Returns `Ok(None)` if not enough space became available in time.
+    fn write_at_least_timeout(
+        &mut self,
+        len: usize,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error>;
+
+    /// Acquires a read buffer if data is available, without blocking.
+    #[inline]
+    fn try_read(&mut self) -> Result<Option<Self::ReadBuf<'_>>, Self::Error> {
+        self.read_timeout(Duration::ZERO)
+    }
+
+    /// Acquires a write buffer if space is available, without blocking.
+    #[inline]
+    fn try_write(&mut self) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        self.write_timeout(Duration::ZERO)
+    }
+
+    /// Returns the bulk data region shared with the peer, if the connection has one.
+    #[inline]
+    fn arena(&self) -> Option<Arc<dyn BulkRegion>> {
+        None
+    }
+}
+
+/// A bound address accepting any number of peers, each on its own endpoint.
+pub trait Listener: Debug + Send + Sync + 'static {
+    /// The error type for transport operations.
+    type Error: StdError + Send + Sync + 'static;
+
+    /// The endpoint type produced for each accepted peer.
+    type Endpoint: Endpoint<Error = Self::Error>;
+
+    /// Accepts the next peer. Blocks until a peer connects.
+    fn accept(&self) -> Result<Self::Endpoint, Self::Error>;
+
+    /// Accepts the next peer. Blocks until a peer connects or `timeout` elapses.
+    ///
+    /// Returns `Ok(None)` if no peer connected in time.
+    fn accept_timeout(&self, timeout: Duration) -> Result<Option<Self::Endpoint>, Self::Error>;
 }
 
 /// A factory for creating IPC communication endpoints.
@@ -73,14 +215,26 @@ pub trait Transport: Debug + Send + Sync + 'static {
     /// The endpoint type produced by this transport.
     type Endpoint: Endpoint<Error = Self::Error>;
 
+    /// The listener type produced by this transport.
+    type Listener: Listener<Error = Self::Error, Endpoint = Self::Endpoint>;
+
     /// Creates a new communication endpoint bound to the given address.
     fn create(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error>;
 
+    /// Binds a listener to the given address, accepting any number of peers.
+    fn listen(&self, addr: &Self::Address) -> Result<Self::Listener, Self::Error>;
+
     /// Establishes a connection to a remote endpoint at the given address.
     fn connect(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error>;
 }
 
+mod stream;
+
+pub mod any;
+pub mod loopback;
 pub mod shmem;
+pub mod tcp;
+pub mod uds;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{collections::BTreeMap, fmt::Debug, io, ptr, sync::Mutex};
+
+use tracing::debug;
+
+use crate::{
+    ipc::transport::BulkRegion,
+    sys::{mmap::Mmap, page, shmem::Shmem},
+};
+
+use super::{error::ShmemTransportError, segment::ShmemSegmentHeader};
+
+/// Allocations are rounded up to whole cache lines.
+const ARENA_ALIGN: usize = 64;
+
+/// A shared memory region for bulk data, mapped by both sides of a connection.
+///
+/// Arguments too large to copy through the channels are staged in the arena by the
+/// connecting side and sent as an offset, see `Argument::from_shared_slice`. The other
+/// side resolves them to its own mapping with `Request::resolve_shared`, so the data
+/// is never copied by the transport.
+///
+/// Only the connecting side allocates, the arena is laid out as the segment header
+/// on the first page followed by the data.
+pub struct ShmemArena {
+    shmem: Shmem,
+    mapping: Mmap,
+    data_len: usize,
+    /// Free ranges of the data, offset to length, never adjacent.
+    free: Mutex<BTreeMap<usize, usize>>,
+}
+
+// SAFETY: The mapping is shared memory valid for the lifetime of the arena, and
+// allocations hand out disjoint ranges of it.
+unsafe impl Send for ShmemArena {}
+unsafe impl Sync for ShmemArena {}
+
+impl ShmemArena {
+    pub(super) fn create<S: AsRef<str>>(name: S, len: usize) -> Result<Self, ShmemTransportError> {
+        let creation_error = |source: io::Error| ShmemTransportError::CreationError {
+            name: name.as_ref().to_owned(),
+            source,
+        };
+
+        let data_len = page::page_align(len);
+        let file_len = data_len + page::page_size();
+
+        let shmem = Shmem::create(&name, file_len).map_err(creation_error)?;
+        let mapping = Mmap::map_shared(&shmem, file_len).map_err(creation_error)?;
+
+        // SAFETY: The segment is newly created and its first page holds the header.
+        unsafe {
+            let header = mapping.ptr().cast::<ShmemSegmentHeader>();
+            ptr::write(header, ShmemSegmentHeader::new());
+            (*header).publish();
+        }
+
+        debug!("[Shmem] Arena '{}' created, data_len={}", shmem, data_len);
+        Ok(Self::new(shmem, mapping, data_len))
+    }
+
+    /// Maps the arena created along with the connection, `None` if there is none.
+    pub(super) fn open<S: AsRef<str>>(name: S) -> Result<Option<Self>, ShmemTransportError> {
+        let open_error = |source: io::Error| ShmemTransportError::OpenError {
+            name: name.as_ref().to_owned(),
+            source,
+        };
+
+        let shmem = match Shmem::open(&name) {
+            Ok(shmem) => shmem,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(open_error(e)),
+        };
+        if shmem.size() <= page::page_size() || shmem.size() % page::page_size() != 0 {
+            return Err(open_error(io::ErrorKind::InvalidData.into()));
+        }
+        let mapping = Mmap::map_shared(&shmem, shmem.size()).map_err(open_error)?;
+        let data_len = shmem.size() - page::page_size();
+
+        debug!("[Shmem] Arena '{}' opened, data_len={}", shmem, data_len);
+        Ok(Some(Self::new(shmem, mapping, data_len)))
+    }
+
+    fn new(shmem: Shmem, mapping: Mmap, data_len: usize) -> Self {
+        Self {
+            shmem,
+            mapping,
+            data_len,
+            free: Mutex::new(BTreeMap::from([(0, data_len)])),
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        self.shmem.name()
+    }
+}
+
+impl BulkRegion for ShmemArena {
+    #[inline]
+    fn as_mut_ptr(&self) -> *mut u8 {
+        // SAFETY: The data follows the header page within the mapping.
+        unsafe { self.mapping.ptr().add(page::page_size()) }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.data_len
+    }
+
+    fn reserve(&self, len: usize) -> Option<usize> {
+        let size = len.max(1).checked_next_multiple_of(ARENA_ALIGN)?;
+
+        let mut free = self.free.lock().unwrap_or_else(|e| e.into_inner());
+        let (&offset, &free_len) = free.iter().find(|&(_, &free_len)| free_len >= size)?;
+        free.remove(&offset);
+        if free_len > size {
+            free.insert(offset + size, free_len - size);
+        }
+
+        Some(offset)
+    }
+
+    /// Merges the range with its free neighbours.
+    fn release(&self, offset: usize, len: usize) {
+        let mut offset = offset;
+        let mut size = len.max(1).next_multiple_of(ARENA_ALIGN);
+
+        let mut free = self.free.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((&next, &next_len)) = free.range(offset + size..).next()
+            && next == offset + size
+        {
+            free.remove(&next);
+            size += next_len;
+        }
+        if let Some((&prev, &prev_len)) = free.range(..offset).next_back()
+            && prev + prev_len == offset
+        {
+            free.remove(&prev);
+            offset = prev;
+            size += prev_len;
+        }
+        free.insert(offset, size);
+    }
+}
+
+impl Debug for ShmemArena {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShmemArena")
+            .field("name", &self.name())
+            .field("data_ptr", &self.as_mut_ptr())
+            .field("data_len", &self.data_len)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        process, slice,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    fn unique_arena_name() -> String {
+        static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+        format!(
+            "/xgpu_arena_{}_{}",
+            process::id(),
+            SEQ.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    #[test]
+    fn test_alloc_release() {
+        let name = unique_arena_name();
+        let arena = ShmemArena::create(&name, 4096).unwrap();
+        let arena: &dyn BulkRegion = &arena;
+        assert_eq!(arena.len(), page::page_align(4096));
+
+        let a = arena.alloc(100).unwrap();
+        let b = arena.alloc(100).unwrap();
+        assert_eq!((a.offset(), a.len()), (0, 100));
+        assert_eq!(b.offset(), 128);
+
+        // Too large for what is left
+        assert!(arena.alloc(arena.len()).is_none());
+
+        // Freed neighbours merge back into a single range
+        drop(a);
+        drop(b);
+        let whole = arena.alloc(arena.len()).unwrap();
+        assert_eq!(whole.offset(), 0);
+    }
+
+    #[test]
+    fn test_shared_between_mappings() {
+        let name = unique_arena_name();
+        let arena = ShmemArena::create(&name, 4096).unwrap();
+        let arena: &dyn BulkRegion = &arena;
+        let peer = ShmemArena::open(&name).unwrap().expect("Arena not found");
+        assert_eq!(peer.len(), arena.len());
+
+        let mut buf = arena.alloc(16).unwrap();
+        buf.copy_from_slice(&[7u8; 16]);
+
+        // The peer reaches the same bytes at the same offset of its own mapping
+        let mirror = unsafe { slice::from_raw_parts(peer.as_mut_ptr().add(buf.offset()), 16) };
+        assert_eq!(mirror, &[7u8; 16]);
+
+        assert!(ShmemArena::open(unique_arena_name()).unwrap().is_none());
+    }
+}
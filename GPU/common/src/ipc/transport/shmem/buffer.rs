@@ -21,14 +21,14 @@ use tracing::debug;
 
 use crate::{
     ipc::transport::{ReadBuf, WriteBuf},
-    sys::futex::FutexMutexGuard,
+    sys::futex::RobustMutexGuard,
 };
 
 use super::{channel::ShmemChannel, error::ShmemTransportError};
 
 #[derive(Debug)]
 pub struct ShmemReadBuffer<'a> {
-    _guard: FutexMutexGuard<'a>,
+    _guard: Option<RobustMutexGuard<'a>>,
     channel: &'a ShmemChannel,
     ptr: *mut u8,
     len: usize,
@@ -39,7 +39,7 @@ impl<'a> ShmemReadBuffer<'a> {
     /// Creates a new read buffer.
     #[inline]
     pub fn new(
-        guard: FutexMutexGuard<'a>,
+        guard: Option<RobustMutexGuard<'a>>,
         channel: &'a ShmemChannel,
         ptr: *mut u8,
         len: usize,
@@ -74,8 +74,8 @@ impl<'a> Deref for ShmemReadBuffer<'a> {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        // SAFETY: The existence of the lock guard (`_guard`) guarantees that
-        // we have exclusive read access and that the pointer and length are valid
+        // SAFETY: The lock guard (`_guard`), or being the single reader in SPSC mode,
+        // guarantees that we have exclusive read access and that the pointer and length are valid
         // for the lifetime of this struct.
         unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
     }
@@ -83,8 +83,8 @@ impl<'a> Deref for ShmemReadBuffer<'a> {
 
 impl<'a> DerefMut for ShmemReadBuffer<'a> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        // SAFETY: The existence of the lock guard (`_guard`) guarantees that
-        // we have exclusive read access and that the pointer and length are valid
+        // SAFETY: The lock guard (`_guard`), or being the single reader in SPSC mode,
+        // guarantees that we have exclusive read access and that the pointer and length are valid
         // for the lifetime of this struct. A read buffer is typically read-only,
         // but providing `DerefMut` can be useful in some protocols.
         unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
@@ -108,7 +108,7 @@ impl Drop for ShmemReadBuffer<'_> {
 
 #[derive(Debug)]
 pub struct ShmemWriteBuffer<'a> {
-    _guard: FutexMutexGuard<'a>,
+    _guard: Option<RobustMutexGuard<'a>>,
     channel: &'a ShmemChannel,
     ptr: *mut u8,
     len: usize,
@@ -119,7 +119,7 @@ impl<'a> ShmemWriteBuffer<'a> {
     /// Creates a new write buffer.
     #[inline]
     pub fn new(
-        guard: FutexMutexGuard<'a>,
+        guard: Option<RobustMutexGuard<'a>>,
         channel: &'a ShmemChannel,
         ptr: *mut u8,
         len: usize,
@@ -154,8 +154,8 @@ impl<'a> Deref for ShmemWriteBuffer<'a> {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        // SAFETY: The existence of the lock guard (`_guard`) guarantees that
-        // we have exclusive access and that the pointer and length are valid
+        // SAFETY: The lock guard (`_guard`), or being the single writer in SPSC mode,
+        // guarantees that we have exclusive access and that the pointer and length are valid
         // for the lifetime of this struct.
         unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
     }
@@ -163,8 +163,8 @@ impl<'a> Deref for ShmemWriteBuffer<'a> {
 
 impl<'a> DerefMut for ShmemWriteBuffer<'a> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        // SAFETY: The existence of the lock guard (`_guard`) guarantees that
-        // we have exclusive access and that the pointer and length are valid
+        // SAFETY: The lock guard (`_guard`), or being the single writer in SPSC mode,
+        // guarantees that we have exclusive access and that the pointer and length are valid
         // for the lifetime of this struct.
         unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
     }
@@ -23,16 +23,25 @@ use std::{
 };
 
 use linux_futex::{Futex, Shared};
-use tracing::debug;
+use tracing::{debug, warn};
 
-use crate::sys::{cache::CacheLineAligned, futex::FutexMutex};
+use crate::sys::{
+    cache::CacheLineAligned,
+    futex::{RobustLockError, RobustMutex, RobustMutexGuard},
+    process::{AtomicProcessId, ProcessId},
+};
 
 use super::{
     buffer::{ShmemReadBuffer, ShmemWriteBuffer},
     error::ShmemTransportError,
     memory::ShmemRegion,
+    segment::ShmemSegmentHeader,
+    wait::{ShmemWaitCounters, ShmemWaitStats, ShmemWaitStrategy},
 };
 
+/// Interval at which a blocked reader or writer checks whether its peer is still alive.
+const LIVENESS_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
 #[repr(u8)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum ShmemChannelState {
@@ -40,6 +49,8 @@ pub enum ShmemChannelState {
     Uninited = 0,
     Ready = 1,
     Closed = 2,
+    /// A process died while holding the buffer lock.
+    Inconsistent = 3,
 }
 
 impl TryFrom<u8> for ShmemChannelState {
@@ -49,6 +60,7 @@ impl TryFrom<u8> for ShmemChannelState {
             0 => Ok(ShmemChannelState::Uninited),
             1 => Ok(ShmemChannelState::Ready),
             2 => Ok(ShmemChannelState::Closed),
+            3 => Ok(ShmemChannelState::Inconsistent),
             _ => Err(ShmemTransportError::InvalidConnectionState),
         }
     }
@@ -60,25 +72,58 @@ impl Display for ShmemChannelState {
     }
 }
 
+/// How readers and writers of a channel are synchronized, chosen by the creator of the channel.
+#[repr(u8)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ShmemSyncMode {
+    /// Exactly one reader and one writer, synchronized by the `head`/`tail` cursors alone.
+    #[default]
+    Spsc = 0,
+    /// Any number of readers and writers, serialized by the buffer lock.
+    Mpmc = 1,
+}
+
+impl TryFrom<u8> for ShmemSyncMode {
+    type Error = ShmemTransportError;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ShmemSyncMode::Spsc),
+            1 => Ok(ShmemSyncMode::Mpmc),
+            _ => Err(ShmemTransportError::InvalidConnectionState),
+        }
+    }
+}
+
 /// A control block located at the start of the shmem for synchronization.
 #[repr(C)]
 #[derive(Debug)]
 pub struct ShmemCtrlBlock {
+    /// Segment header, must stay first so the segment can be inspected without mapping it.
+    header: CacheLineAligned<ShmemSegmentHeader>,
     /// Read cursor. Incremented by readers.
     pub head: CacheLineAligned<AtomicUsize>,
     /// Write cursor. Incremented by writers.
     pub tail: CacheLineAligned<AtomicUsize>,
     /// State of the channel.
     state: CacheLineAligned<AtomicU8>,
-    /// Mutex to ensure exclusive access for buffer.
-    buf_lock: CacheLineAligned<FutexMutex>,
+    /// Synchronization mode of the channel, immutable once the channel is ready.
+    mode: AtomicU8,
+    /// Mutex to ensure exclusive access for buffer, unused in `ShmemSyncMode::Spsc`.
+    buf_lock: CacheLineAligned<RobustMutex>,
     /// Futex for readers to wait on when the buffer is empty.
     readable: CacheLineAligned<Futex<Shared>>,
     /// Futex for writers to wait on when the buffer is full.
     writable: CacheLineAligned<Futex<Shared>>,
+    /// Process which opened the channel.
+    opener: CacheLineAligned<AtomicProcessId>,
 }
 
 impl ShmemCtrlBlock {
+    #[inline]
+    pub(super) fn header(&self) -> &ShmemSegmentHeader {
+        &self.header
+    }
+
     #[inline]
     pub fn get_state(&self) -> Result<ShmemChannelState, ShmemTransportError> {
         ShmemChannelState::try_from(self.state.load(Ordering::Acquire))
@@ -89,37 +134,27 @@ impl ShmemCtrlBlock {
         self.state.store(state as u8, Ordering::Release);
     }
 
-    #[inline]
-    fn wait_readable(&self, last_value: u32) {
-        let _ = self.readable.wait(last_value);
-    }
-
     #[inline]
     pub fn notify_readable(&self) {
-        self.readable.value.fetch_add(1, Ordering::Relaxed);
+        self.readable.value.fetch_add(1, Ordering::Release);
         self.readable.wake(1);
     }
 
     #[inline]
     pub fn notify_all_readable(&self) {
-        self.readable.value.fetch_add(1, Ordering::Relaxed);
+        self.readable.value.fetch_add(1, Ordering::Release);
         self.readable.wake(i32::MAX);
     }
 
-    #[inline]
-    fn wait_writable(&self, last_value: u32) {
-        let _ = self.writable.wait(last_value);
-    }
-
     #[inline]
     pub fn notify_writable(&self) {
-        self.writable.value.fetch_add(1, Ordering::Relaxed);
+        self.writable.value.fetch_add(1, Ordering::Release);
         self.writable.wake(1);
     }
 
     #[inline]
     pub fn notify_all_writable(&self) {
-        self.writable.value.fetch_add(1, Ordering::Relaxed);
+        self.writable.value.fetch_add(1, Ordering::Release);
         self.writable.wake(i32::MAX);
     }
 }
@@ -130,9 +165,12 @@ pub struct ShmemChannel {
     memory: ShmemRegion,
     control_ptr: *mut ShmemCtrlBlock,
     buffer_ptr: *mut u8,
+    sync_mode: ShmemSyncMode,
+    wait_strategy: ShmemWaitStrategy,
+    wait_stats: ShmemWaitCounters,
 }
 
-// SAFETY: All access to raw pointers is synchronized by atomic operations and FutexMutexes.
+// SAFETY: All access to raw pointers is synchronized by atomic operations and RobustMutexes.
 unsafe impl Send for ShmemChannel {}
 unsafe impl Sync for ShmemChannel {}
 
@@ -145,7 +183,11 @@ impl Deref for ShmemChannel {
 }
 
 impl ShmemChannel {
-    pub fn create<S: AsRef<str>>(name: S, buffer_size: usize) -> Result<Self, ShmemTransportError> {
+    pub fn create<S: AsRef<str>>(
+        name: S,
+        buffer_size: usize,
+        sync_mode: ShmemSyncMode,
+    ) -> Result<Self, ShmemTransportError> {
         let reserve_size = size_of::<ShmemCtrlBlock>();
 
         let memory = ShmemRegion::create(&name, buffer_size, reserve_size).map_err(|e| {
@@ -161,6 +203,9 @@ impl ShmemChannel {
             memory,
             control_ptr,
             buffer_ptr,
+            sync_mode,
+            wait_strategy: ShmemWaitStrategy::default(),
+            wait_stats: ShmemWaitCounters::default(),
         };
 
         // SAFETY: The shared memory is newly created and correctly sized.
@@ -169,15 +214,26 @@ impl ShmemChannel {
             ptr::write(
                 channel.control_ptr,
                 ShmemCtrlBlock {
+                    header: CacheLineAligned(ShmemSegmentHeader::new()),
                     head: CacheLineAligned(AtomicUsize::new(0)),
                     tail: CacheLineAligned(AtomicUsize::new(0)),
                     state: CacheLineAligned(AtomicU8::new(ShmemChannelState::Uninited as u8)),
-                    buf_lock: CacheLineAligned(FutexMutex::new()),
+                    mode: AtomicU8::new(sync_mode as u8),
+                    buf_lock: CacheLineAligned(RobustMutex::uninit()),
                     readable: CacheLineAligned(Futex::new(0)),
                     writable: CacheLineAligned(Futex::new(0)),
+                    opener: CacheLineAligned(AtomicProcessId::new()),
                 },
             );
+            channel
+                .buf_lock
+                .init()
+                .map_err(|e| ShmemTransportError::CreationError {
+                    name: name.as_ref().to_owned(),
+                    source: e,
+                })?;
         }
+        channel.header().publish();
         channel.set_state(ShmemChannelState::Ready);
         debug!("[Shmem] '{}': Ready", channel);
 
@@ -185,16 +241,37 @@ impl ShmemChannel {
     }
 
     pub fn open<S: AsRef<str>>(name: S, timeout: Duration) -> Result<Self, ShmemTransportError> {
+        let channel = Self::attach(name, timeout)?;
+        channel.opener.store(ProcessId::current());
+
+        debug!("[Shmem] '{}': Ready", channel);
+        Ok(channel)
+    }
+
+    /// Opens the channel as one of many short-lived writers, without becoming the peer
+    /// whose death closes the channel.
+    pub fn open_shared<S: AsRef<str>>(
+        name: S,
+        timeout: Duration,
+    ) -> Result<Self, ShmemTransportError> {
+        let channel = Self::attach(name, timeout)?;
+
+        debug!("[Shmem] '{}': Ready (shared)", channel);
+        Ok(channel)
+    }
+
+    /// Maps an existing channel once it is ready, trying at least once even if `timeout` is zero.
+    fn attach<S: AsRef<str>>(name: S, timeout: Duration) -> Result<Self, ShmemTransportError> {
         const RETRY_DELAY: Duration = Duration::from_millis(10);
 
         let start_time = Instant::now();
         let shmem = loop {
-            if start_time.elapsed() >= timeout {
-                return Err(ShmemTransportError::ConnectionTimeout);
-            }
             match ShmemRegion::open(&name, size_of::<ShmemCtrlBlock>()) {
                 Ok(shmem) => break shmem,
                 Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    if start_time.elapsed() >= timeout {
+                        return Err(ShmemTransportError::ConnectionTimeout);
+                    }
                     thread::sleep(RETRY_DELAY);
                     continue;
                 }
@@ -210,24 +287,32 @@ impl ShmemChannel {
         let control_ptr = shmem.reserved_ptr() as *mut ShmemCtrlBlock;
         let buffer_ptr = shmem.data_ptr();
 
-        let channel = Self {
+        let mut channel = Self {
             memory: shmem,
             control_ptr,
             buffer_ptr,
+            sync_mode: ShmemSyncMode::default(),
+            wait_strategy: ShmemWaitStrategy::default(),
+            wait_stats: ShmemWaitCounters::default(),
         };
 
         loop {
-            if start_time.elapsed() >= timeout {
-                return Err(ShmemTransportError::ConnectionTimeout);
-            }
             match channel.get_state() {
                 Ok(ShmemChannelState::Ready) => break,
                 Ok(ShmemChannelState::Closed) => return Err(ShmemTransportError::ConnectionClosed),
-                _ => thread::sleep(RETRY_DELAY),
+                Ok(ShmemChannelState::Inconsistent) => {
+                    return Err(ShmemTransportError::ConnectionInconsistent);
+                }
+                _ => {
+                    if start_time.elapsed() >= timeout {
+                        return Err(ShmemTransportError::ConnectionTimeout);
+                    }
+                    thread::sleep(RETRY_DELAY);
+                }
             }
         }
+        channel.sync_mode = ShmemSyncMode::try_from(channel.mode.load(Ordering::Relaxed))?;
 
-        debug!("[Shmem] '{}': Ready", channel);
         Ok(channel)
     }
 
@@ -241,19 +326,130 @@ impl ShmemChannel {
         self.memory.is_owner()
     }
 
+    #[inline]
+    pub fn sync_mode(&self) -> ShmemSyncMode {
+        self.sync_mode
+    }
+
+    /// Sets how this side of the channel waits for its peer.
+    #[inline]
+    pub fn set_wait_strategy(&mut self, strategy: ShmemWaitStrategy) {
+        self.wait_strategy = strategy;
+    }
+
+    /// Returns how often each wait path ended up handing out a buffer.
+    #[inline]
+    pub fn wait_stats(&self) -> ShmemWaitStats {
+        self.wait_stats.snapshot()
+    }
+
     #[inline]
     pub fn capacity(&self) -> usize {
         self.memory.data_len()
     }
 
+    /// Returns the process on the other side of the channel, if it has shown up yet.
+    #[inline]
+    pub fn peer(&self) -> Option<ProcessId> {
+        match self.is_owner() {
+            true => self.opener.load(),
+            false => self.header().creator(),
+        }
+    }
+
+    /// Records the process expected to open the channel, so that its death closes the
+    /// channel even before it gets to open it.
+    #[inline]
+    pub(super) fn expect_peer(&self, peer: ProcessId) {
+        debug_assert!(self.is_owner());
+        self.opener.store(peer);
+    }
+
+    /// Closes the channel if the peer process has died.
+    ///
+    /// A peer whose liveness cannot be determined is assumed to be alive.
+    fn check_peer(&self) -> Result<(), ShmemTransportError> {
+        let Some(peer) = self.peer() else {
+            return Ok(());
+        };
+
+        if peer.is_alive() == Some(false) {
+            warn!("[Shmem] '{}': Peer process {} died", self, peer.pid);
+            self.close();
+            return Err(ShmemTransportError::ConnectionClosed);
+        }
+
+        Ok(())
+    }
+
+    /// Fails if the channel is no longer usable.
+    #[inline]
+    fn check_state(&self) -> Result<(), ShmemTransportError> {
+        match self.get_state()? {
+            ShmemChannelState::Closed => Err(ShmemTransportError::ConnectionClosed),
+            ShmemChannelState::Inconsistent => Err(ShmemTransportError::ConnectionInconsistent),
+            _ => Ok(()),
+        }
+    }
+
+    /// Acquires the buffer lock, or nothing in `ShmemSyncMode::Spsc` where the single reader
+    /// and the single writer each own one cursor.
+    ///
+    /// If the previous lock owner died, the channel is marked inconsistent and closed for good.
+    /// The lock itself is made consistent again, so that every other locker sees the same state.
+    fn lock_buf(&self) -> Result<Option<RobustMutexGuard<'_>>, ShmemTransportError> {
+        if self.sync_mode == ShmemSyncMode::Spsc {
+            return Ok(None);
+        }
+
+        match self.buf_lock.lock() {
+            Ok(guard) => Ok(Some(guard)),
+            Err(RobustLockError::OwnerDied(guard)) => {
+                warn!("[Shmem] '{}': Buffer lock owner died", self);
+                self.set_state(ShmemChannelState::Inconsistent);
+                if let Err(e) = guard.mark_consistent() {
+                    warn!("[Shmem] '{}': Failed to recover buffer lock, {}", self, e);
+                }
+                drop(guard);
+
+                self.notify_all_readable();
+                self.notify_all_writable();
+                Err(ShmemTransportError::ConnectionInconsistent)
+            }
+            Err(RobustLockError::NotRecoverable) => {
+                self.set_state(ShmemChannelState::Inconsistent);
+                Err(ShmemTransportError::ConnectionInconsistent)
+            }
+        }
+    }
+
     pub fn read_buf(&'_ self) -> Result<ShmemReadBuffer<'_>, ShmemTransportError> {
+        self.read_buf_until(None)
+            .map(|buf| buf.expect("Untimed read never times out"))
+    }
+
+    /// Acquires a read buffer, giving up with `Ok(None)` once `deadline` has passed.
+    ///
+    /// In `ShmemSyncMode::Spsc`, the caller must be the only reader of the channel.
+    pub fn read_buf_until(
+        &'_ self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<ShmemReadBuffer<'_>>, ShmemTransportError> {
+        let mut waited = None;
         loop {
-            if self.get_state()? == ShmemChannelState::Closed {
-                return Err(ShmemTransportError::ConnectionClosed);
-            }
+            // Data written before the channel was closed is still handed out.
+            let closed = match self.get_state()? {
+                ShmemChannelState::Inconsistent => {
+                    return Err(ShmemTransportError::ConnectionInconsistent);
+                }
+                state => state == ShmemChannelState::Closed,
+            };
 
-            let guard = self.buf_lock.lock();
+            let guard = self.lock_buf()?;
 
+            // Read the futex event counter before the cursors, so that a notification
+            // published after the cursors were read makes the wait below return at once.
+            let last_value = self.readable.value.load(Ordering::Acquire);
             let head = self.head.load(Ordering::Relaxed);
             let tail = self.tail.load(Ordering::Acquire);
             let readable_bytes = tail.wrapping_sub(head);
@@ -265,47 +461,95 @@ impl ShmemChannel {
                 // We can treat all `readable_bytes` as a single, contiguous slice.
                 // The virtual memory mirroring handles any "wrap-around" seamlessly.
                 debug!("[Shmem] '{}': Reading...", self);
-                return Ok(ShmemReadBuffer::new(guard, self, ptr, readable_bytes));
+                self.wait_stats.record(waited);
+                return Ok(Some(ShmemReadBuffer::new(guard, self, ptr, readable_bytes)));
             }
 
-            // Before releasing the lock, read the current value of the futex event counter.
-            let last_value = self.readable.value.load(Ordering::Relaxed);
             drop(guard);
 
+            if closed {
+                return Err(ShmemTransportError::ConnectionClosed);
+            }
+
+            let Some(timeout) = remaining(deadline) else {
+                return Ok(None);
+            };
+
+            // Wake up periodically to notice a peer that died without closing the channel
+            let timeout =
+                timeout.map_or(LIVENESS_CHECK_INTERVAL, |t| t.min(LIVENESS_CHECK_INTERVAL));
+
             debug!("[Shmem] '{}': Waiting readable...", self);
-            self.wait_readable(last_value);
+            let (path, timed_out) = self.wait_strategy.wait(&self.readable, last_value, timeout);
+            if timed_out {
+                self.check_peer()?;
+            }
+            waited = Some(path);
         }
     }
 
     pub fn write_buf(&'_ self) -> Result<ShmemWriteBuffer<'_>, ShmemTransportError> {
+        self.write_buf_until(1, None)
+            .map(|buf| buf.expect("Untimed write never times out"))
+    }
+
+    /// Acquires a write buffer of at least `min_len` bytes, or of the most the ring
+    /// ever offers if smaller, giving up with `Ok(None)` once `deadline` has passed.
+    ///
+    /// In `ShmemSyncMode::Spsc`, the caller must be the only writer of the channel.
+    pub fn write_buf_until(
+        &'_ self,
+        min_len: usize,
+        deadline: Option<Instant>,
+    ) -> Result<Option<ShmemWriteBuffer<'_>>, ShmemTransportError> {
+        // One byte always stays free, telling a full ring from an empty one
+        let min_len = min_len.clamp(1, self.capacity() - 1);
+        let mut waited = None;
         loop {
-            if self.get_state()? == ShmemChannelState::Closed {
-                return Err(ShmemTransportError::ConnectionClosed);
-            }
+            self.check_state()?;
 
-            let guard = self.buf_lock.lock();
+            let guard = self.lock_buf()?;
 
+            // Read the futex event counter before the cursors, so that a notification
+            // published after the cursors were read makes the wait below return at once.
+            let last_value = self.writable.value.load(Ordering::Acquire);
             let head = self.head.load(Ordering::Acquire);
             let tail = self.tail.load(Ordering::Relaxed);
             let used_space = tail.wrapping_sub(head);
 
             let writable_bytes = self.capacity() - used_space - 1;
-            if writable_bytes > 0 {
+            if writable_bytes >= min_len {
                 let offset = tail % self.capacity();
                 let ptr = unsafe { self.buffer_ptr.add(offset) };
 
                 // We can offer the entire `writable_bytes` as a single, contiguous slice.
                 // The virtual memory mirroring handles any "wrap-around" seamlessly.
                 debug!("[Shmem] '{}': Writting...", self);
-                return Ok(ShmemWriteBuffer::new(guard, self, ptr, writable_bytes));
+                self.wait_stats.record(waited);
+                return Ok(Some(ShmemWriteBuffer::new(
+                    guard,
+                    self,
+                    ptr,
+                    writable_bytes,
+                )));
             }
 
-            // Before releasing the lock, read the current value of the futex event counter.
-            let last_value = self.writable.value.load(Ordering::Relaxed);
             drop(guard);
 
+            let Some(timeout) = remaining(deadline) else {
+                return Ok(None);
+            };
+
+            // Wake up periodically to notice a peer that died without closing the channel
+            let timeout =
+                timeout.map_or(LIVENESS_CHECK_INTERVAL, |t| t.min(LIVENESS_CHECK_INTERVAL));
+
             debug!("[Shmem] '{}': Waiting writable...", self);
-            self.wait_writable(last_value);
+            let (path, timed_out) = self.wait_strategy.wait(&self.writable, last_value, timeout);
+            if timed_out {
+                self.check_peer()?;
+            }
+            waited = Some(path);
         }
     }
 
@@ -324,8 +568,111 @@ impl Drop for ShmemChannel {
     }
 }
 
+/// Returns the wait timeout left until `deadline`, `None` inside means waiting forever.
+///
+/// Returns `None` if the deadline has already passed.
+#[inline]
+fn remaining(deadline: Option<Instant>) -> Option<Option<Duration>> {
+    match deadline {
+        Some(deadline) => {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            (!timeout.is_zero()).then_some(Some(timeout))
+        }
+        None => Some(None),
+    }
+}
+
 impl Display for ShmemChannel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         Display::fmt(self.name(), f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        process::{self, Command},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    fn unique_channel_name() -> String {
+        static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+        format!(
+            "/xgpu_channel_{}_{}",
+            process::id(),
+            SEQ.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    fn exited_process() -> ProcessId {
+        let mut child = Command::new("true").spawn().expect("Failed to spawn child");
+        let id = ProcessId {
+            pid: child.id(),
+            ..ProcessId::current()
+        };
+        child.wait().expect("Failed to wait child");
+
+        id
+    }
+
+    #[test]
+    fn test_peer_identity() {
+        let name = unique_channel_name();
+        let owner = ShmemChannel::create(&name, 4096, ShmemSyncMode::default()).unwrap();
+        assert_eq!(owner.peer(), None);
+
+        let opener = ShmemChannel::open(&name, Duration::from_secs(1)).unwrap();
+        assert_eq!(owner.peer(), Some(ProcessId::current()));
+        assert_eq!(opener.peer(), Some(ProcessId::current()));
+    }
+
+    #[test]
+    fn test_peer_death_closes_channel() {
+        let name = unique_channel_name();
+        let owner = ShmemChannel::create(&name, 4096, ShmemSyncMode::default()).unwrap();
+        owner.opener.store(exited_process());
+
+        let start_time = Instant::now();
+        let result = owner.read_buf();
+
+        assert!(matches!(result, Err(ShmemTransportError::ConnectionClosed)));
+        assert!(start_time.elapsed() < LIVENESS_CHECK_INTERVAL * 10);
+        assert_eq!(owner.get_state().unwrap(), ShmemChannelState::Closed);
+    }
+
+    #[test]
+    fn test_peer_alive_keeps_channel() {
+        let name = unique_channel_name();
+        let owner = ShmemChannel::create(&name, 4096, ShmemSyncMode::default()).unwrap();
+        owner.opener.store(ProcessId::current());
+
+        let result = owner.read_buf_until(Some(Instant::now() + LIVENESS_CHECK_INTERVAL * 3));
+
+        assert!(matches!(result, Ok(None)));
+        assert_eq!(owner.get_state().unwrap(), ShmemChannelState::Ready);
+    }
+
+    #[test]
+    fn test_lock_owner_death_marks_inconsistent() {
+        let name = unique_channel_name();
+        let owner = ShmemChannel::create(&name, 4096, ShmemSyncMode::Mpmc).unwrap();
+        let opener = ShmemChannel::open(&name, Duration::from_secs(1)).unwrap();
+
+        // The lock holder exits without releasing the lock
+        std::thread::scope(|s| {
+            s.spawn(|| std::mem::forget(opener.buf_lock.lock().unwrap()));
+        });
+
+        assert!(matches!(
+            owner.read_buf(),
+            Err(ShmemTransportError::ConnectionInconsistent)
+        ));
+        assert_eq!(owner.get_state().unwrap(), ShmemChannelState::Inconsistent);
+        assert!(matches!(
+            opener.write_buf(),
+            Err(ShmemTransportError::ConnectionInconsistent)
+        ));
+    }
+}
@@ -12,26 +12,64 @@
  * See the Mulan PSL v2 for more details.
  */
 
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::ipc::transport::Endpoint;
+use crate::{
+    ipc::transport::{BulkRegion, Endpoint},
+    sys::process::ProcessId,
+};
 
 use super::{
+    arena::ShmemArena,
     buffer::{ShmemReadBuffer, ShmemWriteBuffer},
     channel::ShmemChannel,
     error::ShmemTransportError,
+    wait::ShmemWaitStats,
 };
 
 /// A `Transport` implementation that uses two shared memory channels for bidirectional communication.
 pub struct ShmemEndpoint {
     tx: ShmemChannel,
     rx: ShmemChannel,
+    arena: Option<Arc<dyn BulkRegion>>,
 }
 
 impl ShmemEndpoint {
     #[inline]
-    pub(crate) fn new(tx: ShmemChannel, rx: ShmemChannel) -> Self {
-        Self { tx, rx }
+    pub(crate) fn new(tx: ShmemChannel, rx: ShmemChannel, arena: Option<ShmemArena>) -> Self {
+        Self {
+            tx,
+            rx,
+            arena: arena.map(|arena| Arc::new(arena) as Arc<dyn BulkRegion>),
+        }
+    }
+
+    /// Records the process expected to open the channels, see `ShmemChannel::expect_peer`.
+    #[inline]
+    pub(super) fn expect_peer(&self, peer: ProcessId) {
+        self.tx.expect_peer(peer);
+        self.rx.expect_peer(peer);
+    }
+
+    #[cfg(test)]
+    pub(super) fn into_channels(self) -> (ShmemChannel, ShmemChannel) {
+        (self.tx, self.rx)
+    }
+
+    /// Returns how often each wait path ended up handing out a read buffer.
+    #[inline]
+    pub fn read_wait_stats(&self) -> ShmemWaitStats {
+        self.rx.wait_stats()
+    }
+
+    /// Returns how often each wait path ended up handing out a write buffer.
+    #[inline]
+    pub fn write_wait_stats(&self) -> ShmemWaitStats {
+        self.tx.wait_stats()
     }
 }
 
@@ -47,6 +85,39 @@ impl Endpoint for ShmemEndpoint {
     fn write(&mut self) -> Result<Self::WriteBuf<'_>, Self::Error> {
         self.tx.write_buf()
     }
+
+    fn read_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::ReadBuf<'_>>, Self::Error> {
+        self.rx.read_buf_until(Some(Instant::now() + timeout))
+    }
+
+    fn write_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        self.tx.write_buf_until(1, Some(Instant::now() + timeout))
+    }
+
+    fn write_at_least(&mut self, len: usize) -> Result<Self::WriteBuf<'_>, Self::Error> {
+        self.tx
+            .write_buf_until(len, None)
+            .map(|buf| buf.expect("Untimed write never times out"))
+    }
+
+    fn write_at_least_timeout(
+        &mut self,
+        len: usize,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        self.tx.write_buf_until(len, Some(Instant::now() + timeout))
+    }
+
+    #[inline]
+    fn arena(&self) -> Option<Arc<dyn BulkRegion>> {
+        self.arena.clone()
+    }
 }
 
 impl Debug for ShmemEndpoint {
@@ -54,6 +125,7 @@ impl Debug for ShmemEndpoint {
         f.debug_struct("ShmemEndpoint")
             .field("tx", &self.tx)
             .field("rx", &self.rx)
+            .field("arena", &self.arena)
             .finish()
     }
 }
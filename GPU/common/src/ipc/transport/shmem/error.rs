@@ -43,6 +43,10 @@ pub enum ShmemTransportError {
     #[error("Connection closed")]
     ConnectionClosed,
 
+    /// Indicates a process died while holding the buffer lock.
+    #[error("Connection inconsistent, a process died while holding the buffer lock")]
+    ConnectionInconsistent,
+
     /// Indicates connection operation timed out.
     #[error("Connection timeout")]
     ConnectionTimeout,
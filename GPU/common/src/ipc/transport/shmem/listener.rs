@@ -0,0 +1,306 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+use std::{
+    process,
+    sync::{
+        RwLock,
+        atomic::{AtomicU32, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use tracing::{debug, warn};
+
+use crate::{
+    ipc::transport::{Listener, ReadBuf, Transport, WriteBuf},
+    sys::{page, process::ProcessId},
+};
+
+use super::{
+    channel::{ShmemChannel, ShmemChannelState, ShmemSyncMode},
+    endpoint::ShmemEndpoint,
+    error::ShmemTransportError,
+    transport::ShmemTransport,
+};
+
+pub(super) const LISTEN_SUFFIX: &str = "_listen";
+
+/// Length of an encoded `ConnectionRequest`.
+const REQUEST_LEN: usize = 3 * size_of::<u64>() + size_of::<u32>();
+
+/// A connection request posted by a client on the request channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ConnectionRequest {
+    /// Names the channels of the new connection.
+    nonce: u64,
+    /// The client, known as the peer of the channels before it opens them.
+    client: ProcessId,
+}
+
+impl ConnectionRequest {
+    fn to_bytes(self) -> [u8; REQUEST_LEN] {
+        let mut bytes = [0; REQUEST_LEN];
+        bytes[..8].copy_from_slice(&self.nonce.to_ne_bytes());
+        bytes[8..16].copy_from_slice(&self.client.start_time.to_ne_bytes());
+        bytes[16..24].copy_from_slice(&self.client.pid_ns.to_ne_bytes());
+        bytes[24..].copy_from_slice(&self.client.pid.to_ne_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; REQUEST_LEN]) -> Self {
+        Self {
+            nonce: u64::from_ne_bytes(bytes[..8].try_into().unwrap()),
+            client: ProcessId {
+                pid: u32::from_ne_bytes(bytes[24..].try_into().unwrap()),
+                start_time: u64::from_ne_bytes(bytes[8..16].try_into().unwrap()),
+                pid_ns: u64::from_ne_bytes(bytes[16..24].try_into().unwrap()),
+            },
+        }
+    }
+}
+
+/// Returns the address of the connection identified by `nonce`.
+#[inline]
+fn connection_addr(addr: &str, nonce: u64) -> String {
+    format!("{}.{:016x}", addr, nonce)
+}
+
+/// Asks the listener owning `requests` for a new connection, returns its address.
+///
+/// The nonce combines the pid with a per-process sequence number, so that concurrent
+/// clients never ask for the same channels.
+pub(super) fn request_connection(
+    addr: &str,
+    requests: &ShmemChannel,
+    deadline: Instant,
+) -> Result<String, ShmemTransportError> {
+    static SEQ: AtomicU32 = AtomicU32::new(0);
+
+    let request = ConnectionRequest {
+        nonce: (process::id() as u64) << 32 | SEQ.fetch_add(1, Ordering::Relaxed) as u64,
+        client: ProcessId::current(),
+    };
+    post_request(requests, request, deadline)?;
+
+    Ok(connection_addr(addr, request.nonce))
+}
+
+/// Writes `request` to the request channel in one piece.
+fn post_request(
+    requests: &ShmemChannel,
+    request: ConnectionRequest,
+    deadline: Instant,
+) -> Result<(), ShmemTransportError> {
+    const RETRY_DELAY: Duration = Duration::from_millis(1);
+
+    loop {
+        let Some(mut buf) = requests.write_buf_until(1, Some(deadline))? else {
+            return Err(ShmemTransportError::ConnectionTimeout);
+        };
+        // A shorter buffer would split the request, wait for the listener to drain.
+        if buf.len() < REQUEST_LEN {
+            drop(buf);
+            if Instant::now() >= deadline {
+                return Err(ShmemTransportError::ConnectionTimeout);
+            }
+            thread::sleep(RETRY_DELAY);
+            continue;
+        }
+        buf[..REQUEST_LEN].copy_from_slice(&request.to_bytes());
+
+        return buf.submit(REQUEST_LEN);
+    }
+}
+
+/// A `Listener` implementation that hands out a pair of shared memory channels per connection.
+///
+/// Clients post connection requests on a shared request channel, named after the listen
+/// address, and the listener creates the channels of each connection it accepts.
+#[derive(Debug)]
+pub struct ShmemListener {
+    addr: String,
+    transport: ShmemTransport,
+    requests: RwLock<Option<ShmemChannel>>,
+}
+
+impl ShmemListener {
+    pub(crate) fn new(
+        addr: String,
+        transport: ShmemTransport,
+    ) -> Result<Self, ShmemTransportError> {
+        let requests = Self::create_requests(&addr)?;
+
+        debug!("[Shmem] '{}': Listening", addr);
+        Ok(Self {
+            addr,
+            transport,
+            requests: RwLock::new(Some(requests)),
+        })
+    }
+
+    /// Request channel has many writers, so it always synchronizes through its buffer lock.
+    fn create_requests(addr: &str) -> Result<ShmemChannel, ShmemTransportError> {
+        ShmemChannel::create(
+            format!("{}{}", addr, LISTEN_SUFFIX),
+            page::page_size(),
+            ShmemSyncMode::Mpmc,
+        )
+    }
+
+    /// Replaces a request channel left inconsistent by a client dying mid-request.
+    fn recreate_requests(&self) -> Result<(), ShmemTransportError> {
+        let mut requests = self.requests.write().unwrap_or_else(|e| e.into_inner());
+        let inconsistent = requests.as_ref().is_none_or(|channel| {
+            matches!(
+                channel.get_state(),
+                Ok(ShmemChannelState::Inconsistent) | Err(_)
+            )
+        });
+        if inconsistent {
+            warn!("[Shmem] '{}': Recreating request channel", self.addr);
+            // The old segment must be unlinked before a new one can take its name.
+            *requests = None;
+            *requests = Some(Self::create_requests(&self.addr)?);
+        }
+
+        Ok(())
+    }
+
+    /// Takes the next connection request.
+    fn next_request(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<ConnectionRequest>, ShmemTransportError> {
+        loop {
+            let requests = self.requests.read().unwrap_or_else(|e| e.into_inner());
+            let result = match requests.as_ref() {
+                Some(channel) => Self::take_request(channel, deadline),
+                None => Err(ShmemTransportError::ConnectionInconsistent),
+            };
+            drop(requests);
+
+            match result {
+                Err(ShmemTransportError::ConnectionInconsistent) => self.recreate_requests()?,
+                result => return result,
+            }
+        }
+    }
+
+    fn take_request(
+        channel: &ShmemChannel,
+        deadline: Option<Instant>,
+    ) -> Result<Option<ConnectionRequest>, ShmemTransportError> {
+        let Some(buf) = channel.read_buf_until(deadline)? else {
+            return Ok(None);
+        };
+        // Requests are submitted whole under the buffer lock.
+        let Some(bytes) = buf.get(..REQUEST_LEN) else {
+            return Err(ShmemTransportError::InvalidConnectionState);
+        };
+        let request = ConnectionRequest::from_bytes(bytes.try_into().unwrap());
+        buf.consume(REQUEST_LEN)?;
+
+        Ok(Some(request))
+    }
+
+    fn accept_until(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<ShmemEndpoint>, ShmemTransportError> {
+        let Some(request) = self.next_request(deadline)? else {
+            return Ok(None);
+        };
+        let endpoint = self
+            .transport
+            .create(&connection_addr(&self.addr, request.nonce))?;
+        // A client dying before it opens the channels must not hang the server
+        endpoint.expect_peer(request.client);
+
+        debug!("[Shmem] '{}': Accepted {:016x}", self.addr, request.nonce);
+        Ok(Some(endpoint))
+    }
+}
+
+impl Listener for ShmemListener {
+    type Error = ShmemTransportError;
+    type Endpoint = ShmemEndpoint;
+
+    fn accept(&self) -> Result<Self::Endpoint, Self::Error> {
+        self.accept_until(None)
+            .map(|endpoint| endpoint.expect("Untimed accept never times out"))
+    }
+
+    fn accept_timeout(&self, timeout: Duration) -> Result<Option<Self::Endpoint>, Self::Error> {
+        self.accept_until(Some(Instant::now() + timeout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use crate::ipc::transport::Endpoint;
+
+    use super::{super::transport::ShmemTransportBuilder, *};
+
+    fn unique_addr() -> String {
+        static SEQ: AtomicU32 = AtomicU32::new(0);
+
+        format!(
+            "/xgpu_listener_{}_{}",
+            process::id(),
+            SEQ.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    #[test]
+    fn test_request_roundtrip() {
+        let request = ConnectionRequest {
+            nonce: 0x0123_4567_89ab_cdef,
+            client: ProcessId::current(),
+        };
+
+        assert_eq!(ConnectionRequest::from_bytes(&request.to_bytes()), request);
+    }
+
+    #[test]
+    fn test_client_death_before_open() {
+        let addr = unique_addr();
+        let listener = ShmemTransportBuilder::default()
+            .build()
+            .listen(&addr)
+            .unwrap();
+
+        let mut child = Command::new("true").spawn().expect("Failed to spawn child");
+        let client = ProcessId {
+            pid: child.id(),
+            ..ProcessId::current()
+        };
+        child.wait().expect("Failed to wait child");
+
+        // The client asks for a connection, then dies without opening its channels
+        let requests =
+            ShmemChannel::open_shared(format!("{}{}", addr, LISTEN_SUFFIX), Duration::ZERO)
+                .unwrap();
+        let request = ConnectionRequest { nonce: 1, client };
+        post_request(&requests, request, Instant::now() + Duration::from_secs(1)).unwrap();
+
+        let mut endpoint = listener.accept().unwrap();
+        assert!(matches!(
+            endpoint.read(),
+            Err(ShmemTransportError::ConnectionClosed)
+        ));
+    }
+}
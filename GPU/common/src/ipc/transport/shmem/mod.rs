@@ -17,18 +17,32 @@
 //! This module provides a high-performance, inter-process communication mechanism
 //! built on shared memory ring buffers. It uses one channel for each direction of
 //! communication. Synchronization is managed by `parking_lot` primitives placed
-//! within the shared memory segment itself.
+//! within the shared memory segment itself. A connection may also carry a data
+//! arena, a larger segment holding bulk payloads that are passed by offset.
 
 mod error;
 pub use error::*;
 
+mod arena;
+pub use arena::ShmemArena;
+
 mod memory;
 
 mod buffer;
 mod channel;
+pub use channel::ShmemSyncMode;
 
 mod endpoint;
 pub use endpoint::*;
 
+mod listener;
+pub use listener::ShmemListener;
+
+mod segment;
+pub use segment::ShmemSegment;
+
 mod transport;
 pub use transport::*;
+
+mod wait;
+pub use wait::{ShmemWaitStats, ShmemWaitStrategy};
@@ -0,0 +1,276 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    io,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tracing::warn;
+
+use crate::sys::{
+    process::{AtomicProcessId, ProcessId},
+    shmem::Shmem,
+};
+
+/// Marks a shared memory segment as an initialized xgpu channel, "XGPUSHM1".
+const SEGMENT_MAGIC: u64 = u64::from_le_bytes(*b"XGPUSHM1");
+
+/// A header located at the very start of every channel segment, identifying its creator.
+#[repr(C)]
+#[derive(Debug)]
+pub(super) struct ShmemSegmentHeader {
+    /// `SEGMENT_MAGIC` once the header is published.
+    magic: AtomicU64,
+    /// Creation time, in seconds since the Unix epoch.
+    created_at: AtomicU64,
+    /// Process which created the segment.
+    creator: AtomicProcessId,
+}
+
+impl ShmemSegmentHeader {
+    pub const fn new() -> Self {
+        Self {
+            magic: AtomicU64::new(0),
+            created_at: AtomicU64::new(0),
+            creator: AtomicProcessId::new(),
+        }
+    }
+
+    /// Records the calling process as creator, the magic is written last.
+    pub fn publish(&self) {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        self.created_at.store(created_at, Ordering::Relaxed);
+        self.creator.store(ProcessId::current());
+        self.magic.store(SEGMENT_MAGIC, Ordering::Release);
+    }
+
+    #[inline]
+    pub fn creator(&self) -> Option<ProcessId> {
+        self.creator.load()
+    }
+}
+
+/// An existing xgpu shared memory segment, as found in the system.
+#[derive(Debug, Clone)]
+pub struct ShmemSegment {
+    name: String,
+    size: usize,
+    created_at: SystemTime,
+    creator: Option<ProcessId>,
+}
+
+impl ShmemSegment {
+    /// Reads the header of segment `name`, returns `None` if it is not an xgpu segment.
+    pub fn inspect<S: AsRef<str>>(name: S) -> io::Result<Option<Self>> {
+        let shmem = Shmem::open(&name)?;
+        if shmem.size() < size_of::<ShmemSegmentHeader>() {
+            return Ok(None);
+        }
+
+        let mut header = MaybeUninit::<ShmemSegmentHeader>::zeroed();
+        // SAFETY: The header consists of plain integers, any bit pattern is valid.
+        let header = unsafe {
+            let bytes = std::slice::from_raw_parts_mut(
+                header.as_mut_ptr().cast::<u8>(),
+                size_of::<ShmemSegmentHeader>(),
+            );
+            shmem.read_at(bytes, 0)?;
+            header.assume_init()
+        };
+        if header.magic.load(Ordering::Acquire) != SEGMENT_MAGIC {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            name: shmem.name().to_owned(),
+            size: shmem.size(),
+            created_at: UNIX_EPOCH + Duration::from_secs(header.created_at.load(Ordering::Relaxed)),
+            creator: header.creator(),
+        }))
+    }
+
+    /// Lists all xgpu segments, skipping those which cannot be inspected.
+    pub fn list() -> io::Result<Vec<Self>> {
+        let mut segments = Vec::new();
+        for name in Shmem::list()? {
+            match Self::inspect(&name) {
+                Ok(Some(segment)) => segments.push(segment),
+                Ok(None) => {}
+                Err(e) => warn!("[Shmem] Failed to inspect '{}', {}", name, e),
+            }
+        }
+        Ok(segments)
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    #[inline]
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
+
+    #[inline]
+    pub fn creator(&self) -> Option<ProcessId> {
+        self.creator
+    }
+
+    /// Returns `true` if the creator is known to be gone.
+    ///
+    /// A creator in another pid namespace is never considered gone.
+    pub fn is_orphaned(&self) -> bool {
+        self.creator
+            .is_some_and(|creator| creator.is_alive() == Some(false))
+    }
+
+    /// Removes the segment, processes which still map it are unaffected.
+    pub fn remove(&self) -> io::Result<()> {
+        Shmem::unlink(&self.name)
+    }
+
+    /// Removes segment `name` if it exists and is orphaned, returns whether it was removed.
+    pub fn reclaim<S: AsRef<str>>(name: S) -> io::Result<bool> {
+        let segment = match Self::inspect(&name) {
+            Ok(Some(segment)) => segment,
+            Ok(None) => return Ok(false),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        if !segment.is_orphaned() {
+            return Ok(false);
+        }
+
+        warn!(
+            "[Shmem] '{}': Reclaiming segment of dead process {}",
+            segment.name,
+            segment.creator.map_or(0, |creator| creator.pid)
+        );
+        segment.remove()?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        process::{self, Command},
+        sync::atomic::AtomicUsize,
+    };
+
+    use crate::ipc::transport::{
+        Transport,
+        shmem::{ShmemTransportBuilder, ShmemTransportError, channel::ShmemChannel},
+    };
+
+    fn unique_segment_addr() -> String {
+        static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+        format!(
+            "/xgpu_segment_{}_{}",
+            process::id(),
+            SEQ.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    /// Leaves the segments of a channel behind, as if its creator was killed.
+    fn abandon(channel: ShmemChannel) {
+        let mut child = Command::new("true").spawn().expect("Failed to spawn child");
+        channel.header().creator.store(ProcessId {
+            pid: child.id(),
+            ..ProcessId::current()
+        });
+        child.wait().expect("Failed to wait child");
+
+        std::mem::forget(channel);
+    }
+
+    #[test]
+    fn test_inspect() {
+        let name = unique_segment_addr();
+        assert!(ShmemSegment::inspect(&name).is_err());
+
+        let _channel = ShmemChannel::create(&name, 4096, Default::default()).unwrap();
+        let segment = ShmemSegment::inspect(&name)
+            .unwrap()
+            .expect("Not an xgpu segment");
+
+        assert_eq!(segment.name(), name);
+        assert_eq!(segment.creator(), Some(ProcessId::current()));
+        assert!(segment.created_at() <= SystemTime::now());
+        assert!(!segment.is_orphaned());
+        assert!(
+            ShmemSegment::list()
+                .unwrap()
+                .iter()
+                .any(|segment| segment.name() == name)
+        );
+    }
+
+    #[test]
+    fn test_inspect_foreign() {
+        let name = unique_segment_addr();
+        let _shmem = Shmem::create(&name, 4096).unwrap();
+
+        assert!(ShmemSegment::inspect(&name).unwrap().is_none());
+        assert!(!ShmemSegment::reclaim(&name).unwrap());
+    }
+
+    #[test]
+    fn test_reclaim() {
+        let name = unique_segment_addr();
+        let channel = ShmemChannel::create(&name, 4096, Default::default()).unwrap();
+        assert!(!ShmemSegment::reclaim(&name).unwrap());
+
+        abandon(channel);
+        assert!(ShmemSegment::inspect(&name).unwrap().unwrap().is_orphaned());
+
+        assert!(ShmemSegment::reclaim(&name).unwrap());
+        assert!(ShmemSegment::inspect(&name).is_err());
+    }
+
+    #[test]
+    fn test_transport_reclaim_stale() {
+        let addr = unique_segment_addr();
+        let transport = ShmemTransportBuilder::new().build();
+
+        let endpoint = transport.create(&addr).unwrap();
+        let (tx, rx) = endpoint.into_channels();
+        abandon(tx);
+        abandon(rx);
+
+        assert!(matches!(
+            transport.create(&addr),
+            Err(ShmemTransportError::CreationError { .. })
+        ));
+
+        let transport = ShmemTransportBuilder::new().reclaim_stale(true).build();
+        let _endpoint = transport.create(&addr).unwrap();
+        let _peer = transport.connect(&addr).unwrap();
+    }
+}
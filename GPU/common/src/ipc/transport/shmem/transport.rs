@@ -12,45 +12,136 @@
  * See the Mulan PSL v2 for more details.
  */
 
-use std::time::Duration;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
 
 use crate::ipc::transport::Transport;
 
-use super::{channel::ShmemChannel, endpoint::ShmemEndpoint, error::ShmemTransportError};
+use super::{
+    arena::ShmemArena,
+    channel::{ShmemChannel, ShmemSyncMode},
+    endpoint::ShmemEndpoint,
+    error::ShmemTransportError,
+    listener::{self, LISTEN_SUFFIX, ShmemListener},
+    segment::ShmemSegment,
+    wait::ShmemWaitStrategy,
+};
 
 const S2C_SUFFIX: &str = "_s2c";
 const C2S_SUFFIX: &str = "_c2s";
+const ARENA_SUFFIX: &str = "_arena";
 
 #[derive(Debug, Clone, Copy)]
 pub struct ShmemTransport {
     buffer_size: usize,
+    arena_size: usize,
     conn_timeout: Duration,
+    sync_mode: ShmemSyncMode,
+    wait_strategy: ShmemWaitStrategy,
+    reclaim_stale: bool,
+}
+
+impl ShmemTransport {
+    /// Removes segments left behind by a dead creator, if enabled.
+    fn reclaim(&self, names: &[&str]) -> Result<(), ShmemTransportError> {
+        if self.reclaim_stale {
+            for name in names {
+                ShmemSegment::reclaim(name).map_err(|e| ShmemTransportError::CreationError {
+                    name: name.to_string(),
+                    source: e,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Opens both channels of the endpoint created at `addr`.
+    fn open(&self, addr: &str, deadline: Instant) -> Result<ShmemEndpoint, ShmemTransportError> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let mut tx = ShmemChannel::open(format!("{}{}", addr, C2S_SUFFIX), timeout)?;
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let mut rx = ShmemChannel::open(format!("{}{}", addr, S2C_SUFFIX), timeout)?;
+
+        tx.set_wait_strategy(self.wait_strategy);
+        rx.set_wait_strategy(self.wait_strategy);
+
+        // Created before the channels, so it is there once they are
+        let arena = ShmemArena::open(format!("{}{}", addr, ARENA_SUFFIX))?;
+
+        Ok(ShmemEndpoint::new(tx, rx, arena))
+    }
 }
 
 impl Transport for ShmemTransport {
     type Error = ShmemTransportError;
     type Endpoint = ShmemEndpoint;
+    type Listener = ShmemListener;
     type Address = str;
 
     fn create(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
-        let tx = ShmemChannel::create(format!("{}{}", addr, S2C_SUFFIX), self.buffer_size)?;
-        let rx = ShmemChannel::create(format!("{}{}", addr, C2S_SUFFIX), self.buffer_size)?;
+        let tx_name = format!("{}{}", addr, S2C_SUFFIX);
+        let rx_name = format!("{}{}", addr, C2S_SUFFIX);
+        let arena_name = format!("{}{}", addr, ARENA_SUFFIX);
 
-        Ok(ShmemEndpoint::new(tx, rx))
+        self.reclaim(&[&arena_name, &tx_name, &rx_name])?;
+
+        let arena = match self.arena_size {
+            0 => None,
+            size => Some(ShmemArena::create(arena_name, size)?),
+        };
+        let mut tx = ShmemChannel::create(tx_name, self.buffer_size, self.sync_mode)?;
+        let mut rx = ShmemChannel::create(rx_name, self.buffer_size, self.sync_mode)?;
+
+        tx.set_wait_strategy(self.wait_strategy);
+        rx.set_wait_strategy(self.wait_strategy);
+
+        Ok(ShmemEndpoint::new(tx, rx, arena))
     }
 
-    fn connect(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
-        let tx = ShmemChannel::open(format!("{}{}", addr, C2S_SUFFIX), self.conn_timeout)?;
-        let rx = ShmemChannel::open(format!("{}{}", addr, S2C_SUFFIX), self.conn_timeout)?;
+    fn listen(&self, addr: &Self::Address) -> Result<Self::Listener, Self::Error> {
+        self.reclaim(&[&format!("{}{}", addr, LISTEN_SUFFIX)])?;
 
-        Ok(ShmemEndpoint::new(tx, rx))
+        ShmemListener::new(addr.to_owned(), *self)
+    }
+
+    /// Connects to either a listener or a single created endpoint, whichever shows up first.
+    fn connect(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
+        const RETRY_DELAY: Duration = Duration::from_millis(10);
+
+        let deadline = Instant::now() + self.conn_timeout;
+        loop {
+            match ShmemChannel::open_shared(format!("{}{}", addr, LISTEN_SUFFIX), Duration::ZERO) {
+                Ok(requests) => {
+                    let conn_addr = listener::request_connection(addr, &requests, deadline)?;
+                    return self.open(&conn_addr, deadline);
+                }
+                Err(ShmemTransportError::ConnectionTimeout) => {}
+                Err(e) => return Err(e),
+            }
+            match self.open(addr, Instant::now()) {
+                Ok(endpoint) => return Ok(endpoint),
+                Err(ShmemTransportError::ConnectionTimeout) => {}
+                Err(e) => return Err(e),
+            }
+
+            if Instant::now() >= deadline {
+                return Err(ShmemTransportError::ConnectionTimeout);
+            }
+            thread::sleep(RETRY_DELAY);
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ShmemTransportBuilder {
     buffer_size: usize,
+    arena_size: usize,
     conn_timeout: Duration,
+    sync_mode: ShmemSyncMode,
+    wait_strategy: ShmemWaitStrategy,
+    reclaim_stale: bool,
 }
 
 impl ShmemTransportBuilder {
@@ -60,7 +151,11 @@ impl ShmemTransportBuilder {
 
         Self {
             buffer_size: DEFAULT_BUFF_SIZE,
+            arena_size: 0,
             conn_timeout: DEFAULT_CONN_TIMEOUT,
+            sync_mode: ShmemSyncMode::default(),
+            wait_strategy: ShmemWaitStrategy::default(),
+            reclaim_stale: false,
         }
     }
 
@@ -70,17 +165,51 @@ impl ShmemTransportBuilder {
         self
     }
 
+    /// Sets the size of the bulk data arena created for each connection, zero creates
+    /// none. A connecting side maps the arena of the endpoint it opens.
+    #[inline]
+    pub fn arena_size(mut self, value: usize) -> Self {
+        self.arena_size = value;
+        self
+    }
+
     #[inline]
     pub fn connect_timeout(mut self, value: Duration) -> Self {
         self.conn_timeout = value;
         self
     }
 
+    /// Sets how the created channels are synchronized, a connecting side adopts the
+    /// mode of the channels it opens.
+    #[inline]
+    pub fn sync_mode(mut self, value: ShmemSyncMode) -> Self {
+        self.sync_mode = value;
+        self
+    }
+
+    /// Sets how a blocked reader or writer waits for its peer.
+    #[inline]
+    pub fn wait_strategy(mut self, value: ShmemWaitStrategy) -> Self {
+        self.wait_strategy = value;
+        self
+    }
+
+    /// Removes segments left behind at the address by a dead creator before creating.
+    #[inline]
+    pub fn reclaim_stale(mut self, value: bool) -> Self {
+        self.reclaim_stale = value;
+        self
+    }
+
     #[inline]
     pub fn build(self) -> ShmemTransport {
         ShmemTransport {
             buffer_size: self.buffer_size,
+            arena_size: self.arena_size,
             conn_timeout: self.conn_timeout,
+            sync_mode: self.sync_mode,
+            wait_strategy: self.wait_strategy,
+            reclaim_stale: self.reclaim_stale,
         }
     }
 }
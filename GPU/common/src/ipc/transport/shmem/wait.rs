@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use linux_futex::{Futex, Shared, TimedWaitError};
+use nix::{
+    sched::{self, CpuSet},
+    unistd::Pid,
+};
+use tracing::warn;
+
+/// How a blocked reader or writer waits for its peer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ShmemWaitStrategy {
+    /// Sleep on the futex right away.
+    #[default]
+    Futex,
+    /// Busy-poll for up to the given budget, then sleep on the futex.
+    SpinThenFutex(Duration),
+    /// Busy-poll without ever sleeping, pinning the waiting thread to a core if given.
+    BusyPoll(Option<usize>),
+}
+
+/// How often each wait path ended up handing out a buffer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShmemWaitStats {
+    /// The buffer was ready without waiting.
+    pub immediate: u64,
+    /// The buffer became ready while busy-polling.
+    pub spin: u64,
+    /// The buffer became ready after sleeping on the futex.
+    pub futex: u64,
+}
+
+/// The path which ended a wait.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ShmemWaitPath {
+    Spin,
+    Futex,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct ShmemWaitCounters {
+    immediate: AtomicU64,
+    spin: AtomicU64,
+    futex: AtomicU64,
+}
+
+impl ShmemWaitCounters {
+    /// Records a handed out buffer, `path` is the last wait before it became ready.
+    #[inline]
+    pub fn record(&self, path: Option<ShmemWaitPath>) {
+        let counter = match path {
+            None => &self.immediate,
+            Some(ShmemWaitPath::Spin) => &self.spin,
+            Some(ShmemWaitPath::Futex) => &self.futex,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> ShmemWaitStats {
+        ShmemWaitStats {
+            immediate: self.immediate.load(Ordering::Relaxed),
+            spin: self.spin.load(Ordering::Relaxed),
+            futex: self.futex.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl ShmemWaitStrategy {
+    /// Waits until `event` no longer holds `last_value`, or `timeout` has passed.
+    ///
+    /// Returns the path which ended the wait, and whether it ended by timing out.
+    pub(super) fn wait(
+        &self,
+        event: &Futex<Shared>,
+        last_value: u32,
+        timeout: Duration,
+    ) -> (ShmemWaitPath, bool) {
+        let spin_budget = match *self {
+            Self::Futex => Duration::ZERO,
+            Self::SpinThenFutex(budget) => budget.min(timeout),
+            Self::BusyPoll(core) => {
+                if let Some(core) = core {
+                    pin_current_thread(core);
+                }
+                timeout
+            }
+        };
+
+        let start_time = Instant::now();
+        if !spin_budget.is_zero() {
+            loop {
+                if event.value.load(Ordering::Acquire) != last_value {
+                    return (ShmemWaitPath::Spin, false);
+                }
+                if start_time.elapsed() >= spin_budget {
+                    break;
+                }
+                std::hint::spin_loop();
+            }
+            if matches!(self, Self::BusyPoll(_)) {
+                return (ShmemWaitPath::Spin, true);
+            }
+        }
+
+        let timeout = timeout.saturating_sub(start_time.elapsed());
+        let timed_out = matches!(
+            event.wait_for(last_value, timeout),
+            Err(TimedWaitError::TimedOut)
+        );
+        (ShmemWaitPath::Futex, timed_out)
+    }
+}
+
+/// Pins the calling thread to `core`, once per thread.
+fn pin_current_thread(core: usize) {
+    thread_local! {
+        static PINNED_CORE: Cell<Option<usize>> = const { Cell::new(None) };
+    }
+
+    if PINNED_CORE.get() == Some(core) {
+        return;
+    }
+    // Never retry, a failed attempt would otherwise cost a syscall per wait
+    PINNED_CORE.set(Some(core));
+
+    let mut cpu_set = CpuSet::new();
+    let result = cpu_set
+        .set(core)
+        .and_then(|_| sched::sched_setaffinity(Pid::from_raw(0), &cpu_set));
+    if let Err(e) = result {
+        warn!("[Shmem] Failed to pin thread to core {}, {}", core, e);
+    }
+}
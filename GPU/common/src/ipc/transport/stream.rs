@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+//! Buffering shared by the stream socket transports.
+//!
+//! Every frame is sent from, and received into, the start of a page-aligned
+//! buffer, so the bytewise encoded payload sees identical address alignment
+//! on both ends of the connection.
+
+use std::{
+    fmt::Debug,
+    io,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    os::fd::{AsRawFd, BorrowedFd},
+    time::Instant,
+};
+
+use nix::{
+    errno::Errno,
+    poll::{self, PollFd, PollFlags, PollTimeout},
+    sys::socket::{self, MsgFlags},
+};
+use tracing::debug;
+
+use crate::{
+    ipc::{
+        bytewise::AlignedBuffer,
+        transport::{ReadBuf, WriteBuf},
+    },
+    sys::page,
+};
+
+/// Stream buffer errors, converted into the owning transport's error type.
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    ConnectionClosed,
+    ReadOverflow { attempted: usize, capacity: usize },
+    WriteOverflow { attempted: usize, capacity: usize },
+}
+
+impl From<Errno> for StreamError {
+    fn from(value: Errno) -> Self {
+        match value {
+            Errno::EPIPE | Errno::ECONNRESET => Self::ConnectionClosed,
+            errno => Self::Io(errno.into()),
+        }
+    }
+}
+
+/// Waits until `fd` is ready for `events`, or `deadline` passes.
+///
+/// Returns `Ok(false)` on timeout.
+pub fn poll_until(
+    fd: BorrowedFd<'_>,
+    events: PollFlags,
+    deadline: Option<Instant>,
+) -> Result<bool, StreamError> {
+    loop {
+        let timeout = match deadline {
+            Some(deadline) => {
+                // Round up, so we never wake just before the deadline and spin
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                let millis = remaining.as_micros().div_ceil(1000);
+                PollTimeout::try_from(millis).unwrap_or(PollTimeout::MAX)
+            }
+            None => PollTimeout::NONE,
+        };
+
+        let mut fds = [PollFd::new(fd, events)];
+        match poll::poll(&mut fds, timeout) {
+            Ok(0) => return Ok(false),
+            Ok(_) => return Ok(true),
+            Err(Errno::EINTR) => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Receive side stream buffer.
+///
+/// Unread data always starts at the aligned buffer start when handed out.
+/// Consumed bytes are only discarded on the next read, because decoded
+/// messages may still borrow from them.
+#[derive(Debug)]
+pub struct StreamRxBuffer {
+    buf: AlignedBuffer,
+    start: usize,
+    end: usize,
+    starved: bool,
+}
+
+impl StreamRxBuffer {
+    pub fn new(size: usize) -> Self {
+        Self {
+            buf: AlignedBuffer::new(size, page::page_size()),
+            start: 0,
+            end: 0,
+            starved: true,
+        }
+    }
+
+    /// Moves unread data to the buffer start.
+    pub fn compact(&mut self) {
+        if self.start == 0 {
+            return;
+        }
+
+        self.buf.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+        self.starved = self.end == 0;
+    }
+
+    /// Returns `true` if buffered data cannot satisfy the reader.
+    #[inline]
+    pub fn is_starved(&self) -> bool {
+        self.starved
+    }
+
+    /// Receives more data from the socket, blocking until some arrives.
+    pub fn fill(&mut self, fd: BorrowedFd<'_>) -> Result<usize, StreamError> {
+        self.compact();
+
+        if self.end == self.buf.len() {
+            let new_len = self.buf.len().saturating_mul(2).max(page::page_size());
+            self.buf.resize(new_len);
+        }
+
+        loop {
+            match socket::recv(fd.as_raw_fd(), &mut self.buf[self.end..], MsgFlags::empty()) {
+                Ok(0) => return Err(StreamError::ConnectionClosed),
+                Ok(bytes) => {
+                    self.end += bytes;
+                    self.starved = false;
+                    return Ok(bytes);
+                }
+                Err(Errno::EINTR) => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StreamReadBuffer<'a, E> {
+    rx: &'a mut StreamRxBuffer,
+    tag: &'static str,
+    name: &'a str,
+    consumed: usize,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<'a, E> StreamReadBuffer<'a, E> {
+    /// Creates a new read buffer.
+    #[inline]
+    pub fn new(rx: &'a mut StreamRxBuffer, tag: &'static str, name: &'a str) -> Self {
+        Self {
+            rx,
+            tag,
+            name,
+            consumed: 0,
+            _error: PhantomData,
+        }
+    }
+}
+
+impl<E> ReadBuf for StreamReadBuffer<'_, E>
+where
+    E: std::error::Error + From<StreamError> + Send + Sync + 'static,
+{
+    type Error = E;
+
+    fn consume(mut self, bytes: usize) -> Result<(), Self::Error> {
+        if bytes > self.len() {
+            return Err(StreamError::ReadOverflow {
+                attempted: bytes,
+                capacity: self.len(),
+            }
+            .into());
+        }
+
+        self.consumed = bytes;
+        Ok(())
+    }
+}
+
+impl<E> Deref for StreamReadBuffer<'_, E> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.rx.buf[self.rx.start..self.rx.end]
+    }
+}
+
+impl<E> DerefMut for StreamReadBuffer<'_, E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rx.buf[self.rx.start..self.rx.end]
+    }
+}
+
+impl<E> Drop for StreamReadBuffer<'_, E> {
+    fn drop(&mut self) {
+        debug!(
+            "[{}] '{}': Read {} bytes",
+            self.tag, self.name, self.consumed
+        );
+
+        if self.consumed > 0 {
+            self.rx.start += self.consumed;
+            self.rx.starved = self.rx.start == self.rx.end;
+        } else {
+            // Nothing could be decoded, wait for more data on next read.
+            self.rx.starved = true;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StreamWriteBuffer<'a, E> {
+    fd: BorrowedFd<'a>,
+    buf: &'a mut AlignedBuffer,
+    tag: &'static str,
+    name: &'a str,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<'a, E> StreamWriteBuffer<'a, E> {
+    /// Creates a new write buffer.
+    #[inline]
+    pub fn new(
+        fd: BorrowedFd<'a>,
+        buf: &'a mut AlignedBuffer,
+        tag: &'static str,
+        name: &'a str,
+    ) -> Self {
+        Self {
+            fd,
+            buf,
+            tag,
+            name,
+            _error: PhantomData,
+        }
+    }
+}
+
+impl<E> WriteBuf for StreamWriteBuffer<'_, E>
+where
+    E: std::error::Error + From<StreamError> + Send + Sync + 'static,
+{
+    type Error = E;
+
+    fn submit(self, bytes: usize) -> Result<(), Self::Error> {
+        if bytes > self.buf.len() {
+            return Err(StreamError::WriteOverflow {
+                attempted: bytes,
+                capacity: self.buf.len(),
+            }
+            .into());
+        }
+
+        let mut sent = 0;
+        while sent < bytes {
+            // `MSG_NOSIGNAL` keeps a vanished peer from raising `SIGPIPE` in the host process
+            match socket::send(
+                self.fd.as_raw_fd(),
+                &self.buf[sent..bytes],
+                MsgFlags::MSG_NOSIGNAL,
+            ) {
+                Ok(len) => sent += len,
+                Err(Errno::EINTR) => continue,
+                Err(e) => return Err(StreamError::from(e).into()),
+            }
+        }
+
+        debug!("[{}] '{}': Wrote {} bytes", self.tag, self.name, bytes);
+        Ok(())
+    }
+}
+
+impl<E> Deref for StreamWriteBuffer<'_, E> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.buf
+    }
+}
+
+impl<E> DerefMut for StreamWriteBuffer<'_, E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.buf
+    }
+}
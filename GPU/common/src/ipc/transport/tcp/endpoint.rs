@@ -0,0 +1,253 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    fmt::Debug,
+    io,
+    net::{SocketAddr, TcpListener, TcpStream},
+    os::fd::AsFd,
+    time::{Duration, Instant},
+};
+
+use nix::{
+    poll::PollFlags,
+    sys::socket::{self, sockopt},
+};
+use tracing::debug;
+
+use crate::{
+    ipc::{
+        bytewise::AlignedBuffer,
+        transport::{
+            Endpoint,
+            stream::{self, StreamReadBuffer, StreamRxBuffer, StreamWriteBuffer},
+        },
+    },
+    sys::page,
+};
+
+use super::error::TcpTransportError;
+
+const LOG_TAG: &str = "Tcp";
+
+/// Socket options applied to every established connection.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TcpOptions {
+    pub nodelay: bool,
+    pub keepalive: Option<Duration>,
+}
+
+impl TcpOptions {
+    pub fn apply(&self, stream: &TcpStream) -> io::Result<()> {
+        stream.set_nodelay(self.nodelay)?;
+
+        if let Some(interval) = self.keepalive {
+            let secs = interval.as_secs().clamp(1, i32::MAX as u64) as u32;
+
+            socket::setsockopt(stream, sockopt::KeepAlive, &true)?;
+            socket::setsockopt(stream, sockopt::TcpKeepIdle, &secs)?;
+            socket::setsockopt(stream, sockopt::TcpKeepInterval, &secs)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum TcpSocket {
+    /// Bound socket waiting for its peer, accepted on first use.
+    Listening(TcpListener),
+    /// Established connection.
+    Connected(TcpStream),
+}
+
+impl TcpSocket {
+    fn stream(
+        &mut self,
+        addr: &str,
+        options: &TcpOptions,
+        deadline: Option<Instant>,
+    ) -> Result<Option<&TcpStream>, TcpTransportError> {
+        if let Self::Listening(listener) = self {
+            if !stream::poll_until(listener.as_fd(), PollFlags::POLLIN, deadline)? {
+                return Ok(None);
+            }
+            let (stream, peer_addr) =
+                listener
+                    .accept()
+                    .map_err(|e| TcpTransportError::AcceptError {
+                        addr: addr.to_owned(),
+                        source: e,
+                    })?;
+            options.apply(&stream)?;
+
+            debug!("[Tcp] '{}': Accepted {}", addr, peer_addr);
+            *self = Self::Connected(stream);
+        }
+
+        match self {
+            Self::Connected(stream) => Ok(Some(stream)),
+            Self::Listening(_) => unreachable!(),
+        }
+    }
+}
+
+/// A `Transport` implementation that uses a tcp stream socket for bidirectional communication.
+pub struct TcpEndpoint {
+    addr: String,
+    options: TcpOptions,
+    socket: TcpSocket,
+    rx: StreamRxBuffer,
+    tx: AlignedBuffer,
+}
+
+impl TcpEndpoint {
+    #[inline]
+    pub(crate) fn listening(
+        addr: String,
+        listener: TcpListener,
+        options: TcpOptions,
+        buffer_size: usize,
+    ) -> Self {
+        Self::new(addr, options, TcpSocket::Listening(listener), buffer_size)
+    }
+
+    #[inline]
+    pub(crate) fn connected(
+        addr: String,
+        stream: TcpStream,
+        options: TcpOptions,
+        buffer_size: usize,
+    ) -> Self {
+        Self::new(addr, options, TcpSocket::Connected(stream), buffer_size)
+    }
+
+    fn new(addr: String, options: TcpOptions, socket: TcpSocket, buffer_size: usize) -> Self {
+        Self {
+            addr,
+            options,
+            socket,
+            rx: StreamRxBuffer::new(buffer_size),
+            tx: AlignedBuffer::new(buffer_size, page::page_size()),
+        }
+    }
+
+    /// Returns the local socket address, e.g. the actual port when bound to port 0.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        match &self.socket {
+            TcpSocket::Listening(listener) => listener.local_addr(),
+            TcpSocket::Connected(stream) => stream.local_addr(),
+        }
+    }
+}
+
+impl TcpEndpoint {
+    fn read_until(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<StreamReadBuffer<'_, TcpTransportError>>, TcpTransportError> {
+        let Some(stream) = self.socket.stream(&self.addr, &self.options, deadline)? else {
+            return Ok(None);
+        };
+
+        self.rx.compact();
+        if self.rx.is_starved() {
+            if !stream::poll_until(stream.as_fd(), PollFlags::POLLIN, deadline)? {
+                return Ok(None);
+            }
+            self.rx.fill(stream.as_fd())?;
+        }
+
+        Ok(Some(StreamReadBuffer::new(
+            &mut self.rx,
+            LOG_TAG,
+            &self.addr,
+        )))
+    }
+
+    /// The returned buffer is ready once the socket is writable, sending a
+    /// frame larger than the socket buffer may still block on submit.
+    fn write_until(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<StreamWriteBuffer<'_, TcpTransportError>>, TcpTransportError> {
+        let Some(stream) = self.socket.stream(&self.addr, &self.options, deadline)? else {
+            return Ok(None);
+        };
+
+        if !stream::poll_until(stream.as_fd(), PollFlags::POLLOUT, deadline)? {
+            return Ok(None);
+        }
+
+        Ok(Some(StreamWriteBuffer::new(
+            stream.as_fd(),
+            &mut self.tx,
+            LOG_TAG,
+            &self.addr,
+        )))
+    }
+}
+
+impl Endpoint for TcpEndpoint {
+    type Error = TcpTransportError;
+    type ReadBuf<'a> = StreamReadBuffer<'a, TcpTransportError>;
+    type WriteBuf<'a> = StreamWriteBuffer<'a, TcpTransportError>;
+
+    fn read(&mut self) -> Result<Self::ReadBuf<'_>, Self::Error> {
+        self.read_until(None)
+            .map(|buf| buf.expect("Untimed read never times out"))
+    }
+
+    fn write(&mut self) -> Result<Self::WriteBuf<'_>, Self::Error> {
+        self.write_until(None)
+            .map(|buf| buf.expect("Untimed write never times out"))
+    }
+
+    fn read_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::ReadBuf<'_>>, Self::Error> {
+        self.read_until(Some(Instant::now() + timeout))
+    }
+
+    fn write_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        self.write_until(Some(Instant::now() + timeout))
+    }
+
+    /// The write buffer always spans the whole staging buffer.
+    fn write_at_least(&mut self, _len: usize) -> Result<Self::WriteBuf<'_>, Self::Error> {
+        self.write()
+    }
+
+    fn write_at_least_timeout(
+        &mut self,
+        _len: usize,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        self.write_timeout(timeout)
+    }
+}
+
+impl Debug for TcpEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TcpEndpoint")
+            .field("addr", &self.addr)
+            .field("options", &self.options)
+            .field("socket", &self.socket)
+            .finish()
+    }
+}
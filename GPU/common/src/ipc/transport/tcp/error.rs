@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use thiserror::Error;
+
+use crate::ipc::transport::stream::StreamError;
+
+/// TCP transport error types.
+#[derive(Error, Debug)]
+pub enum TcpTransportError {
+    #[error("Failed to resolve address '{addr}', {source}")]
+    ResolveError {
+        addr: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to bind tcp socket '{addr}', {source}")]
+    BindError {
+        addr: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to connect tcp socket '{addr}', {source}")]
+    ConnectError {
+        addr: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to accept connection on tcp socket '{addr}', {source}")]
+    AcceptError {
+        addr: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Indicates an I/O failure on an established connection.
+    #[error("Tcp socket I/O error, {0}")]
+    IoError(#[from] std::io::Error),
+
+    /// Indicates connection was closed.
+    #[error("Connection closed")]
+    ConnectionClosed,
+
+    /// Indicates connection operation timed out.
+    #[error("Connection timeout")]
+    ConnectionTimeout,
+
+    /// Attempted to read beyond buffer capacity.
+    #[error("Read buffer overflow (attempted: {attempted}, capacity: {capacity})")]
+    ReadOverflow { attempted: usize, capacity: usize },
+
+    /// Attempted to write beyond buffer capacity.
+    #[error("Write buffer overflow (attempted: {attempted}, capacity: {capacity})")]
+    WriteOverflow { attempted: usize, capacity: usize },
+}
+
+impl From<StreamError> for TcpTransportError {
+    fn from(value: StreamError) -> Self {
+        match value {
+            StreamError::Io(e) => Self::IoError(e),
+            StreamError::ConnectionClosed => Self::ConnectionClosed,
+            StreamError::ReadOverflow {
+                attempted,
+                capacity,
+            } => Self::ReadOverflow {
+                attempted,
+                capacity,
+            },
+            StreamError::WriteOverflow {
+                attempted,
+                capacity,
+            } => Self::WriteOverflow {
+                attempted,
+                capacity,
+            },
+        }
+    }
+}
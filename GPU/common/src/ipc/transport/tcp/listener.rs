@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+use std::{
+    io,
+    net::{self, SocketAddr},
+    os::fd::AsFd,
+    time::{Duration, Instant},
+};
+
+use nix::poll::PollFlags;
+use tracing::debug;
+
+use crate::ipc::transport::{Listener, stream};
+
+use super::{
+    endpoint::{TcpEndpoint, TcpOptions},
+    error::TcpTransportError,
+};
+
+/// A `Listener` implementation that accepts tcp connections on one address.
+#[derive(Debug)]
+pub struct TcpListener {
+    addr: String,
+    listener: net::TcpListener,
+    options: TcpOptions,
+    buffer_size: usize,
+}
+
+impl TcpListener {
+    pub(crate) fn new(
+        addr: String,
+        listener: net::TcpListener,
+        options: TcpOptions,
+        buffer_size: usize,
+    ) -> Result<Self, TcpTransportError> {
+        // Non-blocking, so that concurrent accepts never block past their deadline.
+        listener.set_nonblocking(true)?;
+
+        Ok(Self {
+            addr,
+            listener,
+            options,
+            buffer_size,
+        })
+    }
+
+    /// Returns the local socket address, e.g. the actual port when bound to port 0.
+    #[inline]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    fn accept_until(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<TcpEndpoint>, TcpTransportError> {
+        loop {
+            if !stream::poll_until(self.listener.as_fd(), PollFlags::POLLIN, deadline)? {
+                return Ok(None);
+            }
+            // Another thread may have taken the connection between poll and accept.
+            let (stream, peer_addr) = match self.listener.accept() {
+                Ok(accepted) => accepted,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(e) => {
+                    return Err(TcpTransportError::AcceptError {
+                        addr: self.addr.clone(),
+                        source: e,
+                    });
+                }
+            };
+            stream.set_nonblocking(false)?;
+            self.options.apply(&stream)?;
+
+            debug!("[Tcp] '{}': Accepted {}", self.addr, peer_addr);
+            return Ok(Some(TcpEndpoint::connected(
+                self.addr.clone(),
+                stream,
+                self.options,
+                self.buffer_size,
+            )));
+        }
+    }
+}
+
+impl Listener for TcpListener {
+    type Error = TcpTransportError;
+    type Endpoint = TcpEndpoint;
+
+    fn accept(&self) -> Result<Self::Endpoint, Self::Error> {
+        self.accept_until(None)
+            .map(|endpoint| endpoint.expect("Untimed accept never times out"))
+    }
+
+    fn accept_timeout(&self, timeout: Duration) -> Result<Option<Self::Endpoint>, Self::Error> {
+        self.accept_until(Some(Instant::now() + timeout))
+    }
+}
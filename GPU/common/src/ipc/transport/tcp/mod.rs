@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+//! A TCP transport layer implementation.
+//!
+//! This module lets the proxy reach an `xgpu-server` running on another node.
+//! Connections have Nagle's algorithm disabled, since IPC traffic is dominated
+//! by small request/response round trips, and use TCP keepalive so a silently
+//! vanished peer is eventually detected.
+
+mod error;
+pub use error::*;
+
+mod endpoint;
+pub use endpoint::*;
+
+mod listener;
+pub use listener::*;
+
+mod transport;
+pub use transport::*;
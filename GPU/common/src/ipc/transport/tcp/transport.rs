@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    io,
+    net::{self, SocketAddr, TcpStream, ToSocketAddrs},
+    thread,
+    time::{Duration, Instant},
+};
+
+use tracing::debug;
+
+use crate::ipc::transport::Transport;
+
+use super::{
+    endpoint::{TcpEndpoint, TcpOptions},
+    error::TcpTransportError,
+    listener::TcpListener,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct TcpTransport {
+    buffer_size: usize,
+    conn_timeout: Duration,
+    options: TcpOptions,
+}
+
+impl TcpTransport {
+    fn resolve(addr: &str) -> Result<Vec<SocketAddr>, TcpTransportError> {
+        addr.to_socket_addrs()
+            .map(Iterator::collect)
+            .map_err(|e| TcpTransportError::ResolveError {
+                addr: addr.to_owned(),
+                source: e,
+            })
+    }
+
+    fn bind(
+        addr: &str,
+        socket_addrs: &[SocketAddr],
+    ) -> Result<net::TcpListener, TcpTransportError> {
+        net::TcpListener::bind(socket_addrs).map_err(|e| TcpTransportError::BindError {
+            addr: addr.to_owned(),
+            source: e,
+        })
+    }
+}
+
+impl Transport for TcpTransport {
+    type Error = TcpTransportError;
+    type Endpoint = TcpEndpoint;
+    type Listener = TcpListener;
+    type Address = str;
+
+    fn create(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
+        let socket_addrs = Self::resolve(addr)?;
+        let listener = Self::bind(addr, &socket_addrs)?;
+
+        debug!("[Tcp] '{}': Listening", addr);
+        Ok(TcpEndpoint::listening(
+            addr.to_owned(),
+            listener,
+            self.options,
+            self.buffer_size,
+        ))
+    }
+
+    fn listen(&self, addr: &Self::Address) -> Result<Self::Listener, Self::Error> {
+        let socket_addrs = Self::resolve(addr)?;
+        let listener = Self::bind(addr, &socket_addrs)?;
+
+        debug!("[Tcp] '{}': Listening", addr);
+        TcpListener::new(addr.to_owned(), listener, self.options, self.buffer_size)
+    }
+
+    fn connect(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
+        const RETRY_DELAY: Duration = Duration::from_millis(10);
+
+        let socket_addrs = Self::resolve(addr)?;
+        let start_time = Instant::now();
+        let stream = 'retry: loop {
+            for socket_addr in &socket_addrs {
+                let remaining = self.conn_timeout.saturating_sub(start_time.elapsed());
+                if remaining.is_zero() {
+                    return Err(TcpTransportError::ConnectionTimeout);
+                }
+                match TcpStream::connect_timeout(socket_addr, remaining) {
+                    Ok(stream) => break 'retry stream,
+                    Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => continue,
+                    Err(e) if e.kind() == io::ErrorKind::TimedOut => {
+                        return Err(TcpTransportError::ConnectionTimeout);
+                    }
+                    Err(e) => {
+                        return Err(TcpTransportError::ConnectError {
+                            addr: addr.to_owned(),
+                            source: e,
+                        });
+                    }
+                }
+            }
+            thread::sleep(RETRY_DELAY);
+        };
+        self.options.apply(&stream)?;
+
+        debug!("[Tcp] '{}': Connected", addr);
+        Ok(TcpEndpoint::connected(
+            addr.to_owned(),
+            stream,
+            self.options,
+            self.buffer_size,
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TcpTransportBuilder {
+    buffer_size: usize,
+    conn_timeout: Duration,
+    nodelay: bool,
+    keepalive: Option<Duration>,
+}
+
+impl TcpTransportBuilder {
+    pub fn new() -> Self {
+        const DEFAULT_BUFF_SIZE: usize = 4096;
+        const DEFAULT_CONN_TIMEOUT: Duration = Duration::from_secs(3);
+        const DEFAULT_KEEPALIVE: Duration = Duration::from_secs(10);
+
+        Self {
+            buffer_size: DEFAULT_BUFF_SIZE,
+            conn_timeout: DEFAULT_CONN_TIMEOUT,
+            nodelay: true,
+            keepalive: Some(DEFAULT_KEEPALIVE),
+        }
+    }
+
+    #[inline]
+    pub fn buffer_size(mut self, value: usize) -> Self {
+        self.buffer_size = value;
+        self
+    }
+
+    #[inline]
+    pub fn connect_timeout(mut self, value: Duration) -> Self {
+        self.conn_timeout = value;
+        self
+    }
+
+    /// Sets `TCP_NODELAY`, enabled by default.
+    #[inline]
+    pub fn nodelay(mut self, value: bool) -> Self {
+        self.nodelay = value;
+        self
+    }
+
+    /// Sets the keepalive idle time and probe interval, `None` disables keepalive.
+    #[inline]
+    pub fn keepalive(mut self, value: Option<Duration>) -> Self {
+        self.keepalive = value;
+        self
+    }
+
+    #[inline]
+    pub fn build(self) -> TcpTransport {
+        TcpTransport {
+            buffer_size: self.buffer_size,
+            conn_timeout: self.conn_timeout,
+            options: TcpOptions {
+                nodelay: self.nodelay,
+                keepalive: self.keepalive,
+            },
+        }
+    }
+}
+
+impl Default for TcpTransportBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
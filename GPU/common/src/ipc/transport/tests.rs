@@ -139,7 +139,11 @@ mod helper {
 }
 
 mod test_suits {
-    use std::{sync::Arc, thread, time::Duration};
+    use std::{
+        sync::Arc,
+        thread,
+        time::{Duration, Instant},
+    };
 
     use tracing::debug;
 
@@ -235,6 +239,101 @@ mod test_suits {
 
         Ok(())
     }
+
+    pub fn read_write_timeout<T: Transport + 'static>(
+        transport: T,
+        address: &T::Address,
+    ) -> Result<(), T::Error> {
+        const PING: &[u8] = b"Ping";
+        const TIMEOUT: Duration = Duration::from_millis(50);
+
+        // Initialize logger
+        helper::init_test_logger();
+
+        // Create server & client
+        let (mut server, mut client) = helper::create_connection(transport, address)?;
+
+        // Nothing to read yet
+        assert!(server.try_read()?.is_none(), "Unexpected readable data");
+
+        let start_time = Instant::now();
+        assert!(
+            server.read_timeout(TIMEOUT)?.is_none(),
+            "Unexpected readable data"
+        );
+        assert!(
+            start_time.elapsed() >= TIMEOUT,
+            "Read returned before timeout"
+        );
+
+        // Client send PING
+        let mut write_buf = client.try_write()?.expect("Endpoint should be writable");
+        write_buf[..PING.len()].copy_from_slice(PING);
+        write_buf.submit(PING.len())?;
+
+        // Server receive PING
+        let read_buf = server
+            .read_timeout(TIMEOUT)?
+            .expect("Endpoint should be readable");
+        assert_eq!(&read_buf[..PING.len()], PING, "Received message mismatch");
+        read_buf.consume(PING.len())?;
+
+        Ok(())
+    }
+
+    pub fn accept_multiple_clients<T: Transport + 'static>(
+        transport: T,
+        address: &T::Address,
+    ) -> Result<(), T::Error> {
+        const CLIENTS: usize = 4;
+
+        // Initialize logger
+        helper::init_test_logger();
+
+        // Bind listener, nobody connected yet
+        let listener = transport.listen(address)?;
+        assert!(
+            listener.accept_timeout(Duration::ZERO)?.is_none(),
+            "Unexpected pending connection"
+        );
+
+        thread::scope(|scope| {
+            // Start client threads, each sending its own PING
+            let clients = (0..CLIENTS)
+                .map(|i| {
+                    let transport = &transport;
+                    scope.spawn(move || -> Result<(), T::Error> {
+                        let message = format!("Ping {}", i);
+                        let mut client = transport.connect(address)?;
+
+                        helper::send_message(&mut client, message.as_bytes())?;
+                        helper::receive_message(&mut client, message.as_bytes())
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            // Server echoes every message on its own endpoint, kept open until clients finish
+            let mut servers = Vec::with_capacity(CLIENTS);
+            for _ in 0..CLIENTS {
+                let mut server = listener.accept()?;
+
+                let read_buf = server.read()?;
+                let message = read_buf.to_vec();
+                read_buf.consume(message.len())?;
+                debug!("Echoing message '{}'", String::from_utf8_lossy(&message));
+
+                helper::send_message(&mut server, &message)?;
+                servers.push(server);
+            }
+
+            // Wait for all clients to finish
+            for client in clients {
+                client.join().expect("client thread panicked")?;
+            }
+
+            Ok(())
+        })
+    }
 }
 
 mod shmem {
@@ -248,7 +347,10 @@ mod shmem {
         time::{Duration, SystemTime, UNIX_EPOCH},
     };
 
-    use crate::ipc::transport::shmem::{ShmemTransportBuilder, ShmemTransportError};
+    use crate::ipc::transport::shmem::{
+        ShmemSyncMode, ShmemTransportBuilder, ShmemTransportError, ShmemWaitStats,
+        ShmemWaitStrategy,
+    };
 
     use super::*;
 
@@ -276,6 +378,14 @@ mod shmem {
         )
     }
 
+    #[test]
+    fn test_read_write_timeout() -> Result<(), ShmemTransportError> {
+        let transport = ShmemTransportBuilder::default().build();
+        let address = unique_shmem_addr();
+
+        test_suits::read_write_timeout(transport, &address)
+    }
+
     #[test]
     fn test_bidirectional_communication() -> Result<(), ShmemTransportError> {
         let transport = ShmemTransportBuilder::default().build();
@@ -298,4 +408,521 @@ mod shmem {
 
         test_suits::transfer_raw_bytes(transport, &address, DATA_SIZE)
     }
+
+    #[test]
+    fn test_bidirectional_communication_mpmc() -> Result<(), ShmemTransportError> {
+        let transport = ShmemTransportBuilder::new()
+            .sync_mode(ShmemSyncMode::Mpmc)
+            .build();
+        let address = unique_shmem_addr();
+
+        test_suits::bidirectional_communication(transport, &address)
+    }
+
+    #[test]
+    fn test_raw_bytes_transfer_mpmc() -> Result<(), ShmemTransportError> {
+        const DATA_SIZE: usize = 16 * 1024 * 1024; // 16M
+        const BUFFER_SIZE: usize = 16 * 1024; // 16K
+        const TEST_TIMEOUT: Duration = Duration::from_millis(200);
+
+        let transport = ShmemTransportBuilder::new()
+            .buffer_size(BUFFER_SIZE)
+            .connect_timeout(TEST_TIMEOUT)
+            .sync_mode(ShmemSyncMode::Mpmc)
+            .build();
+        let address = unique_shmem_addr();
+
+        test_suits::transfer_raw_bytes(transport, &address, DATA_SIZE)
+    }
+
+    /// Reads one message which the peer sends after `delay`, returning the reader's stats.
+    fn delayed_read_stats(
+        strategy: ShmemWaitStrategy,
+        delay: Duration,
+    ) -> Result<ShmemWaitStats, ShmemTransportError> {
+        const PING: &[u8] = b"Ping";
+
+        let transport = ShmemTransportBuilder::new().wait_strategy(strategy).build();
+        let address = unique_shmem_addr();
+        let (mut server, mut client) = helper::create_connection(transport, address.as_str())?;
+
+        std::thread::scope(|s| {
+            let sender = s.spawn(|| {
+                std::thread::sleep(delay);
+                helper::send_message(&mut client, PING)
+            });
+            helper::receive_message(&mut server, PING)?;
+            sender.join().expect("Sender thread panicked")
+        })?;
+
+        Ok(server.read_wait_stats())
+    }
+
+    #[test]
+    fn test_wait_strategy_futex() -> Result<(), ShmemTransportError> {
+        let stats = delayed_read_stats(ShmemWaitStrategy::Futex, Duration::from_millis(20))?;
+
+        assert_eq!(stats.spin, 0);
+        assert!(stats.futex > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wait_strategy_spin_then_futex() -> Result<(), ShmemTransportError> {
+        // The budget is exhausted long before the message arrives
+        let strategy = ShmemWaitStrategy::SpinThenFutex(Duration::from_micros(100));
+        let stats = delayed_read_stats(strategy, Duration::from_millis(20))?;
+        assert_eq!(stats.spin, 0);
+        assert!(stats.futex > 0);
+
+        // The message arrives well within the budget
+        let strategy = ShmemWaitStrategy::SpinThenFutex(Duration::from_secs(10));
+        let stats = delayed_read_stats(strategy, Duration::from_millis(20))?;
+        assert!(stats.spin > 0);
+        assert_eq!(stats.futex, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wait_strategy_busy_poll() -> Result<(), ShmemTransportError> {
+        let stats =
+            delayed_read_stats(ShmemWaitStrategy::BusyPoll(None), Duration::from_millis(20))?;
+
+        assert!(stats.spin > 0);
+        assert_eq!(stats.futex, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wait_strategy_immediate() -> Result<(), ShmemTransportError> {
+        let transport = ShmemTransportBuilder::new().build();
+        let address = unique_shmem_addr();
+        let (mut server, mut client) = helper::create_connection(transport, address.as_str())?;
+
+        helper::send_message(&mut client, b"Ping")?;
+        helper::receive_message(&mut server, b"Ping")?;
+
+        let stats = server.read_wait_stats();
+        assert_eq!(stats.immediate, 1);
+        assert_eq!(client.write_wait_stats().immediate, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_accept_multiple_clients() -> Result<(), ShmemTransportError> {
+        let transport = ShmemTransportBuilder::default().build();
+        let address = unique_shmem_addr();
+
+        test_suits::accept_multiple_clients(transport, &address)
+    }
+}
+
+mod uds {
+    use std::{
+        process,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    use crate::ipc::transport::uds::{UdsTransportBuilder, UdsTransportError};
+
+    use super::*;
+
+    fn unique_socket_path() -> String {
+        static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+        let curr_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .expect("Failed to get current time");
+
+        std::env::temp_dir()
+            .join(format!(
+                "xgpu_{}_{}_{}.sock",
+                process::id(),
+                curr_time,
+                SEQ.fetch_add(1, Ordering::Relaxed)
+            ))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_read_write_timeout() -> Result<(), UdsTransportError> {
+        let transport = UdsTransportBuilder::default().build();
+        let address = unique_socket_path();
+
+        test_suits::read_write_timeout(transport, &address)
+    }
+
+    #[test]
+    fn test_bidirectional_communication() -> Result<(), UdsTransportError> {
+        let transport = UdsTransportBuilder::default().build();
+        let address = unique_socket_path();
+
+        test_suits::bidirectional_communication(transport, &address)
+    }
+
+    #[test]
+    fn test_raw_bytes_transfer() -> Result<(), UdsTransportError> {
+        const DATA_SIZE: usize = 16 * 1024 * 1024; // 16M
+        const BUFFER_SIZE: usize = 16 * 1024; // 16K
+        const TEST_TIMEOUT: Duration = Duration::from_millis(200);
+
+        let transport = UdsTransportBuilder::new()
+            .buffer_size(BUFFER_SIZE)
+            .connect_timeout(TEST_TIMEOUT)
+            .build();
+        let address = unique_socket_path();
+
+        test_suits::transfer_raw_bytes(transport, &address, DATA_SIZE)
+    }
+
+    #[test]
+    fn test_socket_unlinked_on_drop() -> Result<(), UdsTransportError> {
+        let transport = UdsTransportBuilder::default().build();
+        let address = unique_socket_path();
+
+        let (server, client) = helper::create_connection(transport, address.as_str())?;
+        assert!(std::path::Path::new(&address).exists());
+
+        drop(client);
+        drop(server);
+        assert!(!std::path::Path::new(&address).exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_accept_multiple_clients() -> Result<(), UdsTransportError> {
+        let transport = UdsTransportBuilder::default().build();
+        let address = unique_socket_path();
+
+        test_suits::accept_multiple_clients(transport, &address)
+    }
+
+    #[test]
+    fn test_listener_unlinked_on_drop() -> Result<(), UdsTransportError> {
+        let transport = UdsTransportBuilder::default().build();
+        let address = unique_socket_path();
+
+        let listener = transport.listen(&address)?;
+        let _client = transport.connect(&address)?;
+        let _server = listener.accept()?;
+        assert!(std::path::Path::new(&address).exists());
+
+        drop(listener);
+        assert!(!std::path::Path::new(&address).exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_connect_zero_timeout() -> Result<(), UdsTransportError> {
+        let transport = UdsTransportBuilder::new()
+            .connect_timeout(Duration::ZERO)
+            .build();
+        let address = unique_socket_path();
+
+        // Nothing listens yet, the one attempt fails
+        let result = transport.connect(&address);
+        assert!(matches!(result, Err(UdsTransportError::ConnectionTimeout)));
+
+        // A listener is there, the one attempt succeeds
+        let listener = transport.listen(&address)?;
+        let _client = transport.connect(&address)?;
+        let _server = listener.accept()?;
+
+        Ok(())
+    }
+}
+
+mod tcp {
+    use std::{net::TcpListener, time::Duration};
+
+    use crate::ipc::transport::tcp::{TcpTransportBuilder, TcpTransportError};
+
+    use super::*;
+
+    fn unused_tcp_addr() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind tcp socket");
+        let addr = listener.local_addr().expect("Failed to get local address");
+
+        addr.to_string()
+    }
+
+    #[test]
+    fn test_read_write_timeout() -> Result<(), TcpTransportError> {
+        let transport = TcpTransportBuilder::default().build();
+        let address = unused_tcp_addr();
+
+        test_suits::read_write_timeout(transport, &address)
+    }
+
+    #[test]
+    fn test_bidirectional_communication() -> Result<(), TcpTransportError> {
+        let transport = TcpTransportBuilder::default().build();
+        let address = unused_tcp_addr();
+
+        test_suits::bidirectional_communication(transport, &address)
+    }
+
+    #[test]
+    fn test_raw_bytes_transfer() -> Result<(), TcpTransportError> {
+        const DATA_SIZE: usize = 16 * 1024 * 1024; // 16M
+        const BUFFER_SIZE: usize = 16 * 1024; // 16K
+        const TEST_TIMEOUT: Duration = Duration::from_millis(200);
+
+        let transport = TcpTransportBuilder::new()
+            .buffer_size(BUFFER_SIZE)
+            .connect_timeout(TEST_TIMEOUT)
+            .build();
+        let address = unused_tcp_addr();
+
+        test_suits::transfer_raw_bytes(transport, &address, DATA_SIZE)
+    }
+
+    #[test]
+    fn test_connect_timeout() {
+        const TEST_TIMEOUT: Duration = Duration::from_millis(50);
+
+        let transport = TcpTransportBuilder::new()
+            .connect_timeout(TEST_TIMEOUT)
+            .build();
+        let address = unused_tcp_addr();
+
+        let result = transport.connect(&address);
+        assert!(matches!(result, Err(TcpTransportError::ConnectionTimeout)));
+    }
+
+    #[test]
+    fn test_bind_ephemeral_port() -> Result<(), TcpTransportError> {
+        let transport = TcpTransportBuilder::default().build();
+
+        let server = transport.create("127.0.0.1:0")?;
+        let local_addr = server.local_addr()?;
+        assert_ne!(local_addr.port(), 0);
+
+        let mut client = transport.connect(&local_addr.to_string())?;
+        let mut server = server;
+
+        helper::send_message(&mut client, b"Ping")?;
+        helper::receive_message(&mut server, b"Ping")
+    }
+
+    #[test]
+    fn test_accept_multiple_clients() -> Result<(), TcpTransportError> {
+        let transport = TcpTransportBuilder::default().build();
+        let address = unused_tcp_addr();
+
+        test_suits::accept_multiple_clients(transport, &address)
+    }
+}
+
+mod loopback {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use crate::{
+        ipc::transport::loopback::{LoopbackTransportBuilder, LoopbackTransportError},
+        sys::page,
+    };
+
+    use super::*;
+
+    fn unique_loopback_addr() -> String {
+        static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+        format!("loopback_{}", SEQ.fetch_add(1, Ordering::Relaxed))
+    }
+
+    #[test]
+    fn test_read_write_timeout() -> Result<(), LoopbackTransportError> {
+        let transport = LoopbackTransportBuilder::default().build();
+        let address = unique_loopback_addr();
+
+        test_suits::read_write_timeout(transport, &address)
+    }
+
+    #[test]
+    fn test_bidirectional_communication() -> Result<(), LoopbackTransportError> {
+        let transport = LoopbackTransportBuilder::default().build();
+        let address = unique_loopback_addr();
+
+        test_suits::bidirectional_communication(transport, &address)
+    }
+
+    #[test]
+    fn test_raw_bytes_transfer() -> Result<(), LoopbackTransportError> {
+        const DATA_SIZE: usize = 16 * 1024 * 1024; // 16M
+        const BUFFER_SIZE: usize = 16 * 1024; // 16K
+        const TEST_TIMEOUT: Duration = Duration::from_millis(200);
+
+        let transport = LoopbackTransportBuilder::new()
+            .buffer_size(BUFFER_SIZE)
+            .connect_timeout(TEST_TIMEOUT)
+            .build();
+        let address = unique_loopback_addr();
+
+        test_suits::transfer_raw_bytes(transport, &address, DATA_SIZE)
+    }
+
+    #[test]
+    fn test_space_reclaimed_while_reader_behind() -> Result<(), LoopbackTransportError> {
+        const BUFFER_SIZE: usize = 16 * 1024; // 16K
+        const TEST_TIMEOUT: Duration = Duration::from_millis(200);
+
+        let transport = LoopbackTransportBuilder::new()
+            .buffer_size(BUFFER_SIZE)
+            .build();
+        let address = unique_loopback_addr();
+        let (mut server, mut client) = helper::create_connection(transport, address.as_str())?;
+
+        let data: Vec<u8> = (0..BUFFER_SIZE + page::page_size())
+            .map(|i| (i % 251) as u8)
+            .collect();
+
+        // Fill the whole buffer, then consume part of it
+        let mut buf = client.write()?;
+        buf.copy_from_slice(&data[..BUFFER_SIZE]);
+        buf.submit(BUFFER_SIZE)?;
+        let consumed = page::page_size() + 100;
+        server.read()?.consume(consumed)?;
+        assert!(client.write_timeout(TEST_TIMEOUT)?.is_none());
+
+        // The next read reclaims the consumed pages, before all data is drained
+        let buf = server.read()?;
+        assert_eq!(&buf[..], &data[consumed..BUFFER_SIZE]);
+        buf.consume(0)?;
+
+        let mut buf = client
+            .write_timeout(TEST_TIMEOUT)?
+            .expect("Consumed pages should be writable");
+        assert_eq!(buf.len(), page::page_size());
+        buf.copy_from_slice(&data[BUFFER_SIZE..]);
+        buf.submit(page::page_size())?;
+
+        let buf = server.read()?;
+        assert_eq!(&buf[..], &data[consumed..]);
+        buf.consume(data.len() - consumed)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_address_reuse() -> Result<(), LoopbackTransportError> {
+        let transport = LoopbackTransportBuilder::default().build();
+        let address = unique_loopback_addr();
+
+        let server = transport.create(&address)?;
+        assert!(matches!(
+            transport.create(&address),
+            Err(LoopbackTransportError::AddressInUse { .. })
+        ));
+
+        drop(server);
+        let _server = transport.create(&address)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_peer_closed() -> Result<(), LoopbackTransportError> {
+        let transport = LoopbackTransportBuilder::default().build();
+        let address = unique_loopback_addr();
+
+        let (mut server, client) = helper::create_connection(transport, address.as_str())?;
+        drop(client);
+
+        assert!(matches!(
+            server.read(),
+            Err(LoopbackTransportError::ConnectionClosed)
+        ));
+        assert!(matches!(
+            server.write(),
+            Err(LoopbackTransportError::ConnectionClosed)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_accept_multiple_clients() -> Result<(), LoopbackTransportError> {
+        let transport = LoopbackTransportBuilder::default().build();
+        let address = unique_loopback_addr();
+
+        test_suits::accept_multiple_clients(transport, &address)
+    }
+
+    #[test]
+    fn test_listener_closed() -> Result<(), LoopbackTransportError> {
+        let transport = LoopbackTransportBuilder::default().build();
+        let address = unique_loopback_addr();
+
+        let listener = transport.listen(&address)?;
+        assert!(matches!(
+            transport.create(&address),
+            Err(LoopbackTransportError::AddressInUse { .. })
+        ));
+
+        // Connections never accepted are closed along with the listener
+        let mut client = transport.connect(&address)?;
+        drop(listener);
+        assert!(matches!(
+            client.read(),
+            Err(LoopbackTransportError::ConnectionClosed)
+        ));
+
+        let _server = transport.create(&address)?;
+
+        Ok(())
+    }
+}
+
+mod any {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::ipc::transport::any::{AnyTransportError, TransportUri};
+
+    use super::*;
+
+    fn unique_suffix() -> String {
+        static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+        format!(
+            "{}_{}",
+            std::process::id(),
+            SEQ.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    fn run_suites(uri: &str) -> Result<(), AnyTransportError> {
+        let uri = uri.parse::<TransportUri>().expect("Invalid transport uri");
+
+        test_suits::bidirectional_communication(uri.transport(), uri.address())?;
+
+        // Reusing the address also checks that it was released
+        test_suits::transfer_raw_bytes(uri.transport(), uri.address(), 1024 * 1024)?;
+
+        test_suits::accept_multiple_clients(uri.transport(), uri.address())
+    }
+
+    #[test]
+    fn test_loopback_uri() -> Result<(), AnyTransportError> {
+        run_suites(&format!("loopback:///any_{}?buf=16K", unique_suffix()))
+    }
+
+    #[test]
+    fn test_shmem_uri() -> Result<(), AnyTransportError> {
+        run_suites(&format!("shm:///any_{}?buf=16K", unique_suffix()))
+    }
+
+    #[test]
+    fn test_uds_uri() -> Result<(), AnyTransportError> {
+        let path = std::env::temp_dir().join(format!("xgpu_any_{}.sock", unique_suffix()));
+
+        run_suites(&format!("unix://{}?buf=16K", path.display()))
+    }
 }
@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    fmt::Debug,
+    fs,
+    os::{
+        fd::AsFd,
+        unix::net::{UnixListener, UnixStream},
+    },
+    time::{Duration, Instant},
+};
+
+use nix::poll::PollFlags;
+use tracing::debug;
+
+use crate::{
+    ipc::{
+        bytewise::AlignedBuffer,
+        transport::{
+            Endpoint,
+            stream::{self, StreamReadBuffer, StreamRxBuffer, StreamWriteBuffer},
+        },
+    },
+    sys::page,
+};
+
+use super::error::UdsTransportError;
+
+const LOG_TAG: &str = "Uds";
+
+#[derive(Debug)]
+enum UdsSocket {
+    /// Bound socket waiting for its peer, accepted on first use.
+    Listening(UnixListener),
+    /// Established connection.
+    Connected(UnixStream),
+}
+
+impl UdsSocket {
+    fn stream(
+        &mut self,
+        path: &str,
+        deadline: Option<Instant>,
+    ) -> Result<Option<&UnixStream>, UdsTransportError> {
+        if let Self::Listening(listener) = self {
+            if !stream::poll_until(listener.as_fd(), PollFlags::POLLIN, deadline)? {
+                return Ok(None);
+            }
+            let (stream, _) = listener
+                .accept()
+                .map_err(|e| UdsTransportError::AcceptError {
+                    path: path.to_owned(),
+                    source: e,
+                })?;
+
+            debug!("[Uds] '{}': Accepted", path);
+            *self = Self::Connected(stream);
+        }
+
+        match self {
+            Self::Connected(stream) => Ok(Some(stream)),
+            Self::Listening(_) => unreachable!(),
+        }
+    }
+}
+
+/// A `Transport` implementation that uses a unix stream socket for bidirectional communication.
+pub struct UdsEndpoint {
+    path: String,
+    is_owner: bool,
+    socket: UdsSocket,
+    rx: StreamRxBuffer,
+    tx: AlignedBuffer,
+}
+
+impl UdsEndpoint {
+    #[inline]
+    pub(crate) fn listening(path: String, listener: UnixListener, buffer_size: usize) -> Self {
+        Self::new(path, true, UdsSocket::Listening(listener), buffer_size)
+    }
+
+    #[inline]
+    pub(crate) fn connected(path: String, stream: UnixStream, buffer_size: usize) -> Self {
+        Self::new(path, false, UdsSocket::Connected(stream), buffer_size)
+    }
+
+    fn new(path: String, is_owner: bool, socket: UdsSocket, buffer_size: usize) -> Self {
+        Self {
+            path,
+            is_owner,
+            socket,
+            rx: StreamRxBuffer::new(buffer_size),
+            tx: AlignedBuffer::new(buffer_size, page::page_size()),
+        }
+    }
+}
+
+impl UdsEndpoint {
+    fn read_until(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<StreamReadBuffer<'_, UdsTransportError>>, UdsTransportError> {
+        let Some(stream) = self.socket.stream(&self.path, deadline)? else {
+            return Ok(None);
+        };
+
+        self.rx.compact();
+        if self.rx.is_starved() {
+            if !stream::poll_until(stream.as_fd(), PollFlags::POLLIN, deadline)? {
+                return Ok(None);
+            }
+            self.rx.fill(stream.as_fd())?;
+        }
+
+        Ok(Some(StreamReadBuffer::new(
+            &mut self.rx,
+            LOG_TAG,
+            &self.path,
+        )))
+    }
+
+    /// The returned buffer is ready once the socket is writable, sending a
+    /// frame larger than the socket buffer may still block on submit.
+    fn write_until(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<StreamWriteBuffer<'_, UdsTransportError>>, UdsTransportError> {
+        let Some(stream) = self.socket.stream(&self.path, deadline)? else {
+            return Ok(None);
+        };
+
+        if !stream::poll_until(stream.as_fd(), PollFlags::POLLOUT, deadline)? {
+            return Ok(None);
+        }
+
+        Ok(Some(StreamWriteBuffer::new(
+            stream.as_fd(),
+            &mut self.tx,
+            LOG_TAG,
+            &self.path,
+        )))
+    }
+}
+
+impl Endpoint for UdsEndpoint {
+    type Error = UdsTransportError;
+    type ReadBuf<'a> = StreamReadBuffer<'a, UdsTransportError>;
+    type WriteBuf<'a> = StreamWriteBuffer<'a, UdsTransportError>;
+
+    fn read(&mut self) -> Result<Self::ReadBuf<'_>, Self::Error> {
+        self.read_until(None)
+            .map(|buf| buf.expect("Untimed read never times out"))
+    }
+
+    fn write(&mut self) -> Result<Self::WriteBuf<'_>, Self::Error> {
+        self.write_until(None)
+            .map(|buf| buf.expect("Untimed write never times out"))
+    }
+
+    fn read_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::ReadBuf<'_>>, Self::Error> {
+        self.read_until(Some(Instant::now() + timeout))
+    }
+
+    fn write_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        self.write_until(Some(Instant::now() + timeout))
+    }
+
+    /// The write buffer always spans the whole staging buffer.
+    fn write_at_least(&mut self, _len: usize) -> Result<Self::WriteBuf<'_>, Self::Error> {
+        self.write()
+    }
+
+    fn write_at_least_timeout(
+        &mut self,
+        _len: usize,
+        timeout: Duration,
+    ) -> Result<Option<Self::WriteBuf<'_>>, Self::Error> {
+        self.write_timeout(timeout)
+    }
+}
+
+impl Debug for UdsEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UdsEndpoint")
+            .field("path", &self.path)
+            .field("is_owner", &self.is_owner)
+            .field("socket", &self.socket)
+            .finish()
+    }
+}
+
+impl Drop for UdsEndpoint {
+    fn drop(&mut self) {
+        if self.is_owner {
+            debug!("[Uds] '{}': Unlink", self.path);
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+use std::{
+    fs, io,
+    os::{fd::AsFd, unix::net::UnixListener},
+    time::{Duration, Instant},
+};
+
+use nix::poll::PollFlags;
+use tracing::debug;
+
+use crate::ipc::transport::{Listener, stream};
+
+use super::{endpoint::UdsEndpoint, error::UdsTransportError};
+
+/// A `Listener` implementation that accepts unix stream socket connections on one path.
+#[derive(Debug)]
+pub struct UdsListener {
+    path: String,
+    listener: UnixListener,
+    buffer_size: usize,
+}
+
+impl UdsListener {
+    pub(crate) fn new(
+        path: String,
+        listener: UnixListener,
+        buffer_size: usize,
+    ) -> Result<Self, UdsTransportError> {
+        let listener = Self {
+            path,
+            listener,
+            buffer_size,
+        };
+        // Non-blocking, so that concurrent accepts never block past their deadline.
+        listener.listener.set_nonblocking(true)?;
+
+        Ok(listener)
+    }
+
+    fn accept_until(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<UdsEndpoint>, UdsTransportError> {
+        loop {
+            if !stream::poll_until(self.listener.as_fd(), PollFlags::POLLIN, deadline)? {
+                return Ok(None);
+            }
+            // Another thread may have taken the connection between poll and accept.
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(e) => {
+                    return Err(UdsTransportError::AcceptError {
+                        path: self.path.clone(),
+                        source: e,
+                    });
+                }
+            };
+            stream.set_nonblocking(false)?;
+
+            debug!("[Uds] '{}': Accepted", self.path);
+            return Ok(Some(UdsEndpoint::connected(
+                self.path.clone(),
+                stream,
+                self.buffer_size,
+            )));
+        }
+    }
+}
+
+impl Listener for UdsListener {
+    type Error = UdsTransportError;
+    type Endpoint = UdsEndpoint;
+
+    fn accept(&self) -> Result<Self::Endpoint, Self::Error> {
+        self.accept_until(None)
+            .map(|endpoint| endpoint.expect("Untimed accept never times out"))
+    }
+
+    fn accept_timeout(&self, timeout: Duration) -> Result<Option<Self::Endpoint>, Self::Error> {
+        self.accept_until(Some(Instant::now() + timeout))
+    }
+}
+
+impl Drop for UdsListener {
+    fn drop(&mut self) {
+        debug!("[Uds] '{}': Unlink", self.path);
+        let _ = fs::remove_file(&self.path);
+    }
+}
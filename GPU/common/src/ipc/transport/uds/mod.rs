@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+//! A Unix domain socket (UDS) transport layer implementation.
+//!
+//! This module provides an inter-process communication mechanism built on
+//! `AF_UNIX` stream sockets. Unlike the shared memory transport it does not
+//! require both peers to share an IPC namespace, so it also works across
+//! container boundaries through a bind-mounted socket file.
+
+mod error;
+pub use error::*;
+
+mod endpoint;
+pub use endpoint::*;
+
+mod listener;
+pub use listener::*;
+
+mod transport;
+pub use transport::*;
@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    io,
+    os::unix::net::{UnixListener, UnixStream},
+    thread,
+    time::{Duration, Instant},
+};
+
+use tracing::debug;
+
+use crate::ipc::transport::Transport;
+
+use super::{endpoint::UdsEndpoint, error::UdsTransportError, listener::UdsListener};
+
+#[derive(Debug, Clone, Copy)]
+pub struct UdsTransport {
+    buffer_size: usize,
+    conn_timeout: Duration,
+}
+
+impl Transport for UdsTransport {
+    type Error = UdsTransportError;
+    type Endpoint = UdsEndpoint;
+    type Listener = UdsListener;
+    type Address = str;
+
+    fn create(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
+        let listener = UnixListener::bind(addr).map_err(|e| UdsTransportError::BindError {
+            path: addr.to_owned(),
+            source: e,
+        })?;
+
+        debug!("[Uds] '{}': Listening", addr);
+        Ok(UdsEndpoint::listening(
+            addr.to_owned(),
+            listener,
+            self.buffer_size,
+        ))
+    }
+
+    fn listen(&self, addr: &Self::Address) -> Result<Self::Listener, Self::Error> {
+        let listener = UnixListener::bind(addr).map_err(|e| UdsTransportError::BindError {
+            path: addr.to_owned(),
+            source: e,
+        })?;
+
+        debug!("[Uds] '{}': Listening", addr);
+        UdsListener::new(addr.to_owned(), listener, self.buffer_size)
+    }
+
+    fn connect(&self, addr: &Self::Address) -> Result<Self::Endpoint, Self::Error> {
+        const RETRY_DELAY: Duration = Duration::from_millis(10);
+
+        let start_time = Instant::now();
+        // Tries at least once, even with a zero timeout
+        let stream = loop {
+            match UnixStream::connect(addr) {
+                Ok(stream) => break stream,
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+                    ) =>
+                {
+                    if start_time.elapsed() >= self.conn_timeout {
+                        return Err(UdsTransportError::ConnectionTimeout);
+                    }
+                    thread::sleep(RETRY_DELAY);
+                    continue;
+                }
+                Err(e) => {
+                    return Err(UdsTransportError::ConnectError {
+                        path: addr.to_owned(),
+                        source: e,
+                    });
+                }
+            }
+        };
+
+        debug!("[Uds] '{}': Connected", addr);
+        Ok(UdsEndpoint::connected(
+            addr.to_owned(),
+            stream,
+            self.buffer_size,
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UdsTransportBuilder {
+    buffer_size: usize,
+    conn_timeout: Duration,
+}
+
+impl UdsTransportBuilder {
+    pub fn new() -> Self {
+        const DEFAULT_BUFF_SIZE: usize = 4096;
+        const DEFAULT_CONN_TIMEOUT: Duration = Duration::from_millis(100);
+
+        Self {
+            buffer_size: DEFAULT_BUFF_SIZE,
+            conn_timeout: DEFAULT_CONN_TIMEOUT,
+        }
+    }
+
+    #[inline]
+    pub fn buffer_size(mut self, value: usize) -> Self {
+        self.buffer_size = value;
+        self
+    }
+
+    #[inline]
+    pub fn connect_timeout(mut self, value: Duration) -> Self {
+        self.conn_timeout = value;
+        self
+    }
+
+    #[inline]
+    pub fn build(self) -> UdsTransport {
+        UdsTransport {
+            buffer_size: self.buffer_size,
+            conn_timeout: self.conn_timeout,
+        }
+    }
+}
+
+impl Default for UdsTransportBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
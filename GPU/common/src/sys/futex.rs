@@ -12,9 +12,16 @@
  * See the Mulan PSL v2 for more details.
  */
 
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::{
+    cell::UnsafeCell,
+    fmt::Debug,
+    io,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicU32, Ordering},
+};
 
 use linux_futex::{Futex, Shared, WaitError};
+use nix::errno::Errno;
 
 mod limits {
     pub const MAX_SPIN_COUNT: u32 = 100;
@@ -195,6 +202,116 @@ impl Drop for FutexMutexGuard<'_> {
     }
 }
 
+/// A robust, process-shared pthread mutex which survives its owner dying while holding it.
+///
+/// The lock word is a TID-based futex registered on the kernel robust-futex list, through
+/// glibc's robust mutex so the per-thread list glibc already owns is not clobbered. When
+/// the owner dies the kernel sets `FUTEX_OWNER_DIED` and hands the lock to the next locker,
+/// which then sees `RobustLockError::OwnerDied`.
+#[repr(C)]
+pub struct RobustMutex {
+    inner: UnsafeCell<libc::pthread_mutex_t>,
+}
+
+// SAFETY: pthread mutexes are designed to be shared between threads and processes.
+unsafe impl Send for RobustMutex {}
+unsafe impl Sync for RobustMutex {}
+
+/// Error returned when locking a `RobustMutex` whose previous owner died.
+#[derive(Debug)]
+pub enum RobustLockError<'a> {
+    /// The previous owner died while holding the lock, and the lock is now held by the caller.
+    ///
+    /// Unless the guard is marked consistent before it is dropped, the mutex becomes
+    /// permanently unusable.
+    OwnerDied(RobustMutexGuard<'a>),
+    /// An earlier owner died and the state was never marked consistent.
+    NotRecoverable,
+}
+
+impl RobustMutex {
+    /// Creates a placeholder which must be initialized by `init` before use.
+    pub const fn uninit() -> Self {
+        Self {
+            inner: UnsafeCell::new(libc::PTHREAD_MUTEX_INITIALIZER),
+        }
+    }
+
+    /// Initializes the mutex as robust and process-shared.
+    ///
+    /// # Safety
+    /// Must be called exactly once, before the mutex is visible to any other thread or
+    /// process, and the mutex must not be moved afterwards.
+    pub unsafe fn init(&self) -> io::Result<()> {
+        let mut attr = MaybeUninit::<libc::pthread_mutexattr_t>::uninit();
+
+        // SAFETY: `attr` is initialized before use and destroyed afterwards,
+        // the caller guarantees exclusive access to the mutex.
+        unsafe {
+            Errno::result(libc::pthread_mutexattr_init(attr.as_mut_ptr()))?;
+            let result = Errno::result(libc::pthread_mutexattr_setpshared(
+                attr.as_mut_ptr(),
+                libc::PTHREAD_PROCESS_SHARED,
+            ))
+            .and_then(|_| {
+                Errno::result(libc::pthread_mutexattr_setrobust(
+                    attr.as_mut_ptr(),
+                    libc::PTHREAD_MUTEX_ROBUST,
+                ))
+            })
+            .and_then(|_| Errno::result(libc::pthread_mutex_init(self.inner.get(), attr.as_ptr())));
+            libc::pthread_mutexattr_destroy(attr.as_mut_ptr());
+
+            result.map(|_| ()).map_err(io::Error::from)
+        }
+    }
+
+    /// Acquires the lock, blocking the current thread/process until it's available.
+    pub fn lock(&self) -> Result<RobustMutexGuard<'_>, RobustLockError<'_>> {
+        // SAFETY: The mutex was initialized by `init`.
+        match unsafe { libc::pthread_mutex_lock(self.inner.get()) } {
+            0 => Ok(RobustMutexGuard { mutex: self }),
+            libc::EOWNERDEAD => Err(RobustLockError::OwnerDied(RobustMutexGuard { mutex: self })),
+            libc::ENOTRECOVERABLE => Err(RobustLockError::NotRecoverable),
+            errno => panic!("Failed to lock robust mutex, {}", Errno::from_raw(errno)),
+        }
+    }
+
+    /// Unlocks the mutex.
+    fn unlock(&self) {
+        // SAFETY: Only called by the guard, which holds the lock.
+        unsafe { libc::pthread_mutex_unlock(self.inner.get()) };
+    }
+}
+
+impl Debug for RobustMutex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RobustMutex").finish_non_exhaustive()
+    }
+}
+
+/// An RAII guard that releases the robust lock when it goes out of scope.
+#[derive(Debug)]
+pub struct RobustMutexGuard<'a> {
+    mutex: &'a RobustMutex,
+}
+
+impl RobustMutexGuard<'_> {
+    /// Marks the state protected by the mutex as consistent again after its owner died.
+    pub fn mark_consistent(&self) -> io::Result<()> {
+        // SAFETY: The guard holds the lock.
+        Errno::result(unsafe { libc::pthread_mutex_consistent(self.mutex.inner.get()) })
+            .map(|_| ())
+            .map_err(io::Error::from)
+    }
+}
+
+impl Drop for RobustMutexGuard<'_> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,4 +364,66 @@ mod tests {
             "The final count is incorrect, indicating a race condition occurred."
         );
     }
+
+    fn robust_mutex() -> Box<RobustMutex> {
+        let mutex = Box::new(RobustMutex::uninit());
+        unsafe { mutex.init() }.expect("Failed to init robust mutex");
+        mutex
+    }
+
+    /// Locks the mutex on a thread which then exits without unlocking it.
+    fn abandon(mutex: &RobustMutex) {
+        thread::scope(|s| {
+            s.spawn(|| std::mem::forget(mutex.lock().expect("Failed to lock")));
+        });
+    }
+
+    #[test]
+    fn test_robust_mutex_contend() {
+        const NUM_THREADS: usize = 10;
+        const INCREMENTS_PER_THREAD: usize = 10_000;
+
+        let mutex = robust_mutex();
+        let counter = AtomicU32::new(0);
+
+        thread::scope(|s| {
+            for _ in 0..NUM_THREADS {
+                s.spawn(|| {
+                    for _ in 0..INCREMENTS_PER_THREAD {
+                        let _guard = mutex.lock().expect("Failed to lock");
+                        // A non-atomic read-modify-write, only correct under the lock
+                        let num = counter.load(Ordering::Relaxed);
+                        counter.store(num + 1, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(
+            counter.load(Ordering::Relaxed) as usize,
+            NUM_THREADS * INCREMENTS_PER_THREAD
+        );
+    }
+
+    #[test]
+    fn test_robust_mutex_owner_died() {
+        let mutex = robust_mutex();
+        abandon(&mutex);
+
+        match mutex.lock() {
+            Err(RobustLockError::OwnerDied(guard)) => guard.mark_consistent().unwrap(),
+            other => panic!("Expected owner died, got {:?}", other),
+        }
+        assert!(mutex.lock().is_ok());
+    }
+
+    #[test]
+    fn test_robust_mutex_not_recoverable() {
+        let mutex = robust_mutex();
+        abandon(&mutex);
+
+        // Dropping the guard without marking the state consistent poisons the mutex for good
+        assert!(matches!(mutex.lock(), Err(RobustLockError::OwnerDied(_))));
+        assert!(matches!(mutex.lock(), Err(RobustLockError::NotRecoverable)));
+    }
 }
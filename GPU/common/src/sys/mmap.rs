@@ -64,6 +64,35 @@ impl Mmap {
         Ok(mmap)
     }
 
+    /// Maps a file descriptor readable and writable, shared with other processes
+    /// mapping the same file.
+    ///
+    /// - `fd`: The file descriptor to map.
+    /// - `len`: The total length of the file to be mapped.
+    pub fn map_shared<F: AsFd>(fd: &F, len: usize) -> io::Result<Self> {
+        debug_assert!(
+            len % page::page_size() == 0,
+            "Mmap::map_shared requires 'len' ({}) to be page-aligned",
+            len
+        );
+
+        let ptr = unsafe {
+            mm::mmap(
+                None,
+                NonZero::new(len).ok_or(Errno::EINVAL)?,
+                mm::ProtFlags::PROT_READ | mm::ProtFlags::PROT_WRITE,
+                mm::MapFlags::MAP_SHARED,
+                fd.as_fd(),
+                0,
+            )
+        }?;
+
+        let mmap = Self {
+            mmap: MmapGuard { ptr, len },
+        };
+        Ok(mmap)
+    }
+
     /// Returns a pointer to the start of the entire mapping.
     #[inline]
     pub fn ptr(&self) -> *mut u8 {
@@ -16,4 +16,5 @@ pub mod cache;
 pub mod futex;
 pub mod mmap;
 pub mod page;
+pub mod process;
 pub mod shmem;
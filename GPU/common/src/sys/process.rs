@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{
+    fs, io,
+    os::unix::fs::MetadataExt,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+};
+
+/// Identifies a process across pid reuse and pid namespaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessId {
+    /// Process id, as seen from `pid_ns`.
+    pub pid: u32,
+    /// Start time in clock ticks since boot, distinguishes a reused pid.
+    pub start_time: u64,
+    /// Inode of the pid namespace the pid belongs to, `0` if unknown.
+    pub pid_ns: u64,
+}
+
+impl ProcessId {
+    /// Returns the identity of the calling process.
+    pub fn current() -> Self {
+        let pid = std::process::id();
+
+        Self {
+            pid,
+            start_time: read_start_time(pid).unwrap_or_default(),
+            pid_ns: current_pid_ns().unwrap_or_default(),
+        }
+    }
+
+    /// Checks whether the process is still running.
+    ///
+    /// Returns `None` if it cannot be told, e.g. the process lives in another pid namespace.
+    pub fn is_alive(&self) -> Option<bool> {
+        if self.pid_ns == 0 || current_pid_ns().ok()? != self.pid_ns {
+            return None;
+        }
+
+        match read_stat(self.pid) {
+            Ok(stat) => {
+                let (state, start_time) = parse_stat(&stat)?;
+                Some(!matches!(state, 'Z' | 'X') && start_time == self.start_time)
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Some(false),
+            Err(_) => None,
+        }
+    }
+}
+
+/// A `ProcessId` slot which can be published to and read from shared memory.
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct AtomicProcessId {
+    pid: AtomicU32,
+    start_time: AtomicU64,
+    pid_ns: AtomicU64,
+}
+
+impl AtomicProcessId {
+    pub const fn new() -> Self {
+        Self {
+            pid: AtomicU32::new(0),
+            start_time: AtomicU64::new(0),
+            pid_ns: AtomicU64::new(0),
+        }
+    }
+
+    /// Publishes `id`, the pid is written last so readers never see a torn identity.
+    pub fn store(&self, id: ProcessId) {
+        self.pid.store(0, Ordering::Release);
+        self.start_time.store(id.start_time, Ordering::Relaxed);
+        self.pid_ns.store(id.pid_ns, Ordering::Relaxed);
+        self.pid.store(id.pid, Ordering::Release);
+    }
+
+    /// Returns the published identity, or `None` if nothing was published yet.
+    pub fn load(&self) -> Option<ProcessId> {
+        let pid = self.pid.load(Ordering::Acquire);
+        if pid == 0 {
+            return None;
+        }
+
+        Some(ProcessId {
+            pid,
+            start_time: self.start_time.load(Ordering::Relaxed),
+            pid_ns: self.pid_ns.load(Ordering::Relaxed),
+        })
+    }
+}
+
+fn current_pid_ns() -> io::Result<u64> {
+    fs::metadata("/proc/self/ns/pid").map(|meta| meta.ino())
+}
+
+fn read_stat(pid: u32) -> io::Result<String> {
+    fs::read_to_string(format!("/proc/{}/stat", pid))
+}
+
+fn read_start_time(pid: u32) -> io::Result<u64> {
+    let stat = read_stat(pid)?;
+    parse_stat(&stat)
+        .map(|(_, start_time)| start_time)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Malformed /proc stat"))
+}
+
+/// Parses the state and start time out of `/proc/<pid>/stat`.
+fn parse_stat(stat: &str) -> Option<(char, u64)> {
+    // The command name may contain spaces and parentheses, skip past its last ')'
+    let mut fields = stat[stat.rfind(')')? + 1..].split_whitespace();
+
+    let state = fields.next()?.chars().next()?;
+    let start_time = fields.nth(18)?.parse().ok()?;
+
+    Some((state, start_time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{process::Command, thread, time::Duration};
+
+    #[test]
+    fn test_current_is_alive() {
+        let id = ProcessId::current();
+
+        assert_eq!(id.pid, std::process::id());
+        assert!(id.start_time > 0);
+        assert_eq!(id.is_alive(), Some(true));
+    }
+
+    #[test]
+    fn test_exited_is_dead() {
+        let mut child = Command::new("true").spawn().expect("Failed to spawn child");
+        let id = ProcessId {
+            pid: child.id(),
+            start_time: read_start_time(child.id()).expect("Failed to read child stat"),
+            pid_ns: current_pid_ns().unwrap(),
+        };
+
+        // A zombie is already dead
+        while read_stat(id.pid).is_ok_and(|stat| parse_stat(&stat).unwrap().0 != 'Z') {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(id.is_alive(), Some(false));
+
+        child.wait().unwrap();
+        assert_eq!(id.is_alive(), Some(false));
+    }
+
+    #[test]
+    fn test_reused_pid_is_dead() {
+        let id = ProcessId {
+            start_time: ProcessId::current().start_time + 1,
+            ..ProcessId::current()
+        };
+
+        assert_eq!(id.is_alive(), Some(false));
+    }
+
+    #[test]
+    fn test_foreign_namespace_is_unknown() {
+        let id = ProcessId {
+            pid_ns: 0,
+            ..ProcessId::current()
+        };
+
+        assert_eq!(id.is_alive(), None);
+    }
+
+    #[test]
+    fn test_atomic_process_id() {
+        let slot = AtomicProcessId::new();
+        assert_eq!(slot.load(), None);
+
+        let id = ProcessId::current();
+        slot.store(id);
+        assert_eq!(slot.load(), Some(id));
+    }
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "42 (a (b) c) S 1 42 42 0 -1 4194560 100 0 0 0 1 2 0 0 20 0 1 0 12345 0 0";
+        assert_eq!(parse_stat(stat), Some(('S', 12345)));
+    }
+}
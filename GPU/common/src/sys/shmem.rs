@@ -14,7 +14,7 @@
 
 use std::{
     fmt::{Debug, Display},
-    io,
+    fs, io,
     os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd},
 };
 
@@ -24,10 +24,14 @@ use nix::{
     sys::{
         mman as mm,
         stat::{self, Mode},
+        uio,
     },
     unistd,
 };
 
+/// Directory where the kernel exposes POSIX shared memory objects.
+const SHMEM_DIR: &str = "/dev/shm";
+
 #[repr(transparent)]
 pub struct ShmemName(String);
 
@@ -133,6 +137,39 @@ impl Shmem {
     pub fn is_owner(&self) -> bool {
         self.owned
     }
+
+    /// Reads exactly `buf.len()` bytes at `offset` without mapping the object.
+    pub fn read_at(&self, buf: &mut [u8], offset: usize) -> io::Result<()> {
+        let mut read = 0;
+        while read < buf.len() {
+            let pos = (offset + read).try_into().map_err(|_| Errno::EOVERFLOW)?;
+            match uio::pread(&self.fd, &mut buf[read..], pos) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(len) => read += len,
+                Err(Errno::EINTR) => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the shared memory object `name`, regardless of who owns it.
+    pub fn unlink<S: AsRef<str>>(name: S) -> io::Result<()> {
+        mm::shm_unlink(ShmemName::new(name).as_ref())?;
+        Ok(())
+    }
+
+    /// Lists the names of all shared memory objects visible to the process.
+    pub fn list() -> io::Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(SHMEM_DIR)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                names.push(format!("/{}", entry.file_name().to_string_lossy()));
+            }
+        }
+        Ok(names)
+    }
 }
 
 impl AsFd for Shmem {
@@ -248,4 +285,26 @@ mod tests {
         }
         assert!(Shmem::open(&name).is_err());
     }
+
+    #[test]
+    fn test_read_at() {
+        let name = unique_shmem_addr();
+        let shmem = Shmem::create(&name, 4096).unwrap();
+
+        let mut buf = [0xFFu8; 16];
+        shmem.read_at(&mut buf, 4080).unwrap();
+        assert_eq!(buf, [0u8; 16]);
+        assert!(shmem.read_at(&mut buf, 4090).is_err());
+    }
+
+    #[test]
+    fn test_list_and_unlink() {
+        let name = unique_shmem_addr();
+        let shmem = Shmem::create(&name, 1024).unwrap();
+        assert!(Shmem::list().unwrap().contains(&name));
+
+        Shmem::unlink(shmem.name()).unwrap();
+        assert!(!Shmem::list().unwrap().contains(&name));
+        assert!(Shmem::open(&name).is_err());
+    }
 }
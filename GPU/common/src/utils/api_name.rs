@@ -12,6 +12,9 @@
  * See the Mulan PSL v2 for more details.
  */
 
+// Hash of every `ApiFuncName` variant and its value, generated by the build script.
+include!(concat!(env!("OUT_DIR"), "/api_catalog.rs"));
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum ApiFuncName {
@@ -11,3 +11,20 @@ pub use cudax_sys::cublas;
 pub use cudax_sys::cublaslt;
 
 pub use cudax_sys::bootstrap;
+
+// Argument types of the forwarded APIs. Enums and handles are aliases of
+// primitives and pointers, already tagged by `xgpu-common`.
+xgpu_common::type_tags!(
+    runtime::CUstream_st,
+    runtime::CUevent_st,
+    runtime::cudaDeviceProp,
+    runtime::cudaPointerAttributes,
+    driver::CUlaunchConfig,
+    nccl::ncclComm,
+    nccl::ncclUniqueId,
+);
+
+// Structures pointing to further host memory, which is copied along with them.
+xgpu_common::pointer_fields!(driver::CUlaunchConfig, |config| {
+    attrs: [driver::CUlaunchAttribute; config.numAttrs as usize],
+});
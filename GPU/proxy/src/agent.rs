@@ -13,26 +13,51 @@
  */
 
 use ctor::{ctor, dtor};
+use cudax::{driver, nccl, nvml, runtime};
 use lazy_static::lazy_static;
 use libc::gettid;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+use std::env;
 use std::error::Error as StdError;
+use std::ffi::c_char;
 use std::fmt;
+use std::mem;
 use std::process;
-use std::sync::Once;
-use tracing::debug;
+use std::sync::{Arc, Once};
+use tracing::{debug, error};
+
+use crate::intern::intern;
 
 use xgpu_common::ipc::{
-    framer::LengthPrefixFramer,
-    message::Request,
-    peer::Server,
-    transport::shmem::{ShmemTransport, ShmemTransportBuilder},
+    dispatcher::Dispatcher,
+    error::IpcError,
+    framer::{CompressingFramer, LengthPrefixFramer},
+    message::{
+        Argument, MessageError, Request, RequestBatch, RequestFlag, ResponseStatus, TypeTag,
+    },
+    peer::Client,
+    transport::{
+        BulkRegion,
+        any::{AnyTransport, TRANSPORT_URI_ENV, TransportScheme, TransportUri},
+    },
 };
 
+const DEFAULT_TRANSPORT_URI: &str = "shm:///1234";
+const DEFAULT_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+/// Requests in flight shared by all application threads.
+const DEFAULT_WINDOW: usize = 64;
+/// Deferred calls sent on their own once this many are pending.
+const MAX_DEFERRED: usize = 64;
+
+type AgentFramer = CompressingFramer<LengthPrefixFramer>;
+
 #[derive(Debug)]
 pub enum AgentError {
     ServerNotInitialized,
     FmtError(fmt::Error),
+    RequestFailed(ResponseStatus),
+    MessageError(MessageError),
+    IpcError(IpcError<AgentFramer, AnyTransport>),
 }
 
 impl fmt::Display for AgentError {
@@ -40,6 +65,9 @@ impl fmt::Display for AgentError {
         match self {
             AgentError::ServerNotInitialized => write!(f, "Server not initialized"),
             AgentError::FmtError(e) => write!(f, "Format error: {}", e),
+            AgentError::RequestFailed(status) => write!(f, "Request failed: {}", status),
+            AgentError::MessageError(e) => write!(f, "Message error: {}", e),
+            AgentError::IpcError(e) => write!(f, "IPC error: {}", e),
         }
     }
 }
@@ -52,19 +80,114 @@ impl From<fmt::Error> for AgentError {
     }
 }
 
+impl From<MessageError> for AgentError {
+    fn from(err: MessageError) -> Self {
+        match err {
+            MessageError::RequestFailed(status) => AgentError::RequestFailed(status),
+            err => AgentError::MessageError(err),
+        }
+    }
+}
+
+impl From<IpcError<AgentFramer, AnyTransport>> for AgentError {
+    fn from(err: IpcError<AgentFramer, AnyTransport>) -> Self {
+        AgentError::IpcError(err)
+    }
+}
+
+impl AgentError {
+    /// Classifies the failure like the server would, `None` for a failure of the
+    /// proxy itself.
+    fn status(&self) -> Option<ResponseStatus> {
+        match self {
+            // No server to reach, or no way to reach it
+            AgentError::ServerNotInitialized | AgentError::IpcError(_) => {
+                Some(ResponseStatus::BackendUnavailable)
+            }
+            AgentError::RequestFailed(status) => Some(*status),
+            // The return value or out arguments are not what the call declares
+            AgentError::MessageError(_) => Some(ResponseStatus::TypeMismatch),
+            AgentError::FmtError(_) => None,
+        }
+    }
+
+    /// Returns the CUDA runtime error reporting the failure to the application.
+    pub fn cuda_error(&self) -> runtime::cudaError_t {
+        match self.status() {
+            Some(ResponseStatus::UnknownMethod) => runtime::cudaError_cudaErrorNotSupported,
+            Some(ResponseStatus::TypeMismatch) => runtime::cudaError_cudaErrorInvalidValue,
+            Some(ResponseStatus::BackendUnavailable) => {
+                runtime::cudaError_cudaErrorDevicesUnavailable
+            }
+            None | Some(ResponseStatus::Ok | ResponseStatus::HandlerPanic) => {
+                runtime::cudaError_cudaErrorUnknown
+            }
+        }
+    }
+
+    /// Returns the CUDA driver error reporting the failure to the application.
+    pub fn cu_result(&self) -> driver::CUresult {
+        match self.status() {
+            Some(ResponseStatus::UnknownMethod) => driver::cudaError_enum_CUDA_ERROR_NOT_SUPPORTED,
+            Some(ResponseStatus::TypeMismatch) => driver::cudaError_enum_CUDA_ERROR_INVALID_VALUE,
+            Some(ResponseStatus::BackendUnavailable) => driver::cudaError_enum_CUDA_ERROR_NO_DEVICE,
+            None | Some(ResponseStatus::Ok | ResponseStatus::HandlerPanic) => {
+                driver::cudaError_enum_CUDA_ERROR_UNKNOWN
+            }
+        }
+    }
+
+    /// Returns the NVML error reporting the failure to the application.
+    pub fn nvml_return(&self) -> nvml::nvmlReturn_t {
+        match self.status() {
+            Some(ResponseStatus::UnknownMethod) => nvml::nvmlReturn_enum_NVML_ERROR_NOT_SUPPORTED,
+            Some(ResponseStatus::TypeMismatch) => nvml::nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT,
+            Some(ResponseStatus::BackendUnavailable) => {
+                nvml::nvmlReturn_enum_NVML_ERROR_DRIVER_NOT_LOADED
+            }
+            None | Some(ResponseStatus::Ok | ResponseStatus::HandlerPanic) => {
+                nvml::nvmlReturn_enum_NVML_ERROR_UNKNOWN
+            }
+        }
+    }
+
+    /// Returns the NCCL error reporting the failure to the application.
+    #[allow(dead_code)] // No NCCL entry point is hooked yet
+    pub fn nccl_result(&self) -> nccl::ncclResult_t {
+        match self.status() {
+            Some(ResponseStatus::TypeMismatch) => nccl::ncclResult_t_ncclInvalidArgument,
+            Some(ResponseStatus::BackendUnavailable) => nccl::ncclResult_t_ncclSystemError,
+            None
+            | Some(
+                ResponseStatus::Ok | ResponseStatus::UnknownMethod | ResponseStatus::HandlerPanic,
+            ) => nccl::ncclResult_t_ncclInternalError,
+        }
+    }
+}
+
 lazy_static! {
-    static ref SERVER: Mutex<Option<Server<LengthPrefixFramer, ShmemTransport>>> = Mutex::new(None);
-}
-
-fn client_init(addr: String) -> Result<(), Box<dyn std::error::Error>> {
-    let mut server = SERVER.lock();
-    if server.is_none() {
-        let framer = LengthPrefixFramer::new(4 * 1024 * 1024);
-        let transport = ShmemTransportBuilder::new()
-            .buffer_size(4 * 1024 * 1024)
-            .build();
-        *server = Some(Server::create(framer, &transport, &addr)?);
-        debug!("{:#?}", server);
+    static ref DISPATCHER: RwLock<Option<Dispatcher<AgentFramer, AnyTransport>>> =
+        RwLock::new(None);
+    /// No-reply calls waiting to go out ahead of the next synchronous call.
+    static ref DEFERRED: Mutex<Vec<Request<'static>>> = Mutex::new(Vec::new());
+}
+
+fn client_init(uri: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut dispatcher = DISPATCHER.write();
+    if dispatcher.is_none() {
+        let uri = uri
+            .parse::<TransportUri>()?
+            .default_buffer_size(DEFAULT_BUFFER_SIZE);
+        debug!("transport uri: {}", uri);
+
+        // Frames are bounded by the transport buffer the uri asks for
+        let frame_limit = uri.buffer_size().unwrap_or(DEFAULT_BUFFER_SIZE);
+        // Compression only pays off over the network
+        let framer = CompressingFramer::new(LengthPrefixFramer::new(frame_limit))
+            .offer(uri.scheme() == TransportScheme::Tcp);
+        let client = Client::connect(framer, &uri.transport(), uri.address())?;
+        *dispatcher = Some(Dispatcher::new(client, DEFAULT_WINDOW));
+        debug!("{:#?}", dispatcher);
     }
     Ok(())
 }
@@ -87,17 +210,34 @@ fn logger_init() {
 #[ctor]
 fn setup() {
     logger_init();
-    client_init("1234".to_string()).expect("client_init failed");
+    let uri = env::var(TRANSPORT_URI_ENV).unwrap_or_else(|_| DEFAULT_TRANSPORT_URI.to_string());
+    client_init(&uri).expect("client_init failed");
 }
 
 #[dtor]
 fn destroy() {
-    SERVER.lock().take();
+    if let Some(dispatcher) = DISPATCHER.read().as_ref()
+        && let Err(e) = flush_deferred(dispatcher, &mut DEFERRED.lock())
+    {
+        error!("Failed to flush deferred calls: {}", e);
+    }
+    DISPATCHER.write().take();
 }
 
-pub fn invoke_api<T: Clone + 'static + std::marker::Copy>(
-    mut req: Request,
-) -> Result<T, AgentError> {
+pub fn invoke_api<T: Clone + TypeTag + std::marker::Copy>(req: Request) -> Result<T, AgentError> {
+    invoke_with(req, |ret_arg| ret_arg.downcast::<T>())
+}
+
+/// Invokes an API returning a C string, which stays valid for the lifetime of the
+/// process like the library's own.
+pub fn invoke_api_cstr(req: Request) -> Result<*const c_char, AgentError> {
+    invoke_with(req, |ret_arg| Ok(intern(ret_arg.downcast_cstr()?).as_ptr()))
+}
+
+fn invoke_with<R>(
+    req: Request,
+    ret: impl FnOnce(&Argument<'_>) -> Result<R, MessageError>,
+) -> Result<R, AgentError> {
     let pid = process::id();
     let tid = unsafe { gettid() };
     let tspt_addr = format!("{}_{}", pid, tid);
@@ -112,18 +252,102 @@ pub fn invoke_api<T: Clone + 'static + std::marker::Copy>(
 
     debug!("{:#?}", req);
 
-    let mut guard = SERVER.lock();
+    let guard = DISPATCHER.read();
+
+    let dispatcher = guard.as_ref().ok_or(AgentError::ServerNotInitialized)?;
+
+    // Deferred calls go first, in the same frame. The queue stays locked until
+    // sent, so calls leave in the order they were made, and is only cleared once
+    // they are, so a failed send leaves them for the next call.
+    let (ticket, mut batch) = {
+        let mut deferred = DEFERRED.lock();
+        let mut batch = RequestBatch::new();
+        for request in deferred.iter() {
+            batch.push(request.clone());
+        }
+        batch.push(req);
+
+        let ticket = dispatcher
+            .invoke_batch_async(&batch)?
+            .pop()
+            .expect("synchronous call should have a ticket");
+        deferred.clear();
 
-    let server = guard.as_mut().ok_or(AgentError::ServerNotInitialized)?;
+        (ticket, batch)
+    };
 
-    let resp = server.invoke(&req).expect("server.invoke failed");
+    let frame = dispatcher.wait(ticket)?;
+    let resp = frame.response().map_err(IpcError::from)?;
+    let req = batch
+        .requests_mut()
+        .last_mut()
+        .expect("batch should hold the synchronous call");
     //debug!("{:#?}", resp);
 
-    let ret_arg = *resp.ret_value();
+    // Out arguments are left untouched by a failed request
+    if !resp.status().is_ok() {
+        debug!("[--->] request failed on the server: {}", resp.status());
+        return Err(AgentError::RequestFailed(resp.status()));
+    }
 
-    let ret_value = ret_arg.downcast::<T>().unwrap();
+    let ret_value = ret(resp.ret_value())?;
     debug!("[--->] get response ok, updating request args with OUT flag...");
-    req.update_from(&resp).expect("update should succeed");
+    req.update_from(&resp)?;
 
     Ok(ret_value)
 }
+
+/// Returns the bulk data arena of the connection, for staging large host buffers.
+pub fn arena() -> Option<Arc<dyn BulkRegion>> {
+    DISPATCHER.read().as_ref()?.arena()
+}
+
+/// Defers a request until the next synchronous call, for asynchronous APIs whose
+/// errors surface later through `cudaGetLastError`.
+pub fn post_api(mut req: Request<'static>) -> Result<(), AgentError> {
+    req.set_flag(RequestFlag::NO_REPLY);
+    debug!(
+        "[<---] In post_api, request_id: {}, meethod_id: {}, arg_num: {}",
+        req.request_id(),
+        req.method_id(),
+        req.args().len()
+    );
+
+    let guard = DISPATCHER.read();
+
+    let dispatcher = guard.as_ref().ok_or(AgentError::ServerNotInitialized)?;
+
+    let mut deferred = DEFERRED.lock();
+    deferred.push(req);
+    if deferred.len() >= MAX_DEFERRED
+        && let Err(e) = flush_deferred(dispatcher, &mut deferred)
+    {
+        // The caller is told this one failed, the earlier ones stay queued
+        deferred.pop();
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+fn flush_deferred(
+    dispatcher: &Dispatcher<AgentFramer, AnyTransport>,
+    deferred: &mut Vec<Request<'static>>,
+) -> Result<(), AgentError> {
+    if deferred.is_empty() {
+        return Ok(());
+    }
+
+    // Put back if the send fails, to go out with the next call
+    let batch = RequestBatch::with_requests(mem::take(deferred));
+    match dispatcher.invoke_batch_async(&batch) {
+        Ok(tickets) => {
+            debug_assert!(tickets.is_empty());
+            Ok(())
+        }
+        Err(e) => {
+            *deferred = batch.into_requests();
+            Err(e.into())
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use std::{collections::HashSet, ffi::CStr};
+
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+
+lazy_static! {
+    /// Strings returned to the application, which may hold on to them forever.
+    static ref STRINGS: Mutex<HashSet<&'static CStr>> = Mutex::new(HashSet::new());
+}
+
+/// Returns a copy of `s` valid for the lifetime of the process. Each distinct
+/// string is only copied once, as the library returns the same few over and over.
+pub fn intern(s: &CStr) -> &'static CStr {
+    let mut strings = STRINGS.lock();
+    if let Some(interned) = strings.get(s) {
+        return interned;
+    }
+
+    let interned: &'static CStr = Box::leak(s.into());
+    strings.insert(interned);
+    interned
+}
@@ -12,11 +12,13 @@
  * See the Mulan PSL v2 for more details.
  */
 #![allow(clippy::missing_safety_doc)]
+use cudax::driver;
 use cudax::nvml;
 use cudax::runtime;
-use std::os::raw::{c_int, c_void};
+use std::os::raw::{c_char, c_int, c_uint, c_void};
 mod agent;
-use agent::invoke_api;
+use agent::{arena, invoke_api, invoke_api_cstr, post_api};
+mod intern;
 use tracing::debug;
 use xgpu_common::ipc::message::Request;
 use xgpu_common::ipc::message::{Argument, ArgumentFlag};
@@ -26,7 +28,7 @@ use xgpu_common::utils::api_name::ApiFuncName;
 pub unsafe extern "C" fn cudaDeviceSynchronize() -> runtime::cudaError_t {
     debug!("[Hooked] api_name: cudaDeviceSynchronize");
     let req = Request::with_args(ApiFuncName::FuncCudadevicesynchronize as u64, vec![]);
-    invoke_api::<runtime::cudaError_t>(req).expect("call invoke_api failed")
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
 }
 
 #[unsafe(no_mangle)]
@@ -42,14 +44,21 @@ pub unsafe extern "C" fn cudaDeviceGetStreamPriorityRange(
             unsafe { Argument::from_mut_ptr(greatest_priority, ArgumentFlag::ARG_OUT) },
         ],
     );
-    invoke_api::<runtime::cudaError_t>(req).expect("call invoke_api failed")
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cudaGetLastError() -> runtime::cudaError_t {
+    debug!("[Hooked] api_name: cudaGetLastError");
+    let req = Request::with_args(ApiFuncName::FuncCudagetlasterror as u64, vec![]);
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn cudaPeekAtLastError() -> runtime::cudaError_t {
     debug!("[Hooked] api_name: cudaPeekAtLastError");
     let req = Request::with_args(ApiFuncName::FuncCudapeekatlasterror as u64, vec![]);
-    invoke_api::<runtime::cudaError_t>(req).expect("call invoke_api failed")
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
 }
 
 #[unsafe(no_mangle)]
@@ -59,7 +68,7 @@ pub unsafe extern "C" fn cudaGetDeviceCount(count: *mut c_int) -> runtime::cudaE
         ApiFuncName::FuncCudagetdevicecount as u64,
         vec![unsafe { Argument::from_mut_ptr(count, ArgumentFlag::ARG_OUT) }],
     );
-    invoke_api::<runtime::cudaError_t>(req).expect("call invoke_api failed")
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
 }
 
 #[unsafe(no_mangle)]
@@ -75,7 +84,7 @@ pub unsafe extern "C" fn cudaGetDeviceProperties_v2(
             Argument::from_mut(&mut device, ArgumentFlag::ARG_IN),
         ],
     );
-    invoke_api::<runtime::cudaError_t>(req).expect("call invoke_api failed")
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
 }
 
 #[unsafe(no_mangle)]
@@ -93,7 +102,7 @@ pub unsafe extern "C" fn cudaDeviceGetAttribute(
             Argument::from_ref(&device, ArgumentFlag::ARG_IN),
         ],
     );
-    invoke_api::<runtime::cudaError_t>(req).expect("call invoke_api failed")
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
 }
 
 #[unsafe(no_mangle)]
@@ -103,7 +112,7 @@ pub unsafe extern "C" fn cudaSetDevice(device: c_int) -> runtime::cudaError_t {
         ApiFuncName::FuncCudasetdevice as u64,
         vec![Argument::from_ref(&device, ArgumentFlag::ARG_IN)],
     );
-    invoke_api::<runtime::cudaError_t>(req).expect("call invoke_api failed")
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
 }
 
 #[unsafe(no_mangle)]
@@ -115,7 +124,7 @@ pub unsafe extern "C" fn cudaThreadExchangeStreamCaptureMode(
         ApiFuncName::FuncCudathreadexchangestreamcapturemode as u64,
         vec![unsafe { Argument::from_mut_ptr(mode, ArgumentFlag::ARG_OUT) }],
     );
-    invoke_api::<runtime::cudaError_t>(req).expect("call invoke_api failed")
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
 }
 
 #[unsafe(no_mangle)]
@@ -135,7 +144,175 @@ pub unsafe extern "C" fn cudaMemset(
             Argument::from_ref(&count, ArgumentFlag::ARG_IN),
         ],
     );
-    invoke_api::<runtime::cudaError_t>(req).expect("call invoke_api failed")
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cudaMemsetAsync(
+    dev_ptr: *mut c_void,
+    value: c_int,
+    count: usize,
+    stream: runtime::cudaStream_t,
+) -> runtime::cudaError_t {
+    debug!("[Hooked] api_name: cudaMemsetAsync");
+    let req = Request::with_args(
+        ApiFuncName::FuncCudamemsetasync as u64,
+        vec![
+            unsafe {
+                Argument::from_mut_ptr(dev_ptr, ArgumentFlag::ARG_IN | ArgumentFlag::ARG_VIRT)
+            },
+            Argument::from_value(value, ArgumentFlag::ARG_IN),
+            Argument::from_value(count, ArgumentFlag::ARG_IN),
+            Argument::from_value(stream, ArgumentFlag::ARG_IN),
+        ],
+    );
+    // Deferred until the next synchronous call, errors are reported by `cudaGetLastError`
+    match post_api(req) {
+        Ok(()) => runtime::cudaError_cudaSuccess,
+        Err(e) => e.cuda_error(),
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cudaMemcpy(
+    dst: *mut c_void,
+    src: *const c_void,
+    count: usize,
+    kind: runtime::cudaMemcpyKind,
+) -> runtime::cudaError_t {
+    debug!("[Hooked] api_name: cudaMemcpy");
+    if count == 0 {
+        return runtime::cudaError_cudaSuccess;
+    }
+    if dst.is_null() || src.is_null() {
+        return runtime::cudaError_cudaErrorInvalidValue;
+    }
+    let kind = match kind {
+        runtime::cudaMemcpyKind_cudaMemcpyDefault => match unsafe { memcpy_kind(dst, src) } {
+            Ok(kind) => kind,
+            Err(e) => return e,
+        },
+        kind => kind,
+    };
+
+    let method_id = ApiFuncName::FuncCudamemcpy as u64;
+    let device = ArgumentFlag::ARG_IN | ArgumentFlag::ARG_VIRT;
+    // Host buffers are staged in the arena when it has room, and inlined otherwise
+    let arena = arena();
+
+    match kind {
+        runtime::cudaMemcpyKind_cudaMemcpyHostToDevice => {
+            let mut staging = arena.as_deref().and_then(|arena| arena.alloc(count));
+            let src = unsafe { std::slice::from_raw_parts(src.cast::<u8>(), count) };
+            let src_arg = match staging.as_mut() {
+                Some(buf) => {
+                    buf.copy_from_slice(src);
+                    Argument::from_shared_slice(buf.offset(), buf, ArgumentFlag::ARG_IN)
+                }
+                None => Argument::from_slice(src, ArgumentFlag::ARG_IN),
+            };
+            let req = Request::with_args(
+                method_id,
+                vec![
+                    unsafe { Argument::from_mut_ptr(dst, device) },
+                    src_arg,
+                    Argument::from_value(count, ArgumentFlag::ARG_IN),
+                    Argument::from_value(kind, ArgumentFlag::ARG_IN),
+                ],
+            );
+            invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
+        }
+        runtime::cudaMemcpyKind_cudaMemcpyDeviceToHost => {
+            let mut staging = arena.as_deref().and_then(|arena| arena.alloc(count));
+            let dst = unsafe { std::slice::from_raw_parts_mut(dst.cast::<u8>(), count) };
+            let dst_arg = match staging.as_mut() {
+                Some(buf) => Argument::from_shared_slice(buf.offset(), buf, ArgumentFlag::ARG_OUT),
+                None => Argument::from_mut_slice(dst, ArgumentFlag::ARG_OUT),
+            };
+            let req = Request::with_args(
+                method_id,
+                vec![
+                    dst_arg,
+                    unsafe { Argument::from_ptr(src, device) },
+                    Argument::from_value(count, ArgumentFlag::ARG_IN),
+                    Argument::from_value(kind, ArgumentFlag::ARG_IN),
+                ],
+            );
+            let res = match invoke_api::<runtime::cudaError_t>(req) {
+                Ok(res) => res,
+                Err(e) => return e.cuda_error(),
+            };
+            if let Some(buf) = staging {
+                dst.copy_from_slice(&buf);
+            }
+            res
+        }
+        // Pointers the server resolves the direction of itself
+        runtime::cudaMemcpyKind_cudaMemcpyDeviceToDevice
+        | runtime::cudaMemcpyKind_cudaMemcpyDefault => {
+            let req = Request::with_args(
+                method_id,
+                vec![
+                    unsafe { Argument::from_mut_ptr(dst, device) },
+                    unsafe { Argument::from_ptr(src, device) },
+                    Argument::from_value(count, ArgumentFlag::ARG_IN),
+                    Argument::from_value(kind, ArgumentFlag::ARG_IN),
+                ],
+            );
+            invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
+        }
+        runtime::cudaMemcpyKind_cudaMemcpyHostToHost => {
+            unsafe { std::ptr::copy(src.cast::<u8>(), dst.cast::<u8>(), count) };
+            runtime::cudaError_cudaSuccess
+        }
+        _ => runtime::cudaError_cudaErrorInvalidMemcpyDirection,
+    }
+}
+
+/// Resolves the direction of a `cudaMemcpyDefault` copy. Memory of this process must
+/// be staged, so only copies between memory the server knows stay `cudaMemcpyDefault`.
+unsafe fn memcpy_kind(
+    dst: *const c_void,
+    src: *const c_void,
+) -> Result<runtime::cudaMemcpyKind, runtime::cudaError_t> {
+    let is_remote = |ptr: *const c_void| {
+        let mut attributes: runtime::cudaPointerAttributes = unsafe { std::mem::zeroed() };
+        match unsafe { cudaPointerGetAttributes(&mut attributes, ptr) } {
+            runtime::cudaError_cudaSuccess => {
+                Ok(attributes.type_ != runtime::cudaMemoryType_cudaMemoryTypeUnregistered)
+            }
+            e => Err(e),
+        }
+    };
+
+    Ok(match (is_remote(dst)?, is_remote(src)?) {
+        (true, true) => runtime::cudaMemcpyKind_cudaMemcpyDefault,
+        (true, false) => runtime::cudaMemcpyKind_cudaMemcpyHostToDevice,
+        (false, true) => runtime::cudaMemcpyKind_cudaMemcpyDeviceToHost,
+        (false, false) => runtime::cudaMemcpyKind_cudaMemcpyHostToHost,
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cudaGetErrorName(error: runtime::cudaError_t) -> *const c_char {
+    debug!("[Hooked] api_name: cudaGetErrorName");
+    let req = Request::with_args(
+        ApiFuncName::FuncCudageterrorname as u64,
+        vec![Argument::from_value(error, ArgumentFlag::ARG_IN)],
+    );
+    // Like the library does for codes it does not know
+    invoke_api_cstr(req).unwrap_or(c"unrecognized error code".as_ptr())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cudaGetErrorString(error: runtime::cudaError_t) -> *const c_char {
+    debug!("[Hooked] api_name: cudaGetErrorString");
+    let req = Request::with_args(
+        ApiFuncName::FuncCudageterrorstring as u64,
+        vec![Argument::from_value(error, ArgumentFlag::ARG_IN)],
+    );
+    // Like the library does for codes it does not know
+    invoke_api_cstr(req).unwrap_or(c"unrecognized error code".as_ptr())
 }
 
 #[unsafe(no_mangle)]
@@ -151,7 +328,7 @@ pub unsafe extern "C" fn cudaPointerGetAttributes(
             unsafe { Argument::from_ptr(ptr, ArgumentFlag::ARG_IN | ArgumentFlag::ARG_VIRT) },
         ],
     );
-    invoke_api::<runtime::cudaError_t>(req).expect("call invoke_api failed")
+    invoke_api::<runtime::cudaError_t>(req).unwrap_or_else(|e| e.cuda_error())
 }
 
 #[unsafe(no_mangle)] //ok
@@ -159,5 +336,46 @@ pub unsafe extern "C" fn nvmlInit_v2() -> nvml::nvmlReturn_t {
     debug!("[Hooked] api_name: nvmlInit_v2");
     //let mut args = Vec::new();
     let req = Request::with_args(ApiFuncName::FuncNvmlinitV2 as u64, vec![]);
-    invoke_api::<nvml::nvmlReturn_t>(req).expect("call invoke_api failed")
+    invoke_api::<nvml::nvmlReturn_t>(req).unwrap_or_else(|e| e.nvml_return())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nvmlSystemGetDriverVersion(
+    version: *mut c_char,
+    length: c_uint,
+) -> nvml::nvmlReturn_t {
+    debug!("[Hooked] api_name: nvmlSystemGetDriverVersion");
+    if version.is_null() {
+        return nvml::nvmlReturn_enum_NVML_ERROR_INVALID_ARGUMENT;
+    }
+    // The buffer may be uninitialized, only its capacity goes to the server
+    let version =
+        unsafe { Argument::from_out_buffer(version, length as usize, ArgumentFlag::ARG_OUT) };
+    let req = Request::with_args(
+        ApiFuncName::FuncNvmlsystemgetdriverversion as u64,
+        vec![version],
+    );
+    invoke_api::<nvml::nvmlReturn_t>(req).unwrap_or_else(|e| e.nvml_return())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cuDeviceGetName(
+    name: *mut c_char,
+    len: c_int,
+    dev: driver::CUdevice,
+) -> driver::CUresult {
+    debug!("[Hooked] api_name: cuDeviceGetName");
+    let Ok(len) = usize::try_from(len) else {
+        return driver::cudaError_enum_CUDA_ERROR_INVALID_VALUE;
+    };
+    if name.is_null() {
+        return driver::cudaError_enum_CUDA_ERROR_INVALID_VALUE;
+    }
+    // The buffer may be uninitialized, only its capacity goes to the server
+    let name = unsafe { Argument::from_out_buffer(name, len, ArgumentFlag::ARG_OUT) };
+    let req = Request::with_args(
+        ApiFuncName::FuncCudevicegetname as u64,
+        vec![name, Argument::from_value(dev, ArgumentFlag::ARG_IN)],
+    );
+    invoke_api::<driver::CUresult>(req).unwrap_or_else(|e| e.cu_result())
 }
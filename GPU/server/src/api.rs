@@ -17,7 +17,9 @@ use cudax::driver; // cuda
 use cudax::nccl; // nccl
 use cudax::nvml;
 use cudax::runtime;
-use std::os::raw::{c_int, c_uint, c_void};
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+use std::ptr;
 use tracing::debug;
 use xgpu_common::ipc::message::{Argument, ArgumentFlag};
 
@@ -33,18 +35,22 @@ impl ApiHandler for CudaDeviceSynchronizeHandler {
 pub struct CudaDeviceGetStreamPriorityRangeHandler;
 impl ApiHandler for CudaDeviceGetStreamPriorityRangeHandler {
     fn handle_api(&self, args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
+        // Either output may be NULL
         let least = unsafe {
-            args[0].downcast_mut::<c_int>().map_err(|_| {
+            args[0].downcast_opt_mut::<c_int>().map_err(|_| {
                 ServerErr::InvalidType("InvalidType, <least> expected: c_int".into())
             })?
         };
         let greatest = unsafe {
-            args[1].downcast_mut::<c_int>().map_err(|_| {
+            args[1].downcast_opt_mut::<c_int>().map_err(|_| {
                 ServerErr::InvalidType("InvalidType, <greatest> expected: c_int".into())
             })?
         };
         let res = unsafe {
-            runtime::cudaDeviceGetStreamPriorityRange(least as *mut c_int, greatest as *mut c_int)
+            runtime::cudaDeviceGetStreamPriorityRange(
+                least.map_or(ptr::null_mut(), ptr::from_mut),
+                greatest.map_or(ptr::null_mut(), ptr::from_mut),
+            )
         };
         let ret_value = Argument::from_value(res, ArgumentFlag::ARG_OUT);
         Ok(ret_value)
@@ -238,6 +244,82 @@ impl ApiHandler for CudaMemsetHandler {
     }
 }
 
+pub struct CudaMemsetAsyncHandler;
+impl ApiHandler for CudaMemsetAsyncHandler {
+    fn handle_api(&self, args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
+        let dev_ptr = unsafe {
+            args[0].downcast_mut::<c_void>().map_err(|_| {
+                ServerErr::InvalidType("InvalidType, <dev_ptr> expected: c_void".into())
+            })?
+        };
+        let value = args[1]
+            .downcast_ref::<c_int>()
+            .map_err(|_| ServerErr::InvalidType("InvalidType, <value> expected: c_int".into()))?;
+        let count = args[2]
+            .downcast_ref::<usize>()
+            .map_err(|_| ServerErr::InvalidType("InvalidType, <count> expected: usize".into()))?;
+        let stream = args[3]
+            .downcast_ref::<runtime::cudaStream_t>()
+            .map_err(|_| {
+                ServerErr::InvalidType(
+                    "InvalidType, <stream> expected: runtime::cudaStream_t".into(),
+                )
+            })?;
+
+        let res =
+            unsafe { runtime::cudaMemsetAsync(dev_ptr as *mut c_void, *value, *count, *stream) };
+
+        debug!("----------cudaMemsetAsync, res: {}", res);
+        let ret_value = Argument::from_value(res, ArgumentFlag::ARG_OUT);
+        Ok(ret_value)
+    }
+}
+
+pub struct CudaMemcpyHandler;
+impl ApiHandler for CudaMemcpyHandler {
+    fn handle_api(&self, args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
+        let count = args[2]
+            .downcast::<usize>()
+            .map_err(|_| ServerErr::InvalidType("InvalidType, <count> expected: usize".into()))?;
+        let kind = args[3].downcast::<runtime::cudaMemcpyKind>().map_err(|_| {
+            ServerErr::InvalidType("InvalidType, <kind> expected: runtime::cudaMemcpyKind".into())
+        })?;
+
+        // Host buffers are u8 slices, inlined or resolved into the arena
+        let dst = match kind {
+            runtime::cudaMemcpyKind_cudaMemcpyDeviceToHost => unsafe {
+                args[0]
+                    .downcast_mut_slice::<u8>()
+                    .map_err(|_| {
+                        ServerErr::InvalidType("InvalidType, <dst> expected: [u8]".into())
+                    })?
+                    .as_mut_ptr() as *mut c_void
+            },
+            _ => unsafe {
+                args[0].downcast_mut::<c_void>().map_err(|_| {
+                    ServerErr::InvalidType("InvalidType, <dst> expected: c_void".into())
+                })? as *mut c_void
+            },
+        };
+        let src = match kind {
+            runtime::cudaMemcpyKind_cudaMemcpyHostToDevice => args[1]
+                .downcast_slice::<u8>()
+                .map_err(|_| ServerErr::InvalidType("InvalidType, <src> expected: [u8]".into()))?
+                .as_ptr()
+                as *const c_void,
+            _ => args[1]
+                .downcast_ref::<c_void>()
+                .map_err(|_| ServerErr::InvalidType("InvalidType, <src> expected: c_void".into()))?
+                as *const c_void,
+        };
+
+        let res = unsafe { runtime::cudaMemcpy(dst, src, count, kind) };
+        debug!("----------cudaMemcpy, res: {}", res);
+        let ret_value = Argument::from_value(res, ArgumentFlag::ARG_OUT);
+        Ok(ret_value)
+    }
+}
+
 pub struct CudaPointerGetAttributesHandler;
 impl ApiHandler for CudaPointerGetAttributesHandler {
     fn handle_api(&self, args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
@@ -265,6 +347,43 @@ impl ApiHandler for CudaPointerGetAttributesHandler {
     }
 }
 
+pub struct CudaGetErrorNameHandler;
+impl ApiHandler for CudaGetErrorNameHandler {
+    fn handle_api(&self, args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
+        let error = args[0].downcast::<runtime::cudaError_t>().map_err(|_| {
+            ServerErr::InvalidType("InvalidType, <error> expected: runtime::cudaError_t".into())
+        })?;
+        // Names are static strings of the library
+        let name = unsafe { CStr::from_ptr(runtime::cudaGetErrorName(error)) };
+        let ret_value = Argument::from_cstr(name, ArgumentFlag::ARG_OUT);
+        Ok(ret_value)
+    }
+}
+
+pub struct CudaGetErrorStringHandler;
+impl ApiHandler for CudaGetErrorStringHandler {
+    fn handle_api(&self, args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
+        let error = args[0].downcast::<runtime::cudaError_t>().map_err(|_| {
+            ServerErr::InvalidType("InvalidType, <error> expected: runtime::cudaError_t".into())
+        })?;
+        // Descriptions are static strings of the library
+        let string = unsafe { CStr::from_ptr(runtime::cudaGetErrorString(error)) };
+        let ret_value = Argument::from_cstr(string, ArgumentFlag::ARG_OUT);
+        Ok(ret_value)
+    }
+}
+
+/// Reports the part of a string buffer up to its nul as used, or all of it if
+/// the callee left it unterminated.
+fn set_used_cstr(arg: &mut Argument<'_>, buf: &[c_char]) -> Result<(), ServerErr> {
+    let used = buf
+        .iter()
+        .position(|&c| c == 0)
+        .map_or(buf.len(), |nul| nul + 1);
+    arg.set_used(used)
+        .map_err(|e| ServerErr::InvalidType(format!("InvalidType, out buffer: {}", e)))
+}
+
 pub struct CuDeviceGetHandler;
 impl ApiHandler for CuDeviceGetHandler {
     fn handle_api(&self, args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
@@ -283,6 +402,25 @@ impl ApiHandler for CuDeviceGetHandler {
     }
 }
 
+pub struct CuDeviceGetNameHandler;
+impl ApiHandler for CuDeviceGetNameHandler {
+    fn handle_api(&self, args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
+        let name = unsafe {
+            args[0].downcast_out_buffer::<c_char>().map_err(|_| {
+                ServerErr::InvalidType("InvalidType, <name> expected: [c_char]".into())
+            })?
+        };
+        let dev = args[1].downcast::<driver::CUdevice>().map_err(|_| {
+            ServerErr::InvalidType("InvalidType, <dev> expected: driver::CUdevice".into())
+        })?;
+        let len = c_int::try_from(name.len()).unwrap_or(c_int::MAX);
+        let res = unsafe { driver::cuDeviceGetName(name.as_mut_ptr(), len, dev) };
+        set_used_cstr(&mut args[0], name)?;
+        let ret_value = Argument::from_value(res, ArgumentFlag::ARG_OUT);
+        Ok(ret_value)
+    }
+}
+
 pub struct NvmlInitV2Handler;
 impl ApiHandler for NvmlInitV2Handler {
     fn handle_api(&self, _args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
@@ -293,6 +431,22 @@ impl ApiHandler for NvmlInitV2Handler {
     }
 }
 
+pub struct NvmlSystemGetDriverVersionHandler;
+impl ApiHandler for NvmlSystemGetDriverVersionHandler {
+    fn handle_api(&self, args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
+        let version = unsafe {
+            args[0].downcast_out_buffer::<c_char>().map_err(|_| {
+                ServerErr::InvalidType("InvalidType, <version> expected: [c_char]".into())
+            })?
+        };
+        let length = c_uint::try_from(version.len()).unwrap_or(c_uint::MAX);
+        let res = unsafe { nvml::nvmlSystemGetDriverVersion(version.as_mut_ptr(), length) };
+        set_used_cstr(&mut args[0], version)?;
+        let ret_value = Argument::from_value(res, ArgumentFlag::ARG_OUT);
+        Ok(ret_value)
+    }
+}
+
 pub struct NcclCommDestroyHandler; // bad: type mismatch
 impl ApiHandler for NcclCommDestroyHandler {
     fn handle_api(&self, args: &mut [Argument<'_>]) -> Result<Argument<'static>, ServerErr> {
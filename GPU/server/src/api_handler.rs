@@ -16,22 +16,34 @@ use crate::api::*;
 use indexmap::{IndexMap, indexmap};
 use lazy_static::lazy_static;
 use std::fmt;
-use xgpu_common::ipc::message::Argument;
+use std::panic::{self, AssertUnwindSafe};
+use xgpu_common::ipc::message::{Argument, ResponseStatus};
 use xgpu_common::utils::api_name::ApiFuncName;
 
 #[derive(Debug)]
 pub enum ServerErr {
-    ApiRunError(String, i32),
     InvalidType(String),
+    UnknownMethod(u64),
+    HandlerPanic(String),
+}
+
+impl ServerErr {
+    /// Returns the status reported to the client in place of a return value.
+    pub fn status(&self) -> ResponseStatus {
+        match self {
+            ServerErr::InvalidType(_) => ResponseStatus::TypeMismatch,
+            ServerErr::UnknownMethod(_) => ResponseStatus::UnknownMethod,
+            ServerErr::HandlerPanic(_) => ResponseStatus::HandlerPanic,
+        }
+    }
 }
 
 impl fmt::Display for ServerErr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ServerErr::ApiRunError(msg, err_code) => {
-                write!(f, "API run failed, api: {}, error code: {}", msg, err_code)
-            }
             ServerErr::InvalidType(msg) => write!(f, "Invalid type: {}", msg),
+            ServerErr::UnknownMethod(method_id) => write!(f, "Unknown method: {}", method_id),
+            ServerErr::HandlerPanic(msg) => write!(f, "Handler panicked: {}", msg),
         }
     }
 }
@@ -55,13 +67,19 @@ lazy_static! {
         (ApiFuncName::FuncCudastreamcreatewithpriority as u64) => Box::new(CudaStreamCreateWithPriorityHandler) as Box<dyn ApiHandler>, // bad
         (ApiFuncName::FuncCudathreadexchangestreamcapturemode as u64) => Box::new(CudaThreadExchangeStreamCaptureModeHandler) as Box<dyn ApiHandler>, //ok
         (ApiFuncName::FuncCudamemset as u64) => Box::new(CudaMemsetHandler) as Box<dyn ApiHandler>, //
+        (ApiFuncName::FuncCudamemsetasync as u64) => Box::new(CudaMemsetAsyncHandler) as Box<dyn ApiHandler>,
+        (ApiFuncName::FuncCudamemcpy as u64) => Box::new(CudaMemcpyHandler) as Box<dyn ApiHandler>,
         (ApiFuncName::FuncCudapointergetattributes as u64) => Box::new(CudaPointerGetAttributesHandler) as Box<dyn ApiHandler>, //
+        (ApiFuncName::FuncCudageterrorname as u64) => Box::new(CudaGetErrorNameHandler) as Box<dyn ApiHandler>,
+        (ApiFuncName::FuncCudageterrorstring as u64) => Box::new(CudaGetErrorStringHandler) as Box<dyn ApiHandler>,
 
 
 
         (ApiFuncName::FuncCudeviceget as u64) => Box::new(CuDeviceGetHandler) as Box<dyn ApiHandler>, //
+        (ApiFuncName::FuncCudevicegetname as u64) => Box::new(CuDeviceGetNameHandler) as Box<dyn ApiHandler>,
         //
         (ApiFuncName::FuncNvmlinitV2 as u64) => Box::new(NvmlInitV2Handler) as Box<dyn ApiHandler>, //ok
+        (ApiFuncName::FuncNvmlsystemgetdriverversion as u64) => Box::new(NvmlSystemGetDriverVersionHandler) as Box<dyn ApiHandler>,
         (ApiFuncName::FuncNcclcommdestroy as u64) => Box::new(NcclCommDestroyHandler) as Box<dyn ApiHandler>, // bad
     };
 }
@@ -70,11 +88,17 @@ pub unsafe fn call_handler(
     key: u64,
     args: &mut [Argument<'_>],
 ) -> Result<Argument<'static>, ServerErr> {
-    match FUNC_HANDLER_MAP.get(&key) {
-        Some(handler) => handler.handle_api(args),
-        None => Err(ServerErr::ApiRunError(
-            "call_handler.get failed".to_string(),
-            -1,
-        )),
-    }
+    let handler = FUNC_HANDLER_MAP
+        .get(&key)
+        .ok_or(ServerErr::UnknownMethod(key))?;
+
+    // A panicking handler fails its request, not the whole worker
+    panic::catch_unwind(AssertUnwindSafe(|| handler.handle_api(args))).unwrap_or_else(|payload| {
+        let msg = payload
+            .downcast_ref::<&str>()
+            .map(|msg| msg.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Err(ServerErr::HandlerPanic(msg))
+    })
 }
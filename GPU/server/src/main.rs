@@ -12,19 +12,30 @@
  * See the Mulan PSL v2 for more details.
  */
 
-use std::env;
-use tracing::debug;
+use std::{env, thread};
+use tracing::{debug, error, info};
 
 use xgpu_common::ipc::{
-    framer::LengthPrefixFramer,
-    message::{Request, Response},
-    peer::Client,
-    transport::shmem::ShmemTransportBuilder,
+    framer::{CompressingFramer, LengthPrefixFramer},
+    message::{Request, RequestBatch, Response, ResponseBatch},
+    peer::{Listener, Server},
+    transport::{
+        BulkRegion,
+        any::{AnyTransport, TRANSPORT_URI_ENV, TransportScheme, TransportUri},
+    },
 };
 
 mod api;
 mod api_handler;
-use api_handler::call_handler;
+use api_handler::{ServerErr, call_handler};
+mod session;
+use session::ClientSession;
+
+const DEFAULT_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+/// Shared memory arena of each connection, for bulk data such as memcpy payloads.
+const DEFAULT_ARENA_SIZE: usize = 64 * 1024 * 1024;
+
+type ServerFramer = CompressingFramer<LengthPrefixFramer>;
 
 fn main() {
     tracing_subscriber::fmt()
@@ -35,47 +46,168 @@ fn main() {
 
     let args: Vec<String> = env::args().collect();
 
-    if args.len() < 2 {
-        eprintln!("Usage: {} <shmem addr>", args[0]);
-        std::process::exit(1);
+    let uri = match parse_transport_uri(&args[1..]) {
+        Some(uri) => uri,
+        None => {
+            eprintln!(
+                "Usage: {} [--transport <uri> | <shmem addr>]\n\n\
+                 The transport uri may also be set by the {} environment variable,\n\
+                 e.g. 'shm:///1234?buf=4M', 'unix:///run/xgpu.sock' or 'tcp://host:port'.",
+                args[0], TRANSPORT_URI_ENV
+            );
+            std::process::exit(1);
+        }
+    };
+
+    let uri = match uri.parse::<TransportUri>() {
+        Ok(uri) => uri
+            .default_buffer_size(DEFAULT_BUFFER_SIZE)
+            .default_arena_size(DEFAULT_ARENA_SIZE),
+        Err(e) => {
+            eprintln!("Invalid transport uri '{}': {}", uri, e);
+            std::process::exit(1);
+        }
+    };
+
+    serve(uri);
+}
+
+/// Picks the transport uri from `--transport <uri>`, a bare shmem name, or the environment.
+fn parse_transport_uri(args: &[String]) -> Option<String> {
+    match args {
+        [flag, uri] if flag == "--transport" => Some(uri.clone()),
+        [arg] => match arg.strip_prefix("--transport=") {
+            Some(uri) => Some(uri.to_string()),
+            None if !arg.starts_with('-') => Some(format!("shm:///{}", arg)),
+            None => None,
+        },
+        [] => env::var(TRANSPORT_URI_ENV).ok(),
+        _ => None,
     }
+}
+
+/// Accepts clients on the well-known address, serving each on its own worker thread.
+fn serve(uri: TransportUri) {
+    let transport = uri.transport();
+    // Frames are bounded by the transport buffer the uri asks for
+    let frame_limit = uri.buffer_size().unwrap_or(DEFAULT_BUFFER_SIZE);
+    // Compression only pays off over the network
+    let framer = CompressingFramer::new(LengthPrefixFramer::new(frame_limit))
+        .offer(uri.scheme() == TransportScheme::Tcp);
+
+    debug!("transport uri: {}", uri);
+    let listener = match Listener::bind(framer, &transport, uri.address()) {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("[server] Failed to listen on '{}': {}", uri, e);
+            std::process::exit(1);
+        }
+    };
+    info!("[server] Listening on '{}'", uri);
 
-    serve(args[1].clone());
+    for client_id in 0u64.. {
+        let client = match listener.accept() {
+            Ok(client) => client,
+            Err(e) => {
+                error!("[server] Failed to accept client: {}", e);
+                continue;
+            }
+        };
+        debug!("{:#?}", client);
+
+        let worker = thread::Builder::new()
+            .name(format!("xgpu-worker-{}", client_id))
+            .spawn(move || serve_client(client_id, client));
+        if let Err(e) = worker {
+            error!(
+                "[server] Failed to start worker for client {}: {}",
+                client_id, e
+            );
+        }
+    }
 }
 
-fn serve(addr: String) {
-    let transport = ShmemTransportBuilder::new()
-        .buffer_size(4 * 1024 * 1024)
-        .build();
-    let framer = LengthPrefixFramer::new(4 * 1024 * 1024);
+fn serve_client(client_id: u64, mut client: Server<ServerFramer, AnyTransport>) {
+    info!("[server] Client {} connected", client_id);
+    let mut session = ClientSession::new(client_id);
+    let arena = client.arena();
 
-    let mut client = Client::connect(framer, &transport, &addr).unwrap();
-    debug!("{:#?}", client);
+    loop {
+        let mut batch = match client.receive_message::<RequestBatch>() {
+            Ok(Some(batch)) => batch,
+            Ok(None) => continue,
+            Err(e) => {
+                info!("[server] Client {} disconnected: {}", client_id, e);
+                break;
+            }
+        };
 
-    while let Ok(Some(mut request)) = client.receive_message::<Request>() {
-        /* debug!(
-            "[Server] Received request: request_id={}, method_id={}, argc={}",
-            request.request_id(),
-            request.method_id(),
-            request.argc()
-        ); */
+        // Requests of a batch run in order, their responses go back in one batch
+        let mut responses = ResponseBatch::new();
+        for request in batch.requests_mut() {
+            /* debug!(
+                "[Server] Received request: request_id={}, method_id={}, argc={}",
+                request.request_id(),
+                request.method_id(),
+                request.argc()
+            ); */
 
-        //debug!("{:#?}", request);
+            //debug!("{:#?}", request);
 
-        let method_id = request.method_id();
-        let ret = unsafe { call_handler(method_id, request.args_mut()) }
-            .expect("[server] call_handler faied");
+            let method_id = request.method_id();
+            let result = resolve_shared(arena.as_deref(), request)
+                .and_then(|()| unsafe { call_handler(method_id, request.args_mut()) });
 
-        let response = Response::with_request(&request, ret);
+            if request.is_no_reply() {
+                session.latch(method_id, result);
+                continue;
+            }
 
-        client
-            .send_message(&response)
-            .expect("[server] Failed to send response");
+            let response = match result {
+                Ok(ret) => Response::with_request(request, session.resolve(method_id, ret)),
+                Err(e) => {
+                    error!(
+                        "[server] Client {}: method {} failed: {}",
+                        client_id, method_id, e
+                    );
+                    Response::with_status(request, e.status())
+                }
+            };
+            responses.push(response);
+        }
+        if responses.is_empty() {
+            continue;
+        }
+
+        if let Err(e) = client.send_message(&responses) {
+            error!(
+                "[server] Failed to send response to client {}: {}",
+                client_id, e
+            );
+            break;
+        }
 
         /* debug!(
-            "[Server] Sending response: request_id={}, method_id={}",
-            response.request_id(),
-            response.method_id(),
+            "[Server] Sending {} responses to client {}",
+            responses.len(),
+            client_id,
         ); */
     }
 }
+
+/// Points the shared arguments of a request into the arena of the connection.
+///
+/// An argument the arena cannot back fails the whole request, the handler must
+/// never see it.
+fn resolve_shared(
+    arena: Option<&dyn BulkRegion>,
+    request: &mut Request<'_>,
+) -> Result<(), ServerErr> {
+    match arena {
+        // SAFETY: The arena stays mapped while the connection holds it, for longer
+        // than the request lives.
+        Some(arena) => unsafe { request.resolve_shared(arena.as_mut_ptr(), arena.len()) }
+            .map_err(|e| ServerErr::InvalidType(format!("invalid shared argument: {}", e))),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: Mulan PSL v2
+/*
+ * Copyright (c) 2025 Huawei Technologies Co., Ltd.
+ * This software is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *         http://license.coscl.org.cn/MulanPSL2
+ *
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ */
+
+use cudax::runtime;
+use tracing::warn;
+use xgpu_common::{
+    ipc::message::{Argument, ArgumentFlag},
+    utils::api_name::ApiFuncName,
+};
+
+use crate::api_handler::ServerErr;
+
+/// State a worker keeps for its client across requests.
+#[derive(Debug)]
+pub struct ClientSession {
+    client_id: u64,
+    /// First error of a no-reply request, until reported by `cudaGetLastError`.
+    sticky_error: Option<runtime::cudaError_t>,
+}
+
+impl ClientSession {
+    pub fn new(client_id: u64) -> Self {
+        Self {
+            client_id,
+            sticky_error: None,
+        }
+    }
+
+    /// Records the outcome of a no-reply request, whose result nobody receives.
+    pub fn latch(&mut self, method_id: u64, result: Result<Argument<'static>, ServerErr>) {
+        let error = match result {
+            Ok(ret) => match ret.downcast::<runtime::cudaError_t>() {
+                Ok(runtime::cudaError_cudaSuccess) | Err(_) => return,
+                Ok(error) => error,
+            },
+            Err(e) => {
+                warn!(
+                    "[server] Client {}: no-reply method {} failed: {}",
+                    self.client_id, method_id, e
+                );
+                runtime::cudaError_cudaErrorUnknown
+            }
+        };
+
+        if self.sticky_error.is_none() {
+            warn!(
+                "[server] Client {}: latched error {} of no-reply method {}",
+                self.client_id, error, method_id
+            );
+            self.sticky_error = Some(error);
+        }
+    }
+
+    /// Replaces the result of `cudaGetLastError` and `cudaPeekAtLastError` with
+    /// the latched error, if any. Only the former clears it.
+    pub fn resolve(&mut self, method_id: u64, ret: Argument<'static>) -> Argument<'static> {
+        let sticky_error = if method_id == ApiFuncName::FuncCudagetlasterror as u64 {
+            self.sticky_error.take()
+        } else if method_id == ApiFuncName::FuncCudapeekatlasterror as u64 {
+            self.sticky_error
+        } else {
+            None
+        };
+
+        match sticky_error {
+            Some(error) => Argument::from_value(error, ArgumentFlag::ARG_OUT),
+            None => ret,
+        }
+    }
+}